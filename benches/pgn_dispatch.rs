@@ -0,0 +1,89 @@
+//! PGN dispatch benchmark: compile-time perfect hash vs binary search.
+//!
+//! Run with `cargo bench --bench pgn_dispatch`. Uses only `std` so the crate
+//! stays dependency-free.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use voltage_j1939::database::SPN_DEFINITIONS;
+use voltage_j1939::{decode_frame_iter, get_spns_for_pgn, SpnDef};
+
+const ITERATIONS: u32 = 2_000_000;
+
+/// Representative bus mix: high-rate EEC1/EEC2 dominate, plus slower PGNs and unknown traffic.
+const PGN_MIX: [u32; 16] = [
+    61444, 61443, 61444, 61443, 61444, 65265, 65266, 61444, 65262, 65263, 65270, 65271, 0xFF10,
+    0xEA00, 61444, 65253,
+];
+
+fn time<F: FnMut() -> usize>(name: &str, mut f: F) -> Duration {
+    let start = Instant::now();
+    let mut sink = 0usize;
+    for _ in 0..ITERATIONS {
+        sink = sink.wrapping_add(f());
+    }
+    let elapsed = start.elapsed();
+    black_box(sink);
+    println!(
+        "{:<28} {:>8.2} ns/lookup",
+        name,
+        elapsed.as_nanos() as f64 / (ITERATIONS as f64 * PGN_MIX.len() as f64)
+    );
+    elapsed
+}
+
+/// The previous dispatch: a sorted (pgn, start, count) index over the SPNs
+/// grouped by PGN, searched with a binary search.
+struct BinarySearchLookup {
+    index: Vec<(u32, u16, u16)>,
+    spns: Vec<&'static SpnDef>,
+}
+
+impl BinarySearchLookup {
+    fn build() -> Self {
+        let mut spns: Vec<&'static SpnDef> = SPN_DEFINITIONS.iter().collect();
+        spns.sort_by_key(|s| s.pgn);
+        let mut index: Vec<(u32, u16, u16)> = Vec::new();
+        for (i, spn) in spns.iter().enumerate() {
+            match index.last_mut() {
+                Some(last) if last.0 == spn.pgn => last.2 += 1,
+                _ => index.push((spn.pgn, i as u16, 1)),
+            }
+        }
+        Self { index, spns }
+    }
+
+    fn get(&self, pgn: u32) -> Option<&[&'static SpnDef]> {
+        let idx = self.index.binary_search_by_key(&pgn, |(p, _, _)| *p).ok()?;
+        let (_, start, count) = self.index[idx];
+        Some(&self.spns[start as usize..(start + count) as usize])
+    }
+}
+
+fn main() {
+    let baseline = BinarySearchLookup::build();
+    time("binary search", || {
+        PGN_MIX
+            .iter()
+            .filter_map(|&pgn| black_box(&baseline).get(black_box(pgn)))
+            .map(<[_]>::len)
+            .sum()
+    });
+
+    time("perfect hash", || {
+        PGN_MIX
+            .iter()
+            .filter_map(|&pgn| get_spns_for_pgn(black_box(pgn)))
+            .map(<[_]>::len)
+            .sum()
+    });
+
+    let data = [0x05, 0x10, 0x20, 0x20, 0x4E, 0x00, 0x30, 0x40];
+    time("decode_frame_iter (full)", || {
+        PGN_MIX
+            .iter()
+            .map(|&pgn| decode_frame_iter(black_box(0x1800_0000 | (pgn << 8)), &data).count())
+            .sum()
+    });
+}
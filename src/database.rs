@@ -6,142 +6,376 @@
 //! This database covers the most commonly used PGNs for diesel generators and
 //! industrial engines. Data is automatically decoded when matching PGNs are received.
 
-use std::sync::OnceLock;
-
-use crate::types::{SpnDataType, SpnDef};
+use crate::frame::{can_id_for_pgn, DEFAULT_PRIORITY, GLOBAL_ADDRESS};
+use crate::types::{DecodedSpn, PgnInfo, SpnDataType, SpnDef};
 
 // ============================================================================
-// Lazy-initialized lookup tables for O(1) access
+// Compile-time lookup tables - no lazy init, no allocation, no scanning
 // ============================================================================
 
-/// Cached PGN -> SPNs mapping (lazy initialized)
-static PGN_LOOKUP: OnceLock<PgnLookup> = OnceLock::new();
+/// Number of SPN definitions in the database.
+const SPN_COUNT: usize = SPN_TABLE.len();
+
+/// Number of unique PGNs in the database (computed at compile time).
+const PGN_COUNT: usize = count_pgns(SPN_TABLE);
+
+/// Number of unique SPN numbers in the database (computed at compile time).
+const SPN_NUMBER_COUNT: usize = count_spns(SPN_TABLE);
+
+/// Number of unique SPN names in the database (computed at compile time).
+const NAME_COUNT: usize = count_names(SPN_TABLE);
+
+// Perfect-hash slots are u8 indices (EMPTY_SLOT reserved) with at least 4
+// slots per PGN in at most 2^8 slots, which caps the database at 64 PGNs.
+// Past that, widen the slots to u16 rather than raising the load factor.
+const _: () = assert!(
+    PGN_COUNT * 4 <= 1 << 8 && PGN_COUNT < EMPTY_SLOT as usize,
+    "PGN_COUNT exceeds the u8 perfect-hash slot capacity"
+);
+
+/// Number of slots in the PGN perfect-hash table (power of two, load factor <= 1/4).
+const PGN_HASH_BITS: u32 = hash_bits(PGN_COUNT);
+
+/// PGN -> SPNs mapping, fully evaluated at compile time.
+const PGN_TABLE: PgnLookup<SPN_COUNT, PGN_COUNT, { 1 << PGN_HASH_BITS }> =
+    PgnLookup::build(SPN_TABLE);
+
+/// Single runtime instance of [`PGN_TABLE`] (statics can't be read in const fns).
+static PGN_LOOKUP: PgnLookup<SPN_COUNT, PGN_COUNT, { 1 << PGN_HASH_BITS }> = PGN_TABLE;
 
-/// Cached SPN -> SpnDef mapping (lazy initialized)
-static SPN_LOOKUP: OnceLock<SpnLookup> = OnceLock::new();
+/// SPN -> SpnDef mapping, fully evaluated at compile time.
+static SPN_LOOKUP: SpnLookup<SPN_COUNT> = SpnLookup::build(SPN_TABLE);
 
-/// Compact PGN lookup structure using Box<[T]> for minimal memory footprint.
-/// Box<[T]> saves 8 bytes per field vs Vec (no capacity field needed).
-struct PgnLookup {
-    /// Sorted list of (pgn, start_idx, count) for binary search
-    index: Box<[(u32, u16, u16)]>,
-    /// Flattened array of SpnDef references, grouped by PGN
-    spns: Box<[&'static SpnDef]>,
+/// Data length of a single classic CAN frame.
+pub(crate) const FRAME_LEN: usize = 8;
+
+// Every SPN fits in one 8-byte frame; the decoder's fixed-size array path
+// relies on this to skip per-SPN length checks.
+const _: () = {
+    let mut i = 0;
+    while i < SPN_COUNT {
+        assert!(
+            SPN_TABLE[i].required_len() <= FRAME_LEN,
+            "SPN does not fit in an 8-byte frame"
+        );
+        i += 1;
+    }
+};
+
+/// Marker for an empty perfect-hash slot.
+const EMPTY_SLOT: u8 = u8::MAX;
+
+/// PGN lookup structure with a compile-time perfect hash.
+///
+/// `hash(pgn) = (pgn * multiplier) >> (32 - bits)` maps every PGN in the
+/// database to a distinct slot, so a lookup is one multiply, one shift and a
+/// single comparison against the stored PGN.
+struct PgnLookup<const N: usize, const K: usize, const S: usize> {
+    /// Sorted list of (pgn, start_idx, count)
+    index: [(u32, u16, u16); K],
+    /// Flattened array of SpnDef references, grouped by PGN (definition order kept)
+    spns: [&'static SpnDef; N],
+    /// Unit id of each entry in `spns`, for FFI output
+    unit_ids: [u16; N],
+    /// Perfect-hash slots holding an index into `index` (or `EMPTY_SLOT`)
+    slots: [u8; S],
+    /// Multiplier found at compile time that makes the hash collision-free
+    multiplier: u32,
 }
 
 /// Compact SPN lookup using sorted array + binary search (faster than HashMap for small N).
-/// Box<[T]> saves 8 bytes vs Vec (no capacity field needed).
-struct SpnLookup {
+struct SpnLookup<const N: usize> {
     /// Sorted by SPN number for binary search
-    entries: Box<[(u32, &'static SpnDef)]>,
+    entries: [(u32, &'static SpnDef); N],
 }
 
-impl PgnLookup {
-    fn build() -> Self {
-        // O(n log n): Sort SPNs by PGN first
-        let mut sorted_spns: Vec<&'static SpnDef> = SPN_DEFINITIONS.iter().collect();
-        sorted_spns.sort_unstable_by_key(|s| s.pgn);
-
-        // Count unique PGNs for pre-allocation (O(n) but worth it for no realloc)
-        let pgn_count = sorted_spns
-            .windows(2)
-            .filter(|w| w[0].pgn != w[1].pgn)
-            .count()
-            + 1;
-
-        // O(n): Single pass to build index
-        let mut index: Vec<(u32, u16, u16)> = Vec::with_capacity(pgn_count);
-        let mut current_pgn = u32::MAX;
-        let mut start_idx = 0u16;
-
-        for (i, spn) in sorted_spns.iter().enumerate() {
-            if spn.pgn != current_pgn {
-                // Finalize previous PGN group
-                if current_pgn != u32::MAX {
-                    let count = (i as u16) - start_idx;
-                    index.push((current_pgn, start_idx, count));
-                }
-                current_pgn = spn.pgn;
-                start_idx = i as u16;
+/// Count unique PGNs in a table (const-evaluable, O(n^2) is fine at compile time).
+const fn count_pgns(table: &[SpnDef]) -> usize {
+    let mut count = 0;
+    let mut i = 0;
+    while i < table.len() {
+        let mut j = 0;
+        while j < i && table[j].pgn != table[i].pgn {
+            j += 1;
+        }
+        if j == i {
+            count += 1;
+        }
+        i += 1;
+    }
+    count
+}
+
+/// Count unique SPN numbers in a table (const-evaluable, like [`count_pgns`]).
+const fn count_spns(table: &[SpnDef]) -> usize {
+    let mut count = 0;
+    let mut i = 0;
+    while i < table.len() {
+        let mut j = 0;
+        while j < i && table[j].spn != table[i].spn {
+            j += 1;
+        }
+        if j == i {
+            count += 1;
+        }
+        i += 1;
+    }
+    count
+}
+
+/// Sorted, deduplicated SPN numbers (or PGNs if `pgns`) of a table.
+///
+/// Selection by "smallest key above the previous one", O(K * n) at compile time.
+const fn sorted_keys<const K: usize>(table: &[SpnDef], pgns: bool) -> [u32; K] {
+    let mut keys = [0u32; K];
+    let mut k = 0;
+    while k < K {
+        let mut next = u32::MAX;
+        let mut i = 0;
+        while i < table.len() {
+            let key = if pgns { table[i].pgn } else { table[i].spn };
+            if (k == 0 || key > keys[k - 1]) && key < next {
+                next = key;
+            }
+            i += 1;
+        }
+        keys[k] = next;
+        k += 1;
+    }
+    keys
+}
+
+/// Count unique SPN names in a table (const-evaluable, like [`count_pgns`]).
+const fn count_names(table: &[SpnDef]) -> usize {
+    let mut count = 0;
+    let mut i = 0;
+    while i < table.len() {
+        let mut j = 0;
+        while j < i && !str_eq(table[j].name, table[i].name) {
+            j += 1;
+        }
+        if j == i {
+            count += 1;
+        }
+        i += 1;
+    }
+    count
+}
+
+/// Const string equality (`==` on `str` is not const).
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Smallest power of two (as a bit count) giving at least 4 slots per PGN.
+const fn hash_bits(pgn_count: usize) -> u32 {
+    let mut bits = 4;
+    while (1usize << bits) < pgn_count * 4 {
+        bits += 1;
+    }
+    assert!(bits <= 8, "too many PGNs for u8 perfect-hash slots");
+    bits
+}
+
+/// Multiplicative hash used by the PGN perfect-hash table.
+#[inline(always)]
+const fn pgn_hash(pgn: u32, multiplier: u32, bits: u32) -> usize {
+    (pgn.wrapping_mul(multiplier) >> (32 - bits)) as usize
+}
+
+impl<const N: usize, const K: usize, const S: usize> PgnLookup<N, K, S> {
+    const fn build(table: &'static [SpnDef]) -> Self {
+        // Stable insertion sort by PGN - keeps definition order within a PGN
+        let mut spns = [&table[0]; N];
+        let mut i = 0;
+        while i < N {
+            let spn = &table[i];
+            let mut j = i;
+            while j > 0 && spns[j - 1].pgn > spn.pgn {
+                spns[j] = spns[j - 1];
+                j -= 1;
             }
+            spns[j] = spn;
+            i += 1;
         }
-        // Finalize last PGN group
-        if current_pgn != u32::MAX {
-            let count = (sorted_spns.len() as u16) - start_idx;
-            index.push((current_pgn, start_idx, count));
+
+        let mut unit_ids = [0u16; N];
+        let mut i = 0;
+        while i < N {
+            unit_ids[i] = unit_index(spns[i].unit) as u16;
+            i += 1;
+        }
+
+        // Single pass to build (pgn, start_idx, count) index
+        let mut index = [(0u32, 0u16, 0u16); K];
+        let mut k = 0;
+        let mut i = 0;
+        while i < N {
+            if i == 0 || spns[i].pgn != spns[i - 1].pgn {
+                index[k] = (spns[i].pgn, i as u16, 0);
+                k += 1;
+            }
+            index[k - 1].2 += 1;
+            i += 1;
         }
 
-        Self {
-            index: index.into_boxed_slice(),
-            spns: sorted_spns.into_boxed_slice(),
+        // Search for a multiplier that maps every PGN to a distinct slot
+        let bits = S.trailing_zeros();
+        let mut multiplier = 0x9E37_79B1u32; // Fibonacci hashing constant
+        let mut attempts = 0;
+        loop {
+            let mut slots = [EMPTY_SLOT; S];
+            let mut k = 0;
+            while k < K {
+                let slot = pgn_hash(index[k].0, multiplier, bits);
+                if slots[slot] != EMPTY_SLOT {
+                    break;
+                }
+                slots[slot] = k as u8;
+                k += 1;
+            }
+            if k == K {
+                return Self {
+                    index,
+                    spns,
+                    unit_ids,
+                    slots,
+                    multiplier,
+                };
+            }
+            attempts += 1;
+            assert!(attempts < 100_000, "no perfect hash found for PGN table");
+            multiplier = multiplier.wrapping_add(0x6A09_E668); // keep it odd
         }
     }
 
     /// Hot path for frame decoding - always inlined.
     #[inline(always)]
-    fn get(&self, pgn: u32) -> Option<&[&'static SpnDef]> {
-        match self.index.binary_search_by_key(&pgn, |(p, _, _)| *p) {
-            Ok(idx) => {
-                let (_, start, count) = self.index[idx];
-                Some(&self.spns[start as usize..(start + count) as usize])
-            }
-            Err(_) => None, // PGN not found - cold path
+    const fn get(&'static self, pgn: u32) -> Option<&'static [&'static SpnDef]> {
+        let slot = self.slots[pgn_hash(pgn, self.multiplier, S.trailing_zeros())] as usize;
+        if slot >= K || self.index[slot].0 != pgn {
+            return None; // PGN not found - cold path
         }
+        // Range indexing is not const; split_at is
+        let (_, start, count) = self.index[slot];
+        let (_, tail) = self.spns.as_slice().split_at(start as usize);
+        Some(tail.split_at(count as usize).0)
     }
 
-    #[inline]
-    fn pgn_count(&self) -> usize {
-        self.index.len()
+    /// Unit ids matching the slice returned by [`get`](Self::get).
+    #[inline(always)]
+    fn unit_ids(&'static self, pgn: u32) -> &'static [u16] {
+        let slot = self.slots[pgn_hash(pgn, self.multiplier, S.trailing_zeros())] as usize;
+        if slot >= K || self.index[slot].0 != pgn {
+            return &[];
+        }
+        let (_, start, count) = self.index[slot];
+        &self.unit_ids[start as usize..(start + count) as usize]
     }
 
     #[inline]
-    fn iter_pgns(&self) -> impl Iterator<Item = u32> + '_ {
+    fn iter_pgns(&'static self) -> impl Iterator<Item = u32> {
         self.index.iter().map(|(pgn, _, _)| *pgn)
     }
 }
 
-impl SpnLookup {
-    fn build() -> Self {
-        // Pre-allocate with exact capacity
-        let mut entries: Vec<(u32, &'static SpnDef)> =
-            Vec::with_capacity(SPN_DEFINITIONS.len());
-        entries.extend(SPN_DEFINITIONS.iter().map(|s| (s.spn, s)));
-        entries.sort_unstable_by_key(|(spn, _)| *spn);
-        Self {
-            entries: entries.into_boxed_slice(),
+impl<const N: usize> SpnLookup<N> {
+    const fn build(table: &'static [SpnDef]) -> Self {
+        // Insertion sort by SPN number
+        let mut entries = [(0u32, &table[0]); N];
+        let mut i = 0;
+        while i < N {
+            let spn = &table[i];
+            let mut j = i;
+            while j > 0 && entries[j - 1].0 > spn.spn {
+                entries[j] = entries[j - 1];
+                j -= 1;
+            }
+            entries[j] = (spn.spn, spn);
+            i += 1;
         }
+        Self { entries }
     }
 
     /// SPN lookup - inlined for decode_spn_by_number hot path.
+    /// Returns the first definition if an SPN is defined in several PGNs.
     #[inline(always)]
     fn get(&self, spn: u32) -> Option<&'static SpnDef> {
-        match self.entries.binary_search_by_key(&spn, |(s, _)| *s) {
-            Ok(idx) => Some(self.entries[idx].1),
-            Err(_) => None, // SPN not found - cold path
+        let idx = self.entries.partition_point(|(s, _)| *s < spn);
+        match self.entries.get(idx) {
+            Some(&(s, spn_def)) if s == spn => Some(spn_def),
+            _ => None, // SPN not found - cold path
         }
     }
-}
 
-/// Get PGN lookup table - initialized once, then O(1) access.
-/// Hot path: always inlined to avoid function call overhead.
-#[inline(always)]
-fn pgn_lookup() -> &'static PgnLookup {
-    PGN_LOOKUP.get_or_init(PgnLookup::build)
-}
+    /// All definitions of an SPN, in definition order (the sort is stable).
+    fn get_all(&self, spn: u32) -> &[(u32, &'static SpnDef)] {
+        let start = self.entries.partition_point(|(s, _)| *s < spn);
+        let end = self.entries.partition_point(|(s, _)| *s <= spn);
+        &self.entries[start..end]
+    }
 
-/// Get SPN lookup table - initialized once, then O(1) access.
-/// Hot path: always inlined to avoid function call overhead.
-#[inline(always)]
-fn spn_lookup() -> &'static SpnLookup {
-    SPN_LOOKUP.get_or_init(SpnLookup::build)
+    /// Definition of one instance of an SPN repeated within a PGN.
+    fn get_instance(&self, spn: u32, instance: u8) -> Option<&'static SpnDef> {
+        self.get_all(spn)
+            .iter()
+            .map(|(_, spn_def)| *spn_def)
+            .find(|spn_def| spn_def.instance == Some(instance))
+    }
 }
 
 // ============================================================================
 // SPN Database - Complete definitions for common engine PGNs
 // ============================================================================
 
+/// Multiplexed Proprietary B PGN present only in unit-test builds.
+#[cfg(test)]
+pub(crate) const TEST_MUX_PGN: u32 = 0xFF55;
+
 /// All SPN definitions in the database.
-pub static SPN_DEFINITIONS: &[SpnDef] = &[
+pub static SPN_DEFINITIONS: &[SpnDef] = SPN_TABLE;
+
+/// Every SPN number in the database, sorted and without duplicates.
+///
+/// Built at compile time; allocation-free membership checks with
+/// `binary_search`.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::database::SUPPORTED_SPNS;
+///
+/// assert!(SUPPORTED_SPNS.binary_search(&190).is_ok()); // Engine speed
+/// assert!(SUPPORTED_SPNS.windows(2).all(|w| w[0] < w[1]));
+/// ```
+pub const SUPPORTED_SPNS: &[u32] = &sorted_keys::<SPN_NUMBER_COUNT>(SPN_TABLE, false);
+
+/// Every PGN in the database, sorted and without duplicates.
+///
+/// Same PGNs as [`list_supported_pgns`], as a slice built at compile time.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::database::SUPPORTED_PGNS;
+///
+/// assert!(SUPPORTED_PGNS.binary_search(&61444).is_ok()); // EEC1
+/// ```
+pub const SUPPORTED_PGNS: &[u32] = &sorted_keys::<PGN_COUNT>(SPN_TABLE, true);
+
+/// SPN definitions as a constant, so the lookup tables can be built at compile time.
+const SPN_TABLE: &[SpnDef] = &[
     // ========================================================================
     // EEC1 - Electronic Engine Controller 1 (PGN 61444 / 0xF004)
     // Broadcast rate: 10-100ms (engine dependent)
@@ -157,18 +391,20 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 4154,
-        name: "actual_engine_retarder_percent",
+        name: "actual_engine_percent_torque_fractional",
         pgn: 61444,
-        start_byte: 1,
-        start_bit: 0,
-        bit_length: 8,
-        scale: 1.0,
-        offset: -125.0,
+        start_byte: 0,
+        start_bit: 4,
+        bit_length: 4,
+        scale: 0.125,
+        offset: 0.0,
         unit: "%",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 512,
@@ -181,6 +417,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: -125.0,
         unit: "%",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 513,
@@ -193,6 +430,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: -125.0,
         unit: "%",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 190,
@@ -205,6 +443,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "RPM",
         data_type: SpnDataType::Uint16,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 1483,
@@ -217,6 +456,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 1675,
@@ -229,6 +469,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 2432,
@@ -241,6 +482,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: -125.0,
         unit: "%",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     // ========================================================================
     // EEC2 - Electronic Engine Controller 2 (PGN 61443 / 0xF003)
@@ -257,6 +499,8 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "",
         data_type: SpnDataType::Uint8,
+        reports_all_states: true,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 559,
@@ -269,6 +513,8 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "",
         data_type: SpnDataType::Uint8,
+        reports_all_states: true,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 1437,
@@ -281,6 +527,8 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "",
         data_type: SpnDataType::Uint8,
+        reports_all_states: true,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 2970,
@@ -293,6 +541,8 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "",
         data_type: SpnDataType::Uint8,
+        reports_all_states: true,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 91,
@@ -305,6 +555,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "%",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 92,
@@ -317,6 +568,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "%",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 974,
@@ -329,6 +581,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "%",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 29,
@@ -341,6 +594,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "%",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 2979,
@@ -353,6 +607,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 5021,
@@ -365,6 +620,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     // ========================================================================
     // EEC3 - Electronic Engine Controller 3 (PGN 65247 / 0xFEDF)
@@ -381,6 +637,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: -125.0,
         unit: "%",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 515,
@@ -393,6 +650,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "RPM",
         data_type: SpnDataType::Uint16,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 519,
@@ -405,6 +663,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 2978,
@@ -417,6 +676,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: -125.0,
         unit: "%",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 6595,
@@ -429,6 +689,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "kg/h",
         data_type: SpnDataType::Uint16,
+        ..SpnDef::DEFAULT
     },
     // ========================================================================
     // ET1 - Engine Temperature 1 (PGN 65262 / 0xFEEE)
@@ -445,6 +706,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: -40.0,
         unit: "C",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 174,
@@ -457,6 +719,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: -40.0,
         unit: "C",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 175,
@@ -469,6 +732,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: -273.0,
         unit: "C",
         data_type: SpnDataType::Uint16,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 176,
@@ -481,6 +745,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: -273.0,
         unit: "C",
         data_type: SpnDataType::Uint16,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 52,
@@ -493,6 +758,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: -40.0,
         unit: "C",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 1134,
@@ -505,6 +771,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "%",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     // ========================================================================
     // EFL/P1 - Engine Fluid Level/Pressure 1 (PGN 65263 / 0xFEEF)
@@ -521,6 +788,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "kPa",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 22,
@@ -533,6 +801,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "kPa",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 98,
@@ -545,6 +814,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "%",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 100,
@@ -557,6 +827,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "kPa",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 101,
@@ -569,6 +840,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: -250.0,
         unit: "kPa",
         data_type: SpnDataType::Uint16,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 109,
@@ -581,6 +853,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "kPa",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 111,
@@ -593,6 +866,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "%",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     // ========================================================================
     // IC1 - Inlet/Exhaust Conditions 1 (PGN 65270 / 0xFEF6)
@@ -609,6 +883,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "kPa",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 102,
@@ -621,6 +896,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "kPa",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 105,
@@ -633,6 +909,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: -40.0,
         unit: "C",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 106,
@@ -645,6 +922,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "kPa",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 107,
@@ -657,6 +935,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "kPa",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 173,
@@ -669,6 +948,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: -273.0,
         unit: "C",
         data_type: SpnDataType::Uint16,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 112,
@@ -681,6 +961,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "kPa",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     // ========================================================================
     // VEP1 - Vehicle Electrical Power 1 (PGN 65271 / 0xFEF7)
@@ -692,23 +973,25 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         pgn: 65271,
         start_byte: 0,
         start_bit: 0,
-        bit_length: 16,
+        bit_length: 8,
         scale: 1.0,
         offset: -125.0,
         unit: "A",
-        data_type: SpnDataType::Int16,
+        data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 115,
         name: "alternator_current",
         pgn: 65271,
-        start_byte: 2,
+        start_byte: 1,
         start_bit: 0,
-        bit_length: 16,
+        bit_length: 8,
         scale: 1.0,
         offset: 0.0,
         unit: "A",
-        data_type: SpnDataType::Uint16,
+        data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 168,
@@ -721,6 +1004,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "V",
         data_type: SpnDataType::Uint16,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 158,
@@ -733,6 +1017,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "V",
         data_type: SpnDataType::Uint16,
+        ..SpnDef::DEFAULT
     },
     // ========================================================================
     // AMB - Ambient Conditions (PGN 65269 / 0xFEF5)
@@ -749,6 +1034,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "kPa",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 170,
@@ -761,6 +1047,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: -273.0,
         unit: "C",
         data_type: SpnDataType::Uint16,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 171,
@@ -773,6 +1060,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: -273.0,
         unit: "C",
         data_type: SpnDataType::Uint16,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 172,
@@ -785,6 +1073,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: -40.0,
         unit: "C",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 79,
@@ -797,6 +1086,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: -273.0,
         unit: "C",
         data_type: SpnDataType::Uint16,
+        ..SpnDef::DEFAULT
     },
     // ========================================================================
     // LFE - Liquid Fuel Economy (PGN 65266 / 0xFEF2)
@@ -813,6 +1103,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "L/h",
         data_type: SpnDataType::Uint16,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 184,
@@ -825,6 +1116,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "km/L",
         data_type: SpnDataType::Uint16,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 185,
@@ -837,6 +1129,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "km/L",
         data_type: SpnDataType::Uint16,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 51,
@@ -849,6 +1142,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "%",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     // ========================================================================
     // HOURS - Engine Hours, Revolutions (PGN 65253 / 0xFEE5)
@@ -865,6 +1159,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "h",
         data_type: SpnDataType::Uint32,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 249,
@@ -877,6 +1172,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "r",
         data_type: SpnDataType::Uint32,
+        ..SpnDef::DEFAULT
     },
     // ========================================================================
     // FC - Fuel Consumption (PGN 65257 / 0xFEE9)
@@ -893,6 +1189,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "L",
         data_type: SpnDataType::Uint32,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 250,
@@ -905,15 +1202,16 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "L",
         data_type: SpnDataType::Uint32,
+        ..SpnDef::DEFAULT
     },
     // ========================================================================
-    // VH - Vehicle Hours (PGN 65217 / 0xFEC1)
+    // VH - Vehicle Hours (PGN 65255 / 0xFEE7)
     // Broadcast rate: 1000ms
     // ========================================================================
     SpnDef {
         spn: 246,
-        name: "engine_total_idle_hours",
-        pgn: 65217,
+        name: "total_vehicle_hours",
+        pgn: 65255,
         start_byte: 0,
         start_bit: 0,
         bit_length: 32,
@@ -921,11 +1219,12 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "h",
         data_type: SpnDataType::Uint32,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 248,
         name: "engine_total_pto_hours",
-        pgn: 65217,
+        pgn: 65255,
         start_byte: 4,
         start_bit: 0,
         bit_length: 32,
@@ -933,9 +1232,10 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "h",
         data_type: SpnDataType::Uint32,
+        ..SpnDef::DEFAULT
     },
     // ========================================================================
-    // DD - Distance (PGN 65248 / 0xFEE0)
+    // VD - Vehicle Distance (PGN 65248 / 0xFEE0)
     // Broadcast rate: 1000ms
     // ========================================================================
     SpnDef {
@@ -949,6 +1249,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "km",
         data_type: SpnDataType::Uint32,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 245,
@@ -961,6 +1262,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "km",
         data_type: SpnDataType::Uint32,
+        ..SpnDef::DEFAULT
     },
     // ========================================================================
     // CCVS - Cruise Control/Vehicle Speed (PGN 65265 / 0xFEF1)
@@ -977,6 +1279,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 70,
@@ -989,6 +1292,8 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "",
         data_type: SpnDataType::Uint8,
+        reports_all_states: true,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 84,
@@ -1001,6 +1306,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "km/h",
         data_type: SpnDataType::Uint16,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 595,
@@ -1013,6 +1319,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 596,
@@ -1025,6 +1332,33 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
+    },
+    SpnDef {
+        spn: 597,
+        name: "brake_switch",
+        pgn: 65265,
+        start_byte: 3,
+        start_bit: 4,
+        bit_length: 2,
+        scale: 1.0,
+        offset: 0.0,
+        unit: "",
+        data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
+    },
+    SpnDef {
+        spn: 598,
+        name: "clutch_switch",
+        pgn: 65265,
+        start_byte: 3,
+        start_bit: 6,
+        bit_length: 2,
+        scale: 1.0,
+        offset: 0.0,
+        unit: "",
+        data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 86,
@@ -1037,6 +1371,7 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "km/h",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
     },
     SpnDef {
         spn: 976,
@@ -1049,38 +1384,487 @@ pub static SPN_DEFINITIONS: &[SpnDef] = &[
         offset: 0.0,
         unit: "",
         data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
+    },
+    // ========================================================================
+    // DD - Dash Display (PGN 65276 / 0xFEFC)
+    // Broadcast rate: 1000ms
+    // ========================================================================
+    SpnDef {
+        spn: 80,
+        name: "washer_fluid_level",
+        pgn: 65276,
+        start_byte: 0,
+        start_bit: 0,
+        bit_length: 8,
+        scale: 0.4,
+        offset: 0.0,
+        unit: "%",
+        data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
+    },
+    SpnDef {
+        spn: 96,
+        name: "fuel_level_1",
+        pgn: 65276,
+        start_byte: 1,
+        start_bit: 0,
+        bit_length: 8,
+        scale: 0.4,
+        offset: 0.0,
+        unit: "%",
+        data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
+    },
+    SpnDef {
+        spn: 95,
+        name: "engine_fuel_filter_differential_pressure",
+        pgn: 65276,
+        start_byte: 2,
+        start_bit: 0,
+        bit_length: 8,
+        scale: 2.0,
+        offset: 0.0,
+        unit: "kPa",
+        data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
+    },
+    SpnDef {
+        spn: 99,
+        name: "engine_oil_filter_differential_pressure",
+        pgn: 65276,
+        start_byte: 3,
+        start_bit: 0,
+        bit_length: 8,
+        scale: 0.5,
+        offset: 0.0,
+        unit: "kPa",
+        data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
+    },
+    SpnDef {
+        spn: 169,
+        name: "cargo_ambient_temperature",
+        pgn: 65276,
+        start_byte: 4,
+        start_bit: 0,
+        bit_length: 16,
+        scale: 0.03125,
+        offset: -273.0,
+        unit: "C",
+        data_type: SpnDataType::Uint16,
+        ..SpnDef::DEFAULT
+    },
+    SpnDef {
+        spn: 38,
+        name: "fuel_level_2",
+        pgn: 65276,
+        start_byte: 6,
+        start_bit: 0,
+        bit_length: 8,
+        scale: 0.4,
+        offset: 0.0,
+        unit: "%",
+        data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
+    },
+    // ========================================================================
+    // AT1T1I - Aftertreatment 1 DEF Tank 1 Information (PGN 65110 / 0xFE56)
+    // Broadcast rate: 1000ms
+    // ========================================================================
+    SpnDef {
+        spn: 1761,
+        name: "aftertreatment_1_def_tank_level",
+        pgn: 65110,
+        start_byte: 0,
+        start_bit: 0,
+        bit_length: 8,
+        scale: 0.4,
+        offset: 0.0,
+        unit: "%",
+        data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
+    },
+    SpnDef {
+        spn: 3031,
+        name: "aftertreatment_1_def_tank_temperature",
+        pgn: 65110,
+        start_byte: 1,
+        start_bit: 0,
+        bit_length: 8,
+        scale: 1.0,
+        offset: -40.0,
+        unit: "C",
+        data_type: SpnDataType::Uint8,
+        ..SpnDef::DEFAULT
+    },
+    // ========================================================================
+    // ET - Exhaust Temperature (PGN 65031 / 0xFE07)
+    // Broadcast rate: 500ms
+    // One temperature per exhaust manifold: instance 1 = right bank,
+    // instance 2 = left bank
+    // ========================================================================
+    SpnDef {
+        spn: 2433,
+        name: "exhaust_gas_temperature_right_manifold",
+        pgn: 65031,
+        start_byte: 0,
+        start_bit: 0,
+        bit_length: 16,
+        scale: 0.03125,
+        offset: -273.0,
+        unit: "C",
+        data_type: SpnDataType::Uint16,
+        instance: Some(1),
+        ..SpnDef::DEFAULT
+    },
+    SpnDef {
+        spn: 2434,
+        name: "exhaust_gas_temperature_left_manifold",
+        pgn: 65031,
+        start_byte: 2,
+        start_bit: 0,
+        bit_length: 16,
+        scale: 0.03125,
+        offset: -273.0,
+        unit: "C",
+        data_type: SpnDataType::Uint16,
+        instance: Some(2),
+        ..SpnDef::DEFAULT
+    },
+    // ========================================================================
+    // Unit-test fixture: multiplexed Proprietary B PGN (TEST_MUX_PGN)
+    // Byte 0 selects what bytes 1-2 carry: 1 = pressure, 2 = temperature.
+    // No built-in PGN is multiplexed, so this exercises the public
+    // encode/decode paths for mux_byte/mux_value.
+    // ========================================================================
+    #[cfg(test)]
+    SpnDef {
+        spn: 9101,
+        name: "test_mux_pressure",
+        pgn: TEST_MUX_PGN,
+        start_byte: 1,
+        scale: 4.0,
+        unit: "kPa",
+        mux_byte: Some(0),
+        mux_value: Some(1),
+        ..SpnDef::DEFAULT
+    },
+    #[cfg(test)]
+    SpnDef {
+        spn: 9102,
+        name: "test_mux_temperature",
+        pgn: TEST_MUX_PGN,
+        start_byte: 1,
+        offset: -40.0,
+        unit: "C",
+        mux_byte: Some(0),
+        mux_value: Some(2),
+        ..SpnDef::DEFAULT
     },
 ];
 
 // ============================================================================
-// Database lookup functions - O(log n) via binary search
+// PGN metadata - sorted by PGN for binary search
 // ============================================================================
 
-/// Get all SPNs for a given PGN.
-///
-/// Returns a slice of SPN definitions. O(log n) lookup via binary search.
-/// Hot path: always inlined for frame decoding.
-///
-/// # Example
+/// Metadata for every PGN in the database, sorted by PGN.
 ///
-/// ```
-/// use voltage_j1939::database::get_spns_for_pgn;
-///
-/// // EEC1 (PGN 61444) contains engine speed, torque, etc.
-/// if let Some(spns) = get_spns_for_pgn(61444) {
-///     for spn in spns {
-///         println!("SPN {}: {}", spn.spn, spn.name);
-///     }
-/// }
-/// ```
-#[inline(always)]
-pub fn get_spns_for_pgn(pgn: u32) -> Option<&'static [&'static SpnDef]> {
-    pgn_lookup().get(pgn)
-}
-
-/// Get a specific SPN definition by SPN number.
+/// Also covers the identification and diagnostic PGNs handled outside the
+/// SPN table (SOFT, CI, VI, DM1, DM2, DM13).
 ///
-/// O(log n) lookup via binary search.
+/// Broadcast rates are the nominal J1939-71 transmission intervals. For
+/// PGNs with an engine-dependent rate the slowest interval is used.
+/// Priorities are the J1939-71 defaults (3 for engine control, 6 otherwise).
+pub static PGN_INFO: &[PgnInfo] = &[
+    PgnInfo {
+        pgn: 57088,
+        acronym: "DM13",
+        name: "Stop Start Broadcast",
+        rate_ms: None,
+        dlc: Some(8),
+        priority: 6,
+    },
+    PgnInfo {
+        pgn: 61443,
+        acronym: "EEC2",
+        name: "Electronic Engine Controller 2",
+        rate_ms: Some(50),
+        dlc: Some(8),
+        priority: 3,
+    },
+    PgnInfo {
+        pgn: 61444,
+        acronym: "EEC1",
+        name: "Electronic Engine Controller 1",
+        rate_ms: Some(100),
+        dlc: Some(8),
+        priority: 3,
+    },
+    PgnInfo {
+        pgn: 65031,
+        acronym: "ET",
+        name: "Exhaust Temperature",
+        rate_ms: Some(500),
+        dlc: Some(8),
+        priority: 6,
+    },
+    PgnInfo {
+        pgn: 65110,
+        acronym: "AT1T1I",
+        name: "Aftertreatment 1 Diesel Exhaust Fluid Tank 1 Information",
+        rate_ms: Some(1000),
+        dlc: Some(8),
+        priority: 6,
+    },
+    PgnInfo {
+        pgn: 65226,
+        acronym: "DM1",
+        name: "Active Diagnostic Trouble Codes",
+        rate_ms: Some(1000),
+        dlc: None,
+        priority: 6,
+    },
+    PgnInfo {
+        pgn: 65227,
+        acronym: "DM2",
+        name: "Previously Active Diagnostic Trouble Codes",
+        rate_ms: None,
+        dlc: None,
+        priority: 6,
+    },
+    PgnInfo {
+        pgn: 65229,
+        acronym: "DM4",
+        name: "Freeze Frame Parameters",
+        rate_ms: None,
+        dlc: None,
+        priority: 6,
+    },
+    PgnInfo {
+        pgn: 65242,
+        acronym: "SOFT",
+        name: "Software Identification",
+        rate_ms: None,
+        dlc: None,
+        priority: 6,
+    },
+    PgnInfo {
+        pgn: 65247,
+        acronym: "EEC3",
+        name: "Electronic Engine Controller 3",
+        rate_ms: Some(250),
+        dlc: Some(8),
+        priority: 6,
+    },
+    PgnInfo {
+        pgn: 65248,
+        acronym: "VD",
+        name: "Vehicle Distance",
+        rate_ms: Some(1000),
+        dlc: Some(8),
+        priority: 6,
+    },
+    PgnInfo {
+        pgn: 65253,
+        acronym: "HOURS",
+        name: "Engine Hours, Revolutions",
+        rate_ms: Some(1000),
+        dlc: Some(8),
+        priority: 6,
+    },
+    PgnInfo {
+        pgn: 65255,
+        acronym: "VH",
+        name: "Vehicle Hours",
+        rate_ms: Some(1000),
+        dlc: Some(8),
+        priority: 6,
+    },
+    PgnInfo {
+        pgn: 65257,
+        acronym: "FC",
+        name: "Fuel Consumption",
+        rate_ms: Some(1000),
+        dlc: Some(8),
+        priority: 6,
+    },
+    PgnInfo {
+        pgn: 65259,
+        acronym: "CI",
+        name: "Component Identification",
+        rate_ms: None,
+        dlc: None,
+        priority: 6,
+    },
+    PgnInfo {
+        pgn: 65260,
+        acronym: "VI",
+        name: "Vehicle Identification",
+        rate_ms: None,
+        dlc: None,
+        priority: 6,
+    },
+    PgnInfo {
+        pgn: 65262,
+        acronym: "ET1",
+        name: "Engine Temperature 1",
+        rate_ms: Some(1000),
+        dlc: Some(8),
+        priority: 6,
+    },
+    PgnInfo {
+        pgn: 65263,
+        acronym: "EFL/P1",
+        name: "Engine Fluid Level/Pressure 1",
+        rate_ms: Some(500),
+        dlc: Some(8),
+        priority: 6,
+    },
+    PgnInfo {
+        pgn: 65265,
+        acronym: "CCVS",
+        name: "Cruise Control/Vehicle Speed",
+        rate_ms: Some(100),
+        dlc: Some(8),
+        priority: 6,
+    },
+    PgnInfo {
+        pgn: 65266,
+        acronym: "LFE",
+        name: "Liquid Fuel Economy",
+        rate_ms: Some(100),
+        dlc: Some(8),
+        priority: 6,
+    },
+    PgnInfo {
+        pgn: 65269,
+        acronym: "AMB",
+        name: "Ambient Conditions",
+        rate_ms: Some(1000),
+        dlc: Some(8),
+        priority: 6,
+    },
+    PgnInfo {
+        pgn: 65270,
+        acronym: "IC1",
+        name: "Inlet/Exhaust Conditions 1",
+        rate_ms: Some(500),
+        dlc: Some(8),
+        priority: 6,
+    },
+    PgnInfo {
+        pgn: 65271,
+        acronym: "VEP1",
+        name: "Vehicle Electrical Power 1",
+        rate_ms: Some(1000),
+        dlc: Some(8),
+        priority: 6,
+    },
+    PgnInfo {
+        pgn: 65276,
+        acronym: "DD",
+        name: "Dash Display",
+        rate_ms: Some(1000),
+        dlc: Some(8),
+        priority: 6,
+    },
+    // Unit-test fixture, see the multiplexed PGN at the end of SPN_TABLE
+    #[cfg(test)]
+    PgnInfo {
+        pgn: TEST_MUX_PGN,
+        acronym: "TESTMUX",
+        name: "Multiplexed Test Fixture",
+        rate_ms: None,
+        dlc: Some(8),
+        priority: 6,
+    },
+];
+
+// ============================================================================
+// Database lookup functions - O(1) PGN dispatch, O(log n) SPN lookup
+// ============================================================================
+
+/// Get all SPNs for a given PGN.
+///
+/// Returns a slice of SPN definitions. O(1) lookup via a compile-time perfect hash.
+/// Hot path: always inlined for frame decoding.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::database::get_spns_for_pgn;
+///
+/// // EEC1 (PGN 61444) contains engine speed, torque, etc.
+/// if let Some(spns) = get_spns_for_pgn(61444) {
+///     for spn in spns {
+///         println!("SPN {}: {}", spn.spn, spn.name);
+///     }
+/// }
+/// ```
+#[inline(always)]
+pub fn get_spns_for_pgn(pgn: u32) -> Option<&'static [&'static SpnDef]> {
+    PGN_LOOKUP.get(pgn)
+}
+
+/// Unit ids of the SPNs returned by [`get_spns_for_pgn`], in the same order.
+///
+/// Precomputed at compile time so FFI output doesn't search [`UNITS`] per SPN.
+#[inline(always)]
+pub(crate) fn unit_ids_for_pgn(pgn: u32) -> &'static [u16] {
+    PGN_LOOKUP.unit_ids(pgn)
+}
+
+/// Get all SPN definitions for a PGN in a const context.
+///
+/// Same table as [`get_spns_for_pgn`], usable in `const` items and const
+/// fns (e.g. generated dispatch tables placed in flash). Returns an empty
+/// slice for unknown PGNs.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::database::spns_for_pgn_const;
+/// use voltage_j1939::SpnDef;
+///
+/// const EEC1_SPNS: &[&SpnDef] = spns_for_pgn_const(61444);
+/// const _: () = assert!(!EEC1_SPNS.is_empty());
+///
+/// assert_eq!(EEC1_SPNS[0].pgn, 61444);
+/// assert!(spns_for_pgn_const(0xFF00).is_empty());
+/// ```
+pub const fn spns_for_pgn_const(pgn: u32) -> &'static [&'static SpnDef] {
+    match PGN_TABLE.get(pgn) {
+        Some(spns) => spns,
+        None => &[],
+    }
+}
+
+/// Mark which bytes of a PGN's 8-byte payload at least one SPN covers.
+///
+/// Bytes left `false` are not described by the database: reserved, or
+/// carrying parameters not (yet) defined here. All `false` for unknown PGNs.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::database::pgn_byte_coverage;
+///
+/// // ET: right and left manifold temperatures in bytes 0-3
+/// let coverage = pgn_byte_coverage(65031);
+/// assert_eq!(coverage, [true, true, true, true, false, false, false, false]);
+/// ```
+pub fn pgn_byte_coverage(pgn: u32) -> [bool; FRAME_LEN] {
+    let mut coverage = [false; FRAME_LEN];
+    for spn_def in get_spns_for_pgn(pgn).unwrap_or_default() {
+        coverage[spn_def.start_byte as usize..spn_def.required_len()].fill(true);
+    }
+    coverage
+}
+
+/// Get a specific SPN definition by SPN number.
+///
+/// O(log n) lookup via binary search.
 /// Hot path: always inlined for decode_spn_by_number.
 ///
 /// # Example
@@ -1095,22 +1879,468 @@ pub fn get_spns_for_pgn(pgn: u32) -> Option<&'static [&'static SpnDef]> {
 /// ```
 #[inline(always)]
 pub fn get_spn_def(spn: u32) -> Option<&'static SpnDef> {
-    spn_lookup().get(spn)
+    SPN_LOOKUP.get(spn)
+}
+
+/// Get the definition of one instance of an SPN.
+///
+/// For parameters repeated within a PGN (one per bank, side, ...), where
+/// [`get_spn_def`] only returns the first instance. Returns `None` if the
+/// SPN has no definition with that instance.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::database::get_spn_def_instance;
+///
+/// // Right manifold exhaust temperature is instance 1 of ET
+/// let right = get_spn_def_instance(2433, 1).unwrap();
+/// assert_eq!(right.pgn, 65031);
+/// assert!(get_spn_def_instance(2433, 2).is_none());
+/// assert!(get_spn_def_instance(190, 1).is_none()); // Not instanced
+/// ```
+pub fn get_spn_def_instance(spn: u32, instance: u8) -> Option<&'static SpnDef> {
+    SPN_LOOKUP.get_instance(spn, instance)
+}
+
+/// Get the definition of an SPN within a specific PGN.
+///
+/// Unlike [`get_spn_def`], which returns the first definition, this picks
+/// the right one when an SPN is defined in several PGNs.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::database::get_spn_def_in_pgn;
+///
+/// let speed = get_spn_def_in_pgn(190, 61444).unwrap(); // Engine speed in EEC1
+/// assert_eq!(speed.start_byte, 3);
+/// assert!(get_spn_def_in_pgn(190, 65262).is_none()); // Not in ET1
+/// ```
+pub fn get_spn_def_in_pgn(spn: u32, pgn: u32) -> Option<&'static SpnDef> {
+    SPN_LOOKUP
+        .get_all(spn)
+        .iter()
+        .map(|(_, spn_def)| *spn_def)
+        .find(|spn_def| spn_def.pgn == pgn)
+}
+
+/// Get every PGN in the database that carries an SPN.
+///
+/// Most SPNs belong to a single PGN, but some appear in several. Returns an
+/// empty vector for unknown SPNs. PGNs are listed in definition order,
+/// without duplicates.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::database::pgns_for_spn;
+///
+/// assert_eq!(pgns_for_spn(190), vec![61444]); // Engine speed in EEC1
+/// assert!(pgns_for_spn(999_999).is_empty());
+/// ```
+pub fn pgns_for_spn(spn: u32) -> Vec<u32> {
+    let mut pgns = Vec::new();
+    for (_, spn_def) in SPN_LOOKUP.get_all(spn) {
+        if !pgns.contains(&spn_def.pgn) {
+            pgns.push(spn_def.pgn);
+        }
+    }
+    pgns
+}
+
+/// SPN 976 PTO State (J1939-71), indexed by raw value.
+const PTO_STATE_NAMES: [&str; 32] = [
+    "Off/Disabled",
+    "Hold",
+    "Remote Hold",
+    "Standby",
+    "Remote Standby",
+    "Set",
+    "Decelerate/Coast",
+    "Resume",
+    "Accelerate",
+    "Accelerator Override",
+    "Preprogrammed Set Speed 1",
+    "Preprogrammed Set Speed 2",
+    "Preprogrammed Set Speed 3",
+    "Preprogrammed Set Speed 4",
+    "Preprogrammed Set Speed 5",
+    "Preprogrammed Set Speed 6",
+    "Preprogrammed Set Speed 7",
+    "Preprogrammed Set Speed 8",
+    "PTO Set Speed Memory 1",
+    "PTO Set Speed Memory 2",
+    "Reserved",
+    "Reserved",
+    "Reserved",
+    "Reserved",
+    "Reserved",
+    "Reserved",
+    "Reserved",
+    "Reserved",
+    "Reserved",
+    "Reserved",
+    "Reserved",
+    "Not Available",
+];
+
+/// Named states of enumerated SPNs, as (spn, names indexed by raw value).
+static SPN_STATE_NAMES: &[(u32, &[&str])] = &[(976, &PTO_STATE_NAMES)];
+
+/// Get the SAE name of a raw state of an enumerated SPN.
+///
+/// Returns `None` for SPNs without a state table and for raw values
+/// wider than the field. Codes the standard leaves undefined are named
+/// "Reserved".
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::database::spn_state_name;
+///
+/// assert_eq!(spn_state_name(976, 5), Some("Set")); // PTO state
+/// assert_eq!(spn_state_name(976, 25), Some("Reserved"));
+/// assert_eq!(spn_state_name(976, 31), Some("Not Available"));
+/// assert_eq!(spn_state_name(190, 0), None); // Engine speed is not an enum
+/// ```
+pub fn spn_state_name(spn: u32, raw_value: u64) -> Option<&'static str> {
+    let (_, names) = SPN_STATE_NAMES.iter().find(|(s, _)| *s == spn)?;
+    names.get(usize::try_from(raw_value).ok()?).copied()
+}
+
+/// Search SPNs whose name contains `query` (case-insensitive).
+///
+/// Results are sorted by SPN number. An empty query matches every SPN.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::database::search_spns;
+///
+/// let coolant: Vec<u32> = search_spns("Coolant").iter().map(|s| s.spn).collect();
+/// assert_eq!(coolant, vec![109, 110, 111, 112]);
+/// ```
+pub fn search_spns(query: &str) -> Vec<&'static SpnDef> {
+    let query = query.to_ascii_lowercase();
+    SPN_LOOKUP
+        .entries
+        .iter()
+        .map(|(_, spn_def)| *spn_def)
+        .filter(|spn_def| spn_def.name.to_ascii_lowercase().contains(&query))
+        .collect()
+}
+
+/// Get metadata for a PGN.
+///
+/// O(log n) lookup via binary search.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::database::get_pgn_info;
+///
+/// let info = get_pgn_info(61444).unwrap();
+/// assert_eq!(info.acronym, "EEC1");
+/// ```
+#[inline]
+pub fn get_pgn_info(pgn: u32) -> Option<&'static PgnInfo> {
+    PGN_INFO
+        .binary_search_by_key(&pgn, |info| info.pgn)
+        .ok()
+        .map(|idx| &PGN_INFO[idx])
+}
+
+/// Get the nominal broadcast interval of a PGN in milliseconds.
+///
+/// Returns `None` for unknown PGNs and PGNs that are only sent on request.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::database::expected_interval_ms;
+///
+/// assert_eq!(expected_interval_ms(65262), Some(1000)); // ET1
+/// ```
+#[inline]
+pub fn expected_interval_ms(pgn: u32) -> Option<u32> {
+    get_pgn_info(pgn)?.rate_ms
+}
+
+/// Get the expected data length (DLC) of a PGN.
+///
+/// Returns `None` for unknown PGNs and variable-length PGNs that are usually
+/// sent via the transport protocol (e.g. SOFT, CI, VI).
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::database::expected_dlc;
+///
+/// assert_eq!(expected_dlc(61444), Some(8)); // EEC1
+/// assert_eq!(expected_dlc(65260), None); // VI, variable length
+/// ```
+#[inline]
+pub fn expected_dlc(pgn: u32) -> Option<u8> {
+    get_pgn_info(pgn)?.dlc
+}
+
+/// Get the default transmit priority of a PGN.
+///
+/// Returns `None` for PGNs not in the database.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::database::default_priority;
+///
+/// assert_eq!(default_priority(61444), Some(3)); // EEC1
+/// assert_eq!(default_priority(65262), Some(6)); // ET1
+/// ```
+#[inline]
+pub fn default_priority(pgn: u32) -> Option<u8> {
+    Some(get_pgn_info(pgn)?.priority)
+}
+
+/// Build the broadcast CAN ID for a PGN at its default priority.
+///
+/// Uses the priority from [`PgnInfo`], or 6 for PGNs not in the database.
+/// PDU1 PGNs are addressed to the global address; use
+/// [`can_id_for_pgn`] for a specific destination or priority.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::database::default_can_id_for_pgn;
+///
+/// assert_eq!(default_can_id_for_pgn(61444, 0x00), 0x0CF00400); // EEC1
+/// assert_eq!(default_can_id_for_pgn(65262, 0x00), 0x18FEEE00); // ET1
+/// ```
+#[inline]
+pub fn default_can_id_for_pgn(pgn: u32, source_address: u8) -> u32 {
+    let priority = default_priority(pgn).unwrap_or(DEFAULT_PRIORITY);
+    can_id_for_pgn(pgn, priority, source_address, GLOBAL_ADDRESS)
+}
+
+/// Revision of the SPN/PGN tables, bumped whenever a definition changes.
+///
+/// Independent of the crate version: record it alongside decoded data to
+/// know which table produced it.
+pub const DATABASE_REVISION: &str = "6";
+
+/// Get the revision of the built-in database ([`DATABASE_REVISION`]).
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::database::{database_version, DATABASE_REVISION};
+///
+/// assert_eq!(database_version(), DATABASE_REVISION);
+/// ```
+#[inline]
+pub const fn database_version() -> &'static str {
+    DATABASE_REVISION
+}
+
+/// Unit strings of the database, indexed by unit id.
+///
+/// Gives units a plain integer form for FFI (see
+/// [`DecodedSpnC`](crate::types::DecodedSpnC)). Ids are stable: new units
+/// are only ever appended.
+pub static UNITS: &[&str] = UNIT_TABLE;
+
+const UNIT_TABLE: &[&str] = &[
+    "", "%", "A", "C", "L", "L/h", "RPM", "V", "h", "kPa", "kg/h", "km", "km/L", "km/h", "r",
+];
+
+/// Id of a unit in [`UNIT_TABLE`], `UNIT_TABLE.len()` if missing.
+const fn unit_index(unit: &str) -> usize {
+    let mut u = 0;
+    while u < UNIT_TABLE.len() && !str_eq(UNIT_TABLE[u], unit) {
+        u += 1;
+    }
+    u
+}
+
+// Every SPN's unit has an id.
+const _: () = {
+    let mut i = 0;
+    while i < SPN_COUNT {
+        assert!(
+            unit_index(SPN_TABLE[i].unit) < UNIT_TABLE.len(),
+            "SPN unit missing from UNITS"
+        );
+        i += 1;
+    }
+};
+
+/// Get the id of a unit string in [`UNITS`].
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::database::{unit_id, unit_str};
+///
+/// let id = unit_id("RPM").unwrap();
+/// assert_eq!(unit_str(id), Some("RPM"));
+/// assert_eq!(unit_id("furlong"), None);
+/// ```
+pub fn unit_id(unit: &str) -> Option<u16> {
+    UNITS.iter().position(|&u| u == unit).map(|id| id as u16)
+}
+
+/// Get the unit string for a unit id, `None` if the id is unknown.
+#[inline]
+pub fn unit_str(unit_id: u16) -> Option<&'static str> {
+    UNITS.get(unit_id as usize).copied()
 }
 
 /// Get statistics about the database.
 ///
-/// Returns (number of unique PGNs, total number of SPNs).
-/// O(1) after first call (cached).
+/// Returns (number of unique PGNs, total number of SPNs, number of unique
+/// SPN names). SPNs carried by several PGNs count once per PGN.
+/// O(1) - all counts are computed at compile time.
 #[inline]
-pub fn database_stats() -> (usize, usize) {
-    (pgn_lookup().pgn_count(), SPN_DEFINITIONS.len())
+pub fn database_stats() -> (usize, usize, usize) {
+    (PGN_COUNT, SPN_COUNT, NAME_COUNT)
+}
+
+/// Plausible engineering ranges per unit, as `(unit, min, max)`.
+///
+/// Generous physical limits, wide enough for J1939 data ranges (e.g. percent
+/// torque down to -125%, percent load up to 250%) but tight enough to catch
+/// a mistyped scale or a field declared wider than it is.
+const UNIT_BOUNDS: &[(&str, f64, f64)] = &[
+    ("%", -125.0, 255.0),
+    ("C", -273.15, 2000.0),
+    ("kPa", -255.0, 10_000.0),
+    ("RPM", 0.0, 10_000.0),
+    ("km/h", 0.0, 300.0),
+    ("km/L", 0.0, 150.0),
+    ("L/h", 0.0, 5000.0),
+    ("V", 0.0, 5000.0),
+    ("A", -2000.0, 2000.0),
+];
+
+/// Lint the database for SPNs whose value range is implausible for their unit.
+///
+/// Checks each definition's [`SpnDef::value_range`] against heuristic bounds
+/// per unit (e.g. "%" within -125..255, "C" within -273.15..2000). Counters
+/// (hours, distance, fuel totals) and unitless states are not checked.
+/// Returns `(spn, message)` for every definition out of bounds.
+///
+/// This lints the data, not the decoder: a finding usually means a mistyped
+/// scale or a wrong bit length.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::database::sanity_check_ranges;
+///
+/// for (spn, message) in sanity_check_ranges() {
+///     println!("SPN {}: {}", spn, message);
+/// }
+/// ```
+pub fn sanity_check_ranges() -> Vec<(u32, String)> {
+    SPN_DEFINITIONS
+        .iter()
+        .filter_map(|spn_def| {
+            let &(_, lo, hi) = UNIT_BOUNDS.iter().find(|(u, _, _)| *u == spn_def.unit)?;
+            let (min, max) = spn_def.value_range();
+            (min < lo || max > hi).then(|| {
+                (
+                    spn_def.spn,
+                    format!(
+                        "{} range {}..{} {} outside plausible {}..{}",
+                        spn_def.name, min, max, spn_def.unit, lo, hi
+                    ),
+                )
+            })
+        })
+        .collect()
 }
 
 /// List all supported PGNs (already sorted).
 #[inline]
 pub fn list_supported_pgns() -> impl Iterator<Item = u32> {
-    pgn_lookup().iter_pgns()
+    PGN_LOOKUP.iter_pgns()
+}
+
+/// Custom decoder for the payload of one PGN.
+pub type PgnHandler = Box<dyn Fn(&[u8]) -> Vec<DecodedSpn>>;
+
+/// Extension point for PGNs the built-in tables don't describe.
+///
+/// Proprietary messages (Proprietary A 0xEF00, Proprietary B 0xFFxx) often
+/// have fields that don't fit the scale/offset model of [`SpnDef`]. A
+/// handler registered for such a PGN decodes its payload in code;
+/// [`decode_frame_with`](crate::decoder::decode_frame_with) calls it, and
+/// falls back to the built-in tables for every other PGN.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::database::Database;
+/// use voltage_j1939::decoder::decode_frame_with;
+/// use voltage_j1939::DecodedSpn;
+///
+/// let mut db = Database::new();
+/// db.register_pgn_handler(0xEF00, Box::new(|data: &[u8]| {
+///     let raw = u64::from(*data.first().unwrap_or(&0xFF));
+///     vec![DecodedSpn {
+///         value: raw as f64 * 10.0,
+///         raw_value: raw,
+///         spn: 0,
+///         instance: None,
+///         name: "pump_flow",
+///         unit: "L/h",
+///         scale: 10.0,
+///     }]
+/// }));
+///
+/// // Proprietary A to 0x21 from 0x80
+/// let decoded = decode_frame_with(&db, 0x18EF2180, &[12, 0, 0, 0, 0, 0, 0, 0]);
+/// assert_eq!(decoded[0].value, 120.0);
+///
+/// // Other PGNs still use the built-in tables
+/// assert!(!decode_frame_with(&db, 0x0CF00400, &[0; 8]).is_empty());
+/// ```
+#[derive(Default)]
+pub struct Database {
+    /// Registered handlers as (pgn, handler)
+    handlers: Vec<(u32, PgnHandler)>,
+}
+
+impl Database {
+    /// Create a database with no custom handlers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `handler` for `pgn`, replacing any handler already registered.
+    ///
+    /// The handler takes precedence over built-in definitions of the PGN.
+    pub fn register_pgn_handler(&mut self, pgn: u32, handler: PgnHandler) {
+        match self.handlers.iter_mut().find(|(p, _)| *p == pgn) {
+            Some((_, existing)) => *existing = handler,
+            None => self.handlers.push((pgn, handler)),
+        }
+    }
+
+    /// Handler registered for `pgn`, if any.
+    pub fn pgn_handler(&self, pgn: u32) -> Option<&PgnHandler> {
+        self.handlers
+            .iter()
+            .find(|(p, _)| *p == pgn)
+            .map(|(_, handler)| handler)
+    }
+}
+
+impl std::fmt::Debug for Database {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let pgns: Vec<u32> = self.handlers.iter().map(|(pgn, _)| *pgn).collect();
+        f.debug_struct("Database").field("handlers", &pgns).finish()
+    }
 }
 
 #[cfg(test)]
@@ -1119,9 +2349,104 @@ mod tests {
 
     #[test]
     fn test_database_stats() {
-        let (pgn_count, spn_count) = database_stats();
+        let (pgn_count, spn_count, name_count) = database_stats();
         assert!(pgn_count >= 10, "Should have at least 10 PGNs");
         assert!(spn_count >= 50, "Should have at least 50 SPNs");
+
+        let names: std::collections::HashSet<_> = SPN_DEFINITIONS.iter().map(|s| s.name).collect();
+        assert_eq!(name_count, names.len());
+        assert!(name_count <= spn_count);
+        assert!(!database_version().is_empty());
+    }
+
+    /// FNV-1a fingerprint of every table versioned by [`DATABASE_REVISION`].
+    fn table_fingerprint() -> u64 {
+        let mut hash = 0xcbf2_9ce4_8422_2325u64;
+        let mut feed = |text: String| {
+            for byte in text.bytes() {
+                hash = (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3);
+            }
+        };
+
+        // The unit-test fixture is not part of the shipped tables
+        for s in SPN_DEFINITIONS.iter().filter(|s| s.pgn != TEST_MUX_PGN) {
+            feed(format!(
+                "{} {} {} {} {} {} {} {:?} {:x} {:x} {:?} {:?} {:?} {};",
+                s.spn,
+                s.pgn,
+                s.name,
+                s.unit,
+                s.start_byte,
+                s.start_bit,
+                s.bit_length,
+                s.data_type,
+                s.scale.to_bits(),
+                s.offset.to_bits(),
+                s.mux_byte,
+                s.mux_value,
+                s.instance,
+                s.reports_all_states
+            ));
+        }
+        for info in PGN_INFO.iter().filter(|info| info.pgn != TEST_MUX_PGN) {
+            feed(format!("{:?};", info));
+        }
+        for (spn, names) in SPN_STATE_NAMES {
+            feed(format!("{} {:?};", spn, names));
+        }
+        feed(format!("{:?}", UNITS));
+        hash
+    }
+
+    #[test]
+    fn test_database_revision_tracks_tables() {
+        // A table change must come with a DATABASE_REVISION bump: update both here
+        assert_eq!(
+            (DATABASE_REVISION, table_fingerprint()),
+            ("6", 0x6390_7a4f_a8e5_587f),
+            "tables changed: bump DATABASE_REVISION and record the new fingerprint"
+        );
+    }
+
+    #[test]
+    fn test_sanity_check_ranges() {
+        let flagged = sanity_check_ranges();
+        assert!(flagged.is_empty(), "implausible ranges: {:?}", flagged);
+    }
+
+    #[test]
+    fn test_sanity_check_ranges_bounds() {
+        let lookup = |unit: &str| UNIT_BOUNDS.iter().find(|(u, _, _)| *u == unit).unwrap();
+        // Percent torque and percent load fit, a x10 scale typo does not
+        let (_, lo, hi) = *lookup("%");
+        let torque = get_spn_def(513).unwrap().value_range();
+        assert!(torque.0 >= lo && torque.1 <= hi);
+        let typo = SpnDef {
+            scale: 4.0,
+            ..*get_spn_def(91).unwrap()
+        };
+        assert!(typo.value_range().1 > hi);
+
+        // Temperatures down to absolute zero
+        let (_, lo, _) = *lookup("C");
+        assert!(get_spn_def(175).unwrap().value_range().0 >= lo);
+    }
+
+    #[test]
+    fn test_default_can_id_for_pgn() {
+        assert_eq!(default_can_id_for_pgn(61443, 0x00), 0x0CF00300); // EEC2
+        assert_eq!(default_can_id_for_pgn(65276, 0x17), 0x18FEFC17); // DD
+        assert_eq!(default_can_id_for_pgn(65226, 0x00), 0x18FECA00); // DM1
+
+        // Unknown PGN: J1939 default priority 6
+        assert_eq!(default_can_id_for_pgn(0xFF10, 0x80), 0x18FF1080);
+        assert_eq!(default_priority(0xFF10), None);
+
+        for info in PGN_INFO {
+            let can_id = default_can_id_for_pgn(info.pgn, 0x00);
+            assert_eq!(crate::frame::extract_pgn(can_id), info.pgn);
+            assert_eq!((can_id >> 26) as u8, info.priority);
+        }
     }
 
     #[test]
@@ -1157,6 +2482,377 @@ mod tests {
         assert_eq!(spn.offset, -40.0);
     }
 
+    #[test]
+    fn test_pgn_perfect_hash_exhaustive() {
+        // Every 18-bit PGN must resolve to exactly the SPNs defined for it
+        let pgns: Vec<_> = list_supported_pgns().collect();
+        for pgn in 0..=0x3FFFFu32 {
+            match get_spns_for_pgn(pgn) {
+                Some(spns) => {
+                    assert!(pgns.contains(&pgn), "PGN {} should not resolve", pgn);
+                    let expected = SPN_DEFINITIONS.iter().filter(|s| s.pgn == pgn).count();
+                    assert_eq!(spns.len(), expected);
+                    assert!(spns.iter().all(|s| s.pgn == pgn));
+                }
+                None => assert!(!pgns.contains(&pgn), "PGN {} should resolve", pgn),
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_spns_for_pgn_keeps_definition_order() {
+        let spns = get_spns_for_pgn(61444).unwrap();
+        let expected: Vec<_> = SPN_DEFINITIONS
+            .iter()
+            .filter(|s| s.pgn == 61444)
+            .map(|s| s.spn)
+            .collect();
+        let actual: Vec<_> = spns.iter().map(|s| s.spn).collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_pgn_info_sorted_and_complete() {
+        assert!(PGN_INFO.windows(2).all(|w| w[0].pgn < w[1].pgn));
+        // Every PGN carrying SPNs has metadata
+        for pgn in list_supported_pgns() {
+            assert!(get_pgn_info(pgn).is_some(), "Missing PgnInfo for {}", pgn);
+        }
+    }
+
+    #[test]
+    fn test_eec1_layout_matches_j1939_71() {
+        // (SPN, start byte, start bit, bit length) per SAE J1939-71 EEC1
+        let expected = [
+            (899, 0, 0, 4),
+            (4154, 0, 4, 4),
+            (512, 1, 0, 8),
+            (513, 2, 0, 8),
+            (190, 3, 0, 16),
+            (1483, 5, 0, 8),
+            (1675, 6, 0, 4),
+            (2432, 7, 0, 8),
+        ];
+        for (spn, start_byte, start_bit, bit_length) in expected {
+            let def = get_spn_def(spn).unwrap();
+            assert_eq!(def.pgn, 61444, "SPN {}", spn);
+            assert_eq!(
+                (def.start_byte, def.start_bit, def.bit_length),
+                (start_byte, start_bit, bit_length),
+                "SPN {}",
+                spn
+            );
+        }
+    }
+
+    #[test]
+    fn test_no_overlapping_spns_within_pgn() {
+        for pgn in list_supported_pgns() {
+            let mut used = 0u64;
+            // Multiplexed SPNs share bytes by design
+            for def in get_spns_for_pgn(pgn)
+                .unwrap()
+                .iter()
+                .filter(|d| d.mux_byte.is_none())
+            {
+                let start = def.start_byte as u32 * 8 + def.start_bit as u32;
+                let bits = crate::decoder::bit_mask(def.bit_length) << start;
+                assert_eq!(used & bits, 0, "SPN {} overlaps in PGN {}", def.spn, pgn);
+                used |= bits;
+            }
+        }
+    }
+
+    #[test]
+    fn test_pgns_for_spn() {
+        for spn_def in SPN_DEFINITIONS {
+            assert!(pgns_for_spn(spn_def.spn).contains(&spn_def.pgn));
+        }
+        assert_eq!(pgns_for_spn(110), vec![65262]);
+        assert!(pgns_for_spn(0).is_empty());
+    }
+
+    #[test]
+    fn test_instances_distinguish_repeated_spns() {
+        // A (SPN, PGN) pair may repeat only with distinct instances
+        for (i, a) in SPN_DEFINITIONS.iter().enumerate() {
+            for b in &SPN_DEFINITIONS[i + 1..] {
+                assert!(
+                    (a.spn, a.pgn, a.instance) != (b.spn, b.pgn, b.instance),
+                    "SPN {} defined twice in PGN {}",
+                    a.spn,
+                    a.pgn
+                );
+            }
+        }
+        let et = get_spns_for_pgn(65031).unwrap();
+        assert!(et.iter().all(|spn_def| spn_def.instance.is_some()));
+    }
+
+    #[test]
+    fn test_spn_lookup_duplicates() {
+        static TABLE: &[SpnDef] = &[
+            SpnDef {
+                pgn: 100,
+                ..SPN_TABLE[0]
+            },
+            SpnDef {
+                spn: 1,
+                ..SPN_TABLE[0]
+            },
+            SpnDef {
+                pgn: 200,
+                ..SPN_TABLE[0]
+            },
+            SpnDef {
+                pgn: 100,
+                ..SPN_TABLE[0]
+            },
+        ];
+        static LOOKUP: SpnLookup<4> = SpnLookup::build(TABLE);
+
+        let spn = SPN_TABLE[0].spn;
+        let pgns: Vec<_> = LOOKUP.get_all(spn).iter().map(|(_, d)| d.pgn).collect();
+        assert_eq!(pgns, vec![100, 200, 100]);
+        // get() returns the first definition
+        assert_eq!(LOOKUP.get(spn).unwrap().pgn, 100);
+        assert_eq!(LOOKUP.get(1).unwrap().spn, 1);
+        assert!(LOOKUP.get_all(2).is_empty());
+    }
+
+    #[test]
+    fn test_spn_lookup_instances() {
+        // One SPN repeated per bank within the same PGN
+        static TABLE: &[SpnDef] = &[
+            SpnDef {
+                spn: 173,
+                pgn: 65031,
+                start_byte: 0,
+                instance: Some(1),
+                ..SpnDef::DEFAULT
+            },
+            SpnDef {
+                spn: 173,
+                pgn: 65031,
+                start_byte: 1,
+                instance: Some(2),
+                ..SpnDef::DEFAULT
+            },
+        ];
+        static LOOKUP: SpnLookup<2> = SpnLookup::build(TABLE);
+
+        assert_eq!(LOOKUP.get(173).unwrap().instance, Some(1));
+        assert_eq!(LOOKUP.get_instance(173, 1).unwrap().start_byte, 0);
+        assert_eq!(LOOKUP.get_instance(173, 2).unwrap().start_byte, 1);
+        assert!(LOOKUP.get_instance(173, 3).is_none());
+        assert!(LOOKUP.get_instance(174, 1).is_none());
+    }
+
+    #[test]
+    fn test_expected_dlc() {
+        assert_eq!(expected_dlc(61444), Some(8));
+        assert_eq!(expected_dlc(57088), Some(8)); // DM13
+        assert_eq!(expected_dlc(65242), None); // SOFT
+        assert_eq!(expected_dlc(65259), None); // CI
+        assert_eq!(expected_dlc(0xFF00), None); // Unknown
+
+        // Every PGN with SPNs is a single fixed-size frame
+        for pgn in list_supported_pgns() {
+            assert_eq!(expected_dlc(pgn), Some(8), "PGN {}", pgn);
+        }
+    }
+
+    #[test]
+    fn test_spns_for_pgn_const_matches_runtime_lookup() {
+        for pgn in list_supported_pgns() {
+            let runtime = get_spns_for_pgn(pgn).unwrap();
+            let table = spns_for_pgn_const(pgn);
+            assert_eq!(table.len(), runtime.len(), "PGN {}", pgn);
+            assert!(table
+                .iter()
+                .zip(runtime)
+                .all(|(a, b)| a.spn == b.spn && a.pgn == b.pgn));
+        }
+        assert!(spns_for_pgn_const(0).is_empty());
+        assert!(spns_for_pgn_const(0x0CF00400).is_empty());
+    }
+
+    #[test]
+    fn test_get_spn_def_in_pgn() {
+        for spn_def in SPN_DEFINITIONS {
+            let found = get_spn_def_in_pgn(spn_def.spn, spn_def.pgn).unwrap();
+            assert_eq!(found.pgn, spn_def.pgn);
+            assert_eq!(found.name, spn_def.name);
+        }
+        assert!(get_spn_def_in_pgn(110, 61444).is_none());
+        assert!(get_spn_def_in_pgn(999_999, 61444).is_none());
+        assert_eq!(
+            get_spn_def_in_pgn(110, 65262).map(|s| s.name),
+            Some("engine_coolant_temperature")
+        );
+    }
+
+    #[test]
+    fn test_lookups_with_pgn() {
+        use crate::types::Pgn;
+
+        let eec1 = Pgn::from_can_id(0x0CF00400);
+        assert_eq!(eec1.info().unwrap().acronym, "EEC1");
+        assert_eq!(eec1.expected_dlc(), Some(8));
+        assert_eq!(
+            eec1.spns().unwrap().as_ptr(),
+            get_spns_for_pgn(61444).unwrap().as_ptr()
+        );
+        assert_eq!(expected_interval_ms(eec1.value()), Some(100));
+
+        let unknown = Pgn::new(0xFF00).unwrap();
+        assert!(unknown.spns().is_none() && unknown.info().is_none());
+        assert_eq!(unknown.expected_dlc(), None);
+
+        // Plain u32 lookups compose with iterator adapters
+        let found = [61444, 65262, 0xFF00].map(get_spns_for_pgn);
+        assert_eq!(found.map(|spns| spns.is_some()), [true, true, false]);
+
+        // A CAN ID can't be turned into a Pgn by mistake
+        assert!(Pgn::try_from(0x0CF00400).is_err());
+    }
+
+    #[test]
+    fn test_ccvs_layout_matches_j1939_71() {
+        // (SPN, start byte, start bit, bit length) per SAE J1939-71 CCVS
+        let expected = [
+            (69, 0, 0, 2),
+            (70, 0, 2, 2),
+            (84, 1, 0, 16),
+            (595, 3, 0, 2),
+            (596, 3, 2, 2),
+            (597, 3, 4, 2),
+            (598, 3, 6, 2),
+            (86, 5, 0, 8),
+            (976, 6, 0, 5),
+        ];
+        for (spn, start_byte, start_bit, bit_length) in expected {
+            let def = get_spn_def(spn).unwrap();
+            assert_eq!(def.pgn, 65265, "SPN {}", spn);
+            assert_eq!(
+                (def.start_byte, def.start_bit, def.bit_length),
+                (start_byte, start_bit, bit_length),
+                "SPN {}",
+                spn
+            );
+        }
+    }
+
+    #[test]
+    fn test_dd_layout_matches_j1939_71() {
+        // (SPN, start byte, bit length, scale) per SAE J1939-71 DD
+        let expected = [
+            (80, 0, 8, 0.4),
+            (96, 1, 8, 0.4),
+            (95, 2, 8, 2.0),
+            (99, 3, 8, 0.5),
+            (169, 4, 16, 0.03125),
+            (38, 6, 8, 0.4),
+        ];
+        let spns = get_spns_for_pgn(65276).unwrap();
+        assert_eq!(spns.len(), expected.len());
+        for (spn, start_byte, bit_length, scale) in expected {
+            let def = get_spn_def_in_pgn(spn, 65276).unwrap();
+            assert_eq!(
+                (def.start_byte, def.bit_length, def.scale),
+                (start_byte, bit_length, scale),
+                "SPN {}",
+                spn
+            );
+        }
+    }
+
+    #[test]
+    fn test_ic1_and_eec3_pgns_are_distinct() {
+        let ic1: Vec<_> = get_spns_for_pgn(65270)
+            .unwrap()
+            .iter()
+            .map(|s| s.spn)
+            .collect();
+        assert_eq!(ic1, vec![81, 102, 105, 106, 107, 173, 112]);
+
+        let eec3: Vec<_> = get_spns_for_pgn(65247)
+            .unwrap()
+            .iter()
+            .map(|s| s.spn)
+            .collect();
+        assert_eq!(eec3, vec![514, 515, 519, 2978, 6595]);
+        for spn in eec3 {
+            assert_eq!(pgns_for_spn(spn), vec![65247], "SPN {}", spn);
+        }
+
+        assert_eq!(get_pgn_info(65270).unwrap().acronym, "IC1");
+        assert_eq!(get_pgn_info(65247).unwrap().acronym, "EEC3");
+    }
+
+    #[test]
+    fn test_get_pgn_info_acronyms() {
+        let expected = [
+            (57088, "DM13"),
+            (61443, "EEC2"),
+            (61444, "EEC1"),
+            (65031, "ET"),
+            (65110, "AT1T1I"),
+            (65242, "SOFT"),
+            (65247, "EEC3"),
+            (65248, "VD"),
+            (65253, "HOURS"),
+            (65255, "VH"),
+            (65257, "FC"),
+            (65259, "CI"),
+            (65260, "VI"),
+            (65262, "ET1"),
+            (65263, "EFL/P1"),
+            (65265, "CCVS"),
+            (65266, "LFE"),
+            (65269, "AMB"),
+            (65270, "IC1"),
+            (65271, "VEP1"),
+            (65276, "DD"),
+        ];
+        for (pgn, acronym) in expected {
+            assert_eq!(
+                get_pgn_info(pgn).map(|i| i.acronym),
+                Some(acronym),
+                "PGN {}",
+                pgn
+            );
+        }
+        // 65217 is High Resolution Vehicle Distance, not Vehicle Hours
+        assert!(get_pgn_info(65217).is_none());
+        assert_eq!(get_spn_def(246).unwrap().pgn, 65255);
+    }
+
+    #[test]
+    fn test_expected_interval_ms() {
+        assert_eq!(expected_interval_ms(61444), Some(100)); // EEC1
+        assert_eq!(expected_interval_ms(61443), Some(50)); // EEC2
+        assert_eq!(expected_interval_ms(65262), Some(1000)); // ET1
+        assert_eq!(expected_interval_ms(0xFF00), None); // Unknown
+    }
+
+    #[test]
+    fn test_search_spns() {
+        let names: Vec<_> = search_spns("coolant").iter().map(|s| s.name).collect();
+        assert!(names.contains(&"engine_coolant_temperature"));
+        assert!(names.contains(&"coolant_pressure"));
+        assert!(names.contains(&"coolant_level"));
+
+        // Case-insensitive and sorted by SPN number
+        let results = search_spns("TEMP");
+        assert!(results.len() >= 5);
+        assert!(results.windows(2).all(|w| w[0].spn <= w[1].spn));
+        assert!(results.iter().all(|s| s.name.contains("temp")));
+
+        assert!(search_spns("no_such_parameter").is_empty());
+        assert_eq!(search_spns("").len(), SPN_DEFINITIONS.len());
+    }
+
     #[test]
     fn test_list_supported_pgns() {
         let pgns: Vec<_> = list_supported_pgns().collect();
@@ -1164,4 +2860,81 @@ mod tests {
         assert!(pgns.contains(&61444)); // EEC1
         assert!(pgns.contains(&65262)); // ET1
     }
+
+    #[test]
+    fn test_pgn_byte_coverage() {
+        assert_eq!(pgn_byte_coverage(61444), [true; 8]); // EEC1, fully described
+        assert_eq!(pgn_byte_coverage(0xFF00), [false; 8]); // Unknown
+
+        // Every covered byte is inside some SPN, and every SPN byte is covered
+        for pgn in list_supported_pgns() {
+            let coverage = pgn_byte_coverage(pgn);
+            let spns = get_spns_for_pgn(pgn).unwrap();
+            for (byte, &covered) in coverage.iter().enumerate() {
+                let referenced = spns
+                    .iter()
+                    .any(|s| (s.start_byte as usize..s.required_len()).contains(&byte));
+                assert_eq!(covered, referenced, "PGN {} byte {}", pgn, byte);
+            }
+        }
+    }
+
+    #[test]
+    fn test_supported_key_sets() {
+        let mut spns: Vec<u32> = SPN_DEFINITIONS.iter().map(|s| s.spn).collect();
+        spns.sort_unstable();
+        spns.dedup();
+        assert_eq!(SUPPORTED_SPNS, spns);
+        assert!(SUPPORTED_SPNS.iter().all(|&spn| get_spn_def(spn).is_some()));
+
+        let pgns: Vec<u32> = list_supported_pgns().collect();
+        assert_eq!(SUPPORTED_PGNS, pgns);
+    }
+
+    #[test]
+    fn test_unit_ids() {
+        for spn_def in SPN_DEFINITIONS {
+            let id = unit_id(spn_def.unit).unwrap();
+            assert_eq!(unit_str(id), Some(spn_def.unit));
+        }
+        // Ids are part of the FFI surface and must not move
+        assert_eq!(unit_id(""), Some(0));
+        assert_eq!(unit_id("C"), Some(3));
+        assert_eq!(unit_str(UNITS.len() as u16), None);
+
+        // Precomputed ids line up with the PGN's SPNs
+        for pgn in list_supported_pgns() {
+            let spns = get_spns_for_pgn(pgn).unwrap();
+            let ids: Vec<_> = spns.iter().map(|s| unit_id(s.unit).unwrap()).collect();
+            assert_eq!(unit_ids_for_pgn(pgn), ids, "PGN {}", pgn);
+        }
+        assert!(unit_ids_for_pgn(0xFF00).is_empty());
+    }
+
+    #[test]
+    fn test_spn_state_names() {
+        // One name per raw code of the field
+        for &(spn, names) in SPN_STATE_NAMES {
+            let spn_def = get_spn_def(spn).unwrap();
+            assert_eq!(names.len(), 1 << spn_def.bit_length, "SPN {}", spn);
+        }
+
+        assert_eq!(spn_state_name(976, 0), Some("Off/Disabled"));
+        assert_eq!(spn_state_name(976, 19), Some("PTO Set Speed Memory 2"));
+        assert_eq!(spn_state_name(976, 20), Some("Reserved"));
+        assert_eq!(spn_state_name(976, 32), None);
+        assert_eq!(spn_state_name(976, u64::MAX), None);
+    }
+
+    #[test]
+    fn test_list_supported_pgns_sorted_and_unique() {
+        let pgns: Vec<_> = list_supported_pgns().collect();
+        assert!(pgns.windows(2).all(|w| w[0] < w[1]), "not strictly sorted");
+        assert_eq!(pgns.len(), database_stats().0);
+
+        let mut expected: Vec<u32> = SPN_DEFINITIONS.iter().map(|s| s.pgn).collect();
+        expected.sort_unstable();
+        expected.dedup();
+        assert_eq!(pgns, expected);
+    }
 }
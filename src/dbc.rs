@@ -0,0 +1,239 @@
+//! DBC export of the SPN database.
+//!
+//! Emits the built-in database as a Vector CANdb++ compatible `.dbc` file so
+//! it can be cross-checked against existing tooling. One `BO_` message per PGN,
+//! one `SG_` signal per SPN.
+
+use std::fmt::Write;
+
+use crate::database::{get_pgn_info, get_spns_for_pgn, list_supported_pgns};
+use crate::frame::{GLOBAL_ADDRESS, NULL_ADDRESS};
+use crate::types::{J1939Id, SpnDef};
+
+/// Priority used for the CAN ID of exported messages.
+const DBC_PRIORITY: u8 = 6;
+
+/// Source address used for the CAN ID of exported messages (null address).
+const DBC_SOURCE_ADDRESS: u8 = NULL_ADDRESS;
+
+/// Bit 31 marks an extended (29-bit) CAN ID in DBC files.
+const DBC_EXTENDED_FLAG: u32 = 0x8000_0000;
+
+/// Receiver / transmitter placeholder node.
+const DBC_NO_NODE: &str = "Vector__XXX";
+
+/// Export the whole database as DBC text.
+///
+/// Each message uses priority 6 and source address 0xFE. Signal start bits,
+/// lengths, scale and offset match exactly what the decoder reads; the SPN
+/// number is attached to each signal as a comment.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::dbc::export_dbc;
+///
+/// let dbc = export_dbc();
+/// assert!(dbc.contains("BO_ 2565866750 EEC1: 8 Vector__XXX"));
+/// assert!(dbc.contains(" SG_ engine_speed : 24|16@1+ (0.125,0) [0|8191.625] \"RPM\" Vector__XXX"));
+/// ```
+pub fn export_dbc() -> String {
+    let mut out = String::new();
+    out.push_str("VERSION \"\"\n\nNS_ :\n\nBS_:\n\nBU_:\n\n");
+
+    let mut comments = String::new();
+    for pgn in list_supported_pgns() {
+        let message_id = dbc_message_id(pgn);
+        let _ = writeln!(
+            out,
+            "BO_ {} {}: 8 {}",
+            message_id,
+            message_name(pgn),
+            DBC_NO_NODE
+        );
+
+        let spn_defs = get_spns_for_pgn(pgn).unwrap_or(&[]);
+        // DBC supports a single multiplexor signal per message
+        if let Some(mux_byte) = spn_defs.iter().find_map(|def| def.mux_byte) {
+            let _ = writeln!(
+                out,
+                " SG_ mux_byte{} M : {}|8@1+ (1,0) [0|255] \"\" {}",
+                mux_byte,
+                mux_byte as u32 * 8,
+                DBC_NO_NODE
+            );
+        }
+
+        for spn_def in spn_defs {
+            out.push_str(&signal_line(spn_def));
+            let _ = writeln!(
+                comments,
+                "CM_ SG_ {} {} \"SPN {}\";",
+                message_id, spn_def.name, spn_def.spn
+            );
+        }
+        out.push('\n');
+    }
+
+    out.push_str(&comments);
+    out
+}
+
+/// `SG_` line for an SPN, including the trailing newline.
+fn signal_line(spn_def: &SpnDef) -> String {
+    let (start_bit, length) = signal_layout(spn_def);
+    let sign = if spn_def.data_type.is_signed() {
+        '-'
+    } else {
+        '+'
+    };
+    let mux = match (spn_def.mux_byte, spn_def.mux_value) {
+        (Some(_), Some(value)) => format!(" m{}", value),
+        _ => String::new(),
+    };
+    let (min, max) = spn_def.value_range();
+    format!(
+        " SG_ {}{} : {}|{}@1{} ({},{}) [{}|{}] \"{}\" {}\n",
+        spn_def.name,
+        mux,
+        start_bit,
+        length,
+        sign,
+        spn_def.scale,
+        spn_def.offset,
+        min,
+        max,
+        spn_def.unit,
+        DBC_NO_NODE
+    )
+}
+
+/// DBC message ID (extended CAN ID with bit 31 set) for a PGN.
+fn dbc_message_id(pgn: u32) -> u32 {
+    let id = J1939Id {
+        pgn,
+        priority: DBC_PRIORITY,
+        source_address: DBC_SOURCE_ADDRESS,
+        destination_address: GLOBAL_ADDRESS,
+    };
+    DBC_EXTENDED_FLAG | id.to_can_id()
+}
+
+/// DBC identifier for a PGN: its acronym (e.g. `EFL_P1`) or `PGN_<n>`.
+fn message_name(pgn: u32) -> String {
+    match get_pgn_info(pgn) {
+        Some(info) => info
+            .acronym
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect(),
+        None => format!("PGN_{}", pgn),
+    }
+}
+
+/// Intel (little-endian) start bit and length, as read by the decoder.
+fn signal_layout(spn_def: &SpnDef) -> (u32, u32) {
+    (
+        spn_def.start_byte as u32 * 8 + spn_def.start_bit as u32,
+        spn_def.effective_bits() as u32,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::SPN_DEFINITIONS;
+    use crate::decoder::decode_spn;
+
+    /// Parsed `SG_` line: (name, start bit, length, signed, scale, offset).
+    fn parse_signal(line: &str) -> (String, u32, u32, bool, f64, f64) {
+        let line = line.trim_start().strip_prefix("SG_ ").unwrap();
+        let (name, rest) = line.split_once(" : ").unwrap();
+        // Drop the multiplexer indicator, e.g. "name m1"
+        let name = name.split(' ').next().unwrap();
+        let (start, rest) = rest.split_once('|').unwrap();
+        let (length, rest) = rest.split_once('@').unwrap();
+        let signed = rest.as_bytes()[1] == b'-';
+        let factors = &rest[rest.find('(').unwrap() + 1..rest.find(')').unwrap()];
+        let (scale, offset) = factors.split_once(',').unwrap();
+        (
+            name.to_string(),
+            start.parse().unwrap(),
+            length.parse().unwrap(),
+            signed,
+            scale.parse().unwrap(),
+            offset.parse().unwrap(),
+        )
+    }
+
+    /// Generic Intel signal extraction, independent of the decoder.
+    fn extract_intel(data: &[u8; 8], start: u32, length: u32) -> u64 {
+        let frame = u64::from_le_bytes(*data);
+        (frame >> start) & ((1u64 << length) - 1)
+    }
+
+    #[test]
+    fn test_export_dbc_messages() {
+        let dbc = export_dbc();
+        assert!(dbc.starts_with("VERSION \"\""));
+
+        let messages = dbc.lines().filter(|l| l.starts_with("BO_ ")).count();
+        assert_eq!(messages, list_supported_pgns().count());
+
+        // EEC1: 0x18F004FE | 0x80000000
+        assert!(dbc.contains("BO_ 2565866750 EEC1: 8 Vector__XXX"));
+        // Non-identifier characters in acronyms are replaced
+        assert!(dbc.contains(" EFL_P1: 8 "));
+    }
+
+    #[test]
+    fn test_export_dbc_signals() {
+        let dbc = export_dbc();
+        // One signal per SPN, plus the selector of each multiplexed message
+        let signals = dbc.lines().filter(|l| l.starts_with(" SG_ ")).count();
+        let selectors = dbc.lines().filter(|l| l.contains(" M : ")).count();
+        assert_eq!(signals - selectors, SPN_DEFINITIONS.len());
+
+        // Bit field: SPN 559, 2 bits at byte 0 bit 2
+        assert!(dbc.contains(" SG_ accelerator_pedal_kickdown : 2|2@1+ (1,0) [0|3] \"\""));
+        // Offset and range: coolant temperature
+        assert!(dbc.contains(
+            " SG_ engine_coolant_temperature : 0|8@1+ (1,-40) [-40|213] \"C\" Vector__XXX"
+        ));
+        assert!(dbc.contains("CM_ SG_ 2565866750 engine_speed \"SPN 190\";"));
+    }
+
+    #[test]
+    fn test_export_dbc_matches_decoder() {
+        let dbc = export_dbc();
+        let data = [0x35, 0x1A, 0x72, 0x20, 0x4E, 0x11, 0x42, 0x63];
+
+        for line in dbc
+            .lines()
+            .filter(|l| l.starts_with(" SG_ ") && !l.contains(" M : "))
+        {
+            let (name, start, length, signed, scale, offset) = parse_signal(line);
+            let spn_def = SPN_DEFINITIONS.iter().find(|s| s.name == name).unwrap();
+            assert!(!signed);
+
+            let raw = extract_intel(&data, start, length);
+            match decode_spn(&data, spn_def) {
+                Some(expected) => {
+                    let value = (raw as f64).mul_add(scale, offset);
+                    assert!((value - expected).abs() < 1e-9, "Mismatch for {}", name);
+                }
+                None => assert!(raw > spn_def.max_valid_raw(), "Mismatch for {}", name),
+            }
+        }
+    }
+
+    #[test]
+    fn test_signal_line_multiplexed() {
+        let spn_def = SpnDef {
+            mux_byte: Some(0),
+            mux_value: Some(3),
+            ..*SPN_DEFINITIONS.iter().find(|s| s.spn == 110).unwrap()
+        };
+        assert!(signal_line(&spn_def).starts_with(" SG_ engine_coolant_temperature m3 : 0|8@1+ "));
+    }
+}
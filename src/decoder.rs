@@ -2,9 +2,21 @@
 //!
 //! Provides utilities for decoding SPN values from CAN frame data.
 
-use crate::database::{get_spn_def, get_spns_for_pgn};
-use crate::frame::extract_pgn;
-use crate::types::{DecodedSpn, SpnDataType, SpnDef};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::database::{
+    expected_dlc, get_pgn_info, get_spn_def, get_spns_for_pgn, unit_ids_for_pgn, Database,
+    FRAME_LEN,
+};
+use crate::frame::{
+    extract_pgn, extract_source_address, is_transport_pgn, is_valid_j1939_id, parse_can_id,
+};
+use crate::types::{
+    BitNumbering, ByteOrder, DecodeError, DecodedSpn, DecodedSpnC, DecodedSpnRef, DecodedSpnStatus,
+    FrameDecode, J1939Id, ParseError, PartialDecodedSpn, SourcedDecodedSpn, SpnDataType, SpnDef,
+    SpnStatus, SwitchState, TempUnit, TimedDecodedSpn,
+};
 
 /// Precomputed "not available" thresholds for each bit length (0-64).
 /// Computed at compile time via const fn - zero runtime overhead.
@@ -39,22 +51,134 @@ const _: () = {
 /// Hot path: inlined for maximum performance.
 #[inline(always)]
 fn extract_and_validate(data: &[u8], spn_def: &SpnDef) -> Option<(u64, f64)> {
-    let raw_value = extract_raw_value(data, spn_def)?;
+    validate_raw(extract_raw_value(data, spn_def)?, spn_def)
+}
 
-    // Check for "not available" values using precomputed lookup table
-    // bit_length is guaranteed to be <= 64 based on SpnDataType
-    let threshold = NOT_AVAILABLE_THRESHOLD[spn_def.bit_length as usize];
-    if raw_value > threshold {
+/// Check a raw value for special codes and scale it.
+/// Returns (raw_value, scaled_value) if valid.
+#[inline(always)]
+fn validate_raw(raw_value: u64, spn_def: &SpnDef) -> Option<(u64, f64)> {
+    if !is_reportable(raw_value, spn_def) {
         return None;
     }
 
-    let value = (raw_value as f64).mul_add(spn_def.scale, spn_def.offset);
+    let value = raw_as_f64(raw_value, spn_def).mul_add(spn_def.scale, spn_def.offset);
     Some((raw_value, value))
 }
 
+/// Check that a raw value is not a special code the decoder drops (integer only).
+#[inline(always)]
+fn is_reportable(raw_value: u64, spn_def: &SpnDef) -> bool {
+    // Check for "not available" values using precomputed lookup table
+    // Effective bits are clamped to the data type width, so a malformed or
+    // oversized bit_length (64+) can neither index out of bounds nor overflow.
+    let threshold = NOT_AVAILABLE_THRESHOLD[spn_def.effective_bits() as usize];
+    spn_def.reports_all_states || special_code_bits(raw_value, spn_def) <= threshold
+}
+
+/// Build a [`DecodedSpn`] from a validated value.
+#[inline(always)]
+fn make_decoded(spn_def: &SpnDef, raw_value: u64, value: f64) -> DecodedSpn {
+    DecodedSpn {
+        spn: spn_def.spn,
+        instance: spn_def.instance,
+        name: spn_def.name,
+        value,
+        unit: spn_def.unit,
+        raw_value,
+        scale: spn_def.scale,
+    }
+}
+
+/// Bits compared against the special codes.
+/// Signed raw values are sign-extended, so they are masked back to the field width:
+/// like unsigned SPNs, signed SPNs reserve the top two unsigned bit patterns,
+/// i.e. -1 ("not available") and -2 (error).
+#[inline(always)]
+fn special_code_bits(raw_value: u64, spn_def: &SpnDef) -> u64 {
+    if spn_def.data_type.is_signed() {
+        raw_value & bit_mask(spn_def.effective_bits())
+    } else {
+        raw_value
+    }
+}
+
+/// Numeric value of a raw value, honoring the sign of signed types.
+#[inline(always)]
+fn raw_as_f64(raw_value: u64, spn_def: &SpnDef) -> f64 {
+    if spn_def.data_type.is_signed() {
+        raw_value as i64 as f64
+    } else {
+        raw_value as f64
+    }
+}
+
+/// Split a finite `f64` into `(mantissa, exponent)` with `x = mantissa * 2^exponent`.
+/// Integer-only: reads the IEEE 754 bit pattern. Returns `None` for NaN/infinity.
+#[inline]
+fn f64_parts(x: f64) -> Option<(i128, i32)> {
+    let bits = x.to_bits();
+    let biased_exp = ((bits >> 52) & 0x7FF) as i32;
+    let fraction = (bits & ((1u64 << 52) - 1)) as i128;
+    let (mantissa, exponent) = match biased_exp {
+        0x7FF => return None,
+        0 => (fraction, -1074), // Subnormal (or zero)
+        _ => (fraction | (1i128 << 52), biased_exp - 1075),
+    };
+    Some((if bits >> 63 == 1 { -mantissa } else { mantissa }, exponent))
+}
+
+/// `n * 2^exp` rounded to the nearest integer (halves round up).
+/// Returns `None` on overflow.
+#[inline]
+fn mul_pow2_round(n: i128, exp: i32) -> Option<i128> {
+    if exp >= 0 {
+        if exp >= n.unsigned_abs().leading_zeros() as i32 - 1 && n != 0 {
+            return None;
+        }
+        return Some(n << exp.min(127));
+    }
+    let shift = exp.unsigned_abs();
+    if shift >= 127 {
+        return Some(0);
+    }
+    Some((n + (1i128 << (shift - 1))) >> shift)
+}
+
+/// Mask with the lowest `bits` bits set (saturates at 64 bits).
+#[inline(always)]
+pub(crate) const fn bit_mask(bits: u8) -> u64 {
+    match 1u64.checked_shl(bits as u32) {
+        Some(m) => m - 1,
+        None => u64::MAX,
+    }
+}
+
+/// Classify a raw value against the J1939 special codes for its bit length.
+///
+/// Uses the same check as the decoding hot path; values it drops are
+/// split into "error" (2^N - 2) and "not available" (2^N - 1) by looking at
+/// the bit pattern within the field width.
+#[inline]
+fn classify_raw(raw_value: u64, spn_def: &SpnDef) -> SpnStatus {
+    if is_reportable(raw_value, spn_def) {
+        return SpnStatus::Valid;
+    }
+
+    let mask = bit_mask(spn_def.effective_bits());
+    if raw_value & mask == mask {
+        SpnStatus::NotAvailable
+    } else {
+        SpnStatus::Error
+    }
+}
+
 /// Decode a single SPN from CAN data bytes.
 ///
 /// Returns `None` if the data is too short or the value indicates "not available".
+/// This includes the error and "not available" states of 2-bit switches, unless
+/// the SPN sets [`reports_all_states`](SpnDef::reports_all_states); use
+/// [`decode_spn_status`] or [`decode_switch`] to tell them apart.
 /// Hot path: always inlined.
 ///
 /// # Example
@@ -96,19 +220,317 @@ pub fn decode_spn(data: &[u8], spn_def: &SpnDef) -> Option<f64> {
 #[inline(always)]
 pub fn decode_spn_full(data: &[u8], spn_def: &SpnDef) -> Option<DecodedSpn> {
     let (raw_value, value) = extract_and_validate(data, spn_def)?;
-    Some(DecodedSpn {
-        spn: spn_def.spn,
-        name: spn_def.name,
+    Some(make_decoded(spn_def, raw_value, value))
+}
+
+/// Decode a single SPN, keeping a reference to its definition.
+///
+/// Returns `None` in the same cases as [`decode_spn`].
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::decode_spn_ref;
+/// use voltage_j1939::database::get_spn_def;
+///
+/// let data = [0, 0, 0, 0x20, 0x4E, 0, 0, 0];
+/// let decoded = decode_spn_ref(&data, get_spn_def(190).unwrap()).unwrap();
+/// assert_eq!(decoded.value, 2500.0);
+/// assert_eq!(decoded.spn_def.start_byte, 3);
+/// ```
+#[inline]
+pub fn decode_spn_ref(data: &[u8], spn_def: &'static SpnDef) -> Option<DecodedSpnRef> {
+    let (raw_value, value) = extract_and_validate(data, spn_def)?;
+    Some(DecodedSpnRef {
         value,
-        unit: spn_def.unit,
         raw_value,
+        spn_def,
+    })
+}
+
+/// Decode a single SPN with a byte order chosen by the caller.
+///
+/// For one-off decoding of devices that send multi-byte values most
+/// significant byte first, without changing the shared database.
+/// With [`ByteOrder::BigEndian`] the bytes covered by the field are reversed
+/// before decoding, and `start_bit` / `bit_length` apply to the swapped
+/// bytes. Single-byte fields decode the same in either order.
+///
+/// Returns `None` in the same cases as [`decode_spn`].
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::decode_spn_with_order;
+/// use voltage_j1939::database::get_spn_def;
+/// use voltage_j1939::types::ByteOrder;
+///
+/// let spn_def = get_spn_def(190).unwrap(); // Engine speed, bytes 3-4
+/// let swapped = [0xFF, 0xFF, 0xFF, 0x4E, 0x20, 0xFF, 0xFF, 0xFF];
+/// assert_eq!(decode_spn_with_order(&swapped, spn_def, ByteOrder::BigEndian), Some(2500.0));
+/// ```
+#[inline]
+pub fn decode_spn_with_order(data: &[u8], spn_def: &SpnDef, order: ByteOrder) -> Option<f64> {
+    match order {
+        ByteOrder::LittleEndian => decode_spn(data, spn_def),
+        ByteOrder::BigEndian => {
+            let field = data.get(spn_def.start_byte as usize..spn_def.required_len())?;
+            // A field spans at most 5 bytes (start_bit 7 + 32 bits)
+            let mut buf = [0u8; 8];
+            let swapped = &mut buf[..field.len()];
+            swapped.copy_from_slice(field);
+            swapped.reverse();
+            let local = SpnDef {
+                start_byte: 0,
+                ..*spn_def
+            };
+            decode_spn(swapped, &local)
+        }
+    }
+}
+
+/// Decode a single SPN as single precision, for compact storage.
+///
+/// The value is computed in `f64` like [`decode_spn`] and rounded once at
+/// the end. Returns `None` in the same cases as [`decode_spn`].
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::decode_spn_f32;
+/// use voltage_j1939::database::get_spn_def;
+///
+/// let data = [0, 0, 0, 0x21, 0x4E, 0, 0, 0]; // Engine speed raw 20001
+/// assert_eq!(decode_spn_f32(&data, get_spn_def(190).unwrap()), Some(2500.125));
+/// ```
+#[inline]
+pub fn decode_spn_f32(data: &[u8], spn_def: &SpnDef) -> Option<f32> {
+    decode_spn(data, spn_def).map(|value| value as f32)
+}
+
+/// Decode a single SPN, converting temperatures to `unit`.
+///
+/// Only temperature SPNs ([`SpnDef::is_temperature`]) are converted; any
+/// other SPN is returned unchanged in its own unit. Returns `None` in the
+/// same cases as [`decode_spn`].
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::decode_spn_temp;
+/// use voltage_j1939::database::get_spn_def;
+/// use voltage_j1939::types::TempUnit;
+///
+/// let coolant = get_spn_def(110).unwrap(); // raw 130 = 90 C
+/// assert_eq!(decode_spn_temp(&[130], coolant, TempUnit::Kelvin), Some(363.15));
+///
+/// let speed = get_spn_def(190).unwrap(); // Not a temperature: RPM as is
+/// let data = [0, 0, 0, 0x20, 0x4E, 0, 0, 0];
+/// assert_eq!(decode_spn_temp(&data, speed, TempUnit::Kelvin), Some(2500.0));
+/// ```
+#[inline]
+pub fn decode_spn_temp(data: &[u8], spn_def: &SpnDef, unit: TempUnit) -> Option<f64> {
+    let value = decode_spn(data, spn_def)?;
+    if spn_def.is_temperature() {
+        Some(unit.convert_celsius(value))
+    } else {
+        Some(value)
+    }
+}
+
+/// Decode a single SPN as a fixed-point value in milli-units (value × 1000).
+///
+/// Uses integer arithmetic only, for targets without an FPU: `scale` and
+/// `offset` are split into their exact binary mantissa and exponent, so
+/// binary fractions like 0.125 are exact and decimal scales like 0.05 are
+/// rounded to the nearest milli-unit. Falls back to the float path if the
+/// result does not fit.
+///
+/// Returns `None` in the same cases as [`decode_spn`].
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::decode_spn_milli;
+/// use voltage_j1939::database::get_spn_def;
+///
+/// // Engine speed raw 20001 * 0.125 = 2500.125 RPM
+/// let data = [0, 0, 0, 0x21, 0x4E, 0, 0, 0];
+/// assert_eq!(decode_spn_milli(&data, get_spn_def(190).unwrap()), Some(2_500_125));
+///
+/// // Coolant temperature raw 130 - 40 = 90 C
+/// assert_eq!(decode_spn_milli(&[130], get_spn_def(110).unwrap()), Some(90_000));
+/// ```
+#[inline]
+pub fn decode_spn_milli(data: &[u8], spn_def: &SpnDef) -> Option<i64> {
+    let raw_value = extract_raw_value(data, spn_def)?;
+    if !is_reportable(raw_value, spn_def) {
+        return None;
+    }
+
+    // Sign-extended for signed types; unsigned fields are at most 32 bits
+    let raw = raw_value as i64 as i128;
+    let milli = f64_parts(spn_def.scale)
+        .zip(f64_parts(spn_def.offset))
+        .and_then(|((sm, se), (om, oe))| {
+            let scaled = mul_pow2_round(raw.checked_mul(sm)?.checked_mul(1000)?, se)?;
+            let offset = mul_pow2_round(om.checked_mul(1000)?, oe)?;
+            i64::try_from(scaled.checked_add(offset)?).ok()
+        });
+
+    milli.or_else(|| {
+        let value = raw_as_f64(raw_value, spn_def).mul_add(spn_def.scale, spn_def.offset);
+        Some((value * 1000.0).round() as i64)
     })
 }
 
+/// Extract `len` bits starting at absolute bit `start_bit` of `data`.
+///
+/// Uses J1939 (Intel, little-endian) bit order: bit 0 is the least
+/// significant bit of byte 0, bit 8 the least significant bit of byte 1.
+/// Fields may cross byte boundaries. No scaling or special-code handling is
+/// applied.
+///
+/// Returns `None` if `len` is 0 or greater than 64, or if the field extends
+/// past the end of `data`.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::extract_bits;
+///
+/// let data = [0x21, 0x43, 0x65];
+/// assert_eq!(extract_bits(&data, 12, 12), Some(0x654));
+/// assert_eq!(extract_bits(&data, 4, 4), Some(0x2));
+/// assert_eq!(extract_bits(&data, 16, 9), None); // Past the end
+/// ```
+#[inline]
+pub fn extract_bits(data: &[u8], start_bit: usize, len: usize) -> Option<u64> {
+    if len == 0 || len > 64 {
+        return None;
+    }
+    let end_bit = start_bit.checked_add(len)?;
+    let bytes = data.get(start_bit / 8..end_bit.div_ceil(8))?;
+
+    // Up to 9 bytes for an unaligned 64-bit field
+    let word = bytes
+        .iter()
+        .rev()
+        .fold(0u128, |acc, &byte| (acc << 8) | byte as u128);
+    Some((word >> (start_bit % 8)) as u64 & bit_mask(len as u8))
+}
+
+/// Extract `len` bits at `start_bit` of `data`, numbered per `numbering`.
+///
+/// [`BitNumbering::J1939Lsb`] is [`extract_bits`]. With
+/// [`BitNumbering::DbcSawtooth`], `start_bit` is the MSB of a big-endian
+/// (Motorola) signal as a DBC file gives it, so definitions imported from a
+/// DBC decode without recomputing start bits.
+///
+/// Returns `None` in the same cases as [`extract_bits`].
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::extract_bits_with;
+/// use voltage_j1939::types::BitNumbering;
+///
+/// let data = [0x12, 0x34, 0x56];
+/// // DBC "SG_ Sig : 7|16@0+": bytes 0-1, most significant byte first
+/// assert_eq!(extract_bits_with(&data, 7, 16, BitNumbering::DbcSawtooth), Some(0x1234));
+/// // "3|12@0+": low nibble of byte 0, then byte 1
+/// assert_eq!(extract_bits_with(&data, 3, 12, BitNumbering::DbcSawtooth), Some(0x234));
+/// assert_eq!(extract_bits_with(&data, 0, 16, BitNumbering::J1939Lsb), Some(0x3412));
+/// ```
+#[inline]
+pub fn extract_bits_with(
+    data: &[u8],
+    start_bit: usize,
+    len: usize,
+    numbering: BitNumbering,
+) -> Option<u64> {
+    match numbering {
+        BitNumbering::J1939Lsb => extract_bits(data, start_bit, len),
+        BitNumbering::DbcSawtooth => {
+            if len == 0 || len > 64 {
+                return None;
+            }
+            // Position of the MSB when counting from the MSB of byte 0
+            let msb = start_bit / 8 * 8 + 7 - start_bit % 8;
+            let end_bit = msb.checked_add(len)?;
+            let bytes = data.get(msb / 8..end_bit.div_ceil(8))?;
+
+            let word = bytes
+                .iter()
+                .fold(0u128, |acc, &byte| (acc << 8) | byte as u128);
+            let shift = bytes.len() * 8 - msb % 8 - len;
+            Some((word >> shift) as u64 & bit_mask(len as u8))
+        }
+    }
+}
+
+/// Read a little-endian unsigned integer of `num_bytes` bytes at `start_byte`.
+///
+/// Returns `None` if `num_bytes` is 0 or greater than 8, or if the bytes
+/// extend past the end of `data`. Byte-aligned counterpart of
+/// [`extract_bits`] for PGNs decoded by hand.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::read_le_uint;
+///
+/// let data = [0xFF, 0xFF, 0xFF, 0x20, 0x4E, 0x01, 0xFF, 0xFF];
+/// assert_eq!(read_le_uint(&data, 3, 2), Some(0x4E20));
+/// assert_eq!(read_le_uint(&data, 3, 3), Some(0x01_4E20));
+/// assert_eq!(read_le_uint(&data, 7, 2), None); // Past the end
+/// ```
+#[inline]
+pub fn read_le_uint(data: &[u8], start_byte: usize, num_bytes: usize) -> Option<u64> {
+    if num_bytes == 0 || num_bytes > 8 {
+        return None;
+    }
+    let bytes = data.get(start_byte..start_byte.checked_add(num_bytes)?)?;
+    Some(
+        bytes
+            .iter()
+            .rev()
+            .fold(0u64, |acc, &byte| (acc << 8) | byte as u64),
+    )
+}
+
+/// Read a little-endian two's complement integer of `num_bytes` bytes at
+/// `start_byte`, sign-extended to `i64`.
+///
+/// Returns `None` in the same cases as [`read_le_uint`].
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::read_le_int;
+///
+/// assert_eq!(read_le_int(&[0xD4, 0xFE], 0, 2), Some(-300));
+/// assert_eq!(read_le_int(&[0xFF, 0x7F, 0x80], 0, 3), Some(-8_355_841));
+/// assert_eq!(read_le_int(&[0x2C, 0x01], 0, 2), Some(300));
+/// ```
+#[inline]
+pub fn read_le_int(data: &[u8], start_byte: usize, num_bytes: usize) -> Option<i64> {
+    let raw = read_le_uint(data, start_byte, num_bytes)?;
+    let unused = 64 - num_bytes as u32 * 8;
+    Some(((raw << unused) as i64) >> unused)
+}
+
 /// Decode all known SPNs from a CAN frame (zero-allocation iterator).
 ///
-/// This is the preferred method for performance-critical code as it
-/// avoids heap allocation entirely. Always inlined for maximum performance.
+/// This is the preferred method for performance-critical and embedded code:
+/// the iterator borrows `data` and walks the PGN's static SPN table, so no
+/// heap allocation happens. [`decode_frame`] is this iterator collected
+/// into a `Vec`.
+///
+/// Yields one [`DecodedSpn`] per valid SPN, in database definition order.
+/// SPNs that are "not available", in error, truncated, or multiplexed out
+/// by the frame's selector byte are skipped; use [`decode_frame_all`] to see
+/// them.
 ///
 /// # Arguments
 ///
@@ -124,25 +546,46 @@ pub fn decode_spn_full(data: &[u8], spn_def: &SpnDef) -> Option<DecodedSpn> {
 /// ```
 /// use voltage_j1939::decoder::decode_frame_iter;
 ///
+/// // EEC1: engine speed 2500 RPM, other fields "not available"
 /// let can_id = 0x0CF00400u32;
-/// let data = [0x00, 0x00, 0x00, 0x20, 0x4E, 0x00, 0x00, 0x00];
+/// let data = [0xFF, 0xFF, 0xFF, 0x20, 0x4E, 0xFF, 0xFF, 0xFF];
 ///
+/// let mut count = 0;
 /// for spn in decode_frame_iter(can_id, &data) {
-///     println!("{}: {} {}", spn.name, spn.value, spn.unit);
+///     assert_eq!(spn.name, "engine_speed");
+///     assert_eq!(spn.value, 2500.0);
+///     count += 1;
 /// }
+/// assert_eq!(count, 1);
+///
+/// // Unknown PGN: empty iterator
+/// assert_eq!(decode_frame_iter(0x18FF0000, &data).count(), 0);
 /// ```
 #[inline(always)]
-pub fn decode_frame_iter(
-    can_id: u32,
-    data: &[u8],
-) -> impl Iterator<Item = DecodedSpn> + '_ {
-    let pgn = extract_pgn(can_id);
-    get_spns_for_pgn(pgn)
-        .into_iter()
-        .flatten()
+pub fn decode_frame_iter(can_id: u32, data: &[u8]) -> impl Iterator<Item = DecodedSpn> + '_ {
+    present_spns(extract_pgn(can_id), data)
         .filter_map(move |spn_def| decode_spn_full(data, spn_def))
 }
 
+/// Decode all known SPNs from a CAN frame, keeping a reference to each definition.
+///
+/// Same values as [`decode_frame_iter`], as [`DecodedSpnRef`]s.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::decode_frame_ref;
+///
+/// let data = [0xFF, 0xFF, 0xFF, 0x20, 0x4E, 0xFF, 0xFF, 0xFF];
+/// for spn in decode_frame_ref(0x0CF00400, &data) {
+///     println!("{} = {} (scale {})", spn.name(), spn.value, spn.spn_def.scale);
+/// }
+/// ```
+#[inline]
+pub fn decode_frame_ref(can_id: u32, data: &[u8]) -> impl Iterator<Item = DecodedSpnRef> + '_ {
+    present_spns(extract_pgn(can_id), data).filter_map(move |spn_def| decode_spn_ref(data, spn_def))
+}
+
 /// Decode all known SPNs from a CAN frame.
 ///
 /// For zero-allocation iteration, use [`decode_frame_iter`] instead.
@@ -176,386 +619,2896 @@ pub fn decode_frame(can_id: u32, data: &[u8]) -> Vec<DecodedSpn> {
     decode_frame_iter(can_id, data).collect()
 }
 
-/// Decode a specific SPN by number from a CAN frame.
-/// Always inlined for direct SPN decoding hot path.
+/// Decode a CAN frame, using a custom handler from `database` when one is
+/// registered for its PGN.
 ///
-/// # Arguments
+/// Falls back to [`decode_frame`] for every other PGN. See
+/// [`Database::register_pgn_handler`].
 ///
-/// * `spn` - The SPN number to decode
-/// * `data` - The CAN frame data
+/// # Example
 ///
-/// # Returns
+/// ```
+/// use voltage_j1939::database::Database;
+/// use voltage_j1939::decoder::decode_frame_with;
 ///
-/// The decoded value, or `None` if the SPN is not found or data is invalid.
-#[inline(always)]
-pub fn decode_spn_by_number(spn: u32, data: &[u8]) -> Option<f64> {
-    decode_spn(data, get_spn_def(spn)?)
+/// let db = Database::new();
+/// let data = [0x00, 0x00, 0x00, 0x20, 0x4E, 0x00, 0x00, 0x00];
+/// assert_eq!(decode_frame_with(&db, 0x0CF00400, &data)[0].spn, 899);
+/// ```
+#[inline]
+pub fn decode_frame_with(database: &Database, can_id: u32, data: &[u8]) -> Vec<DecodedSpn> {
+    match database.pgn_handler(extract_pgn(can_id)) {
+        Some(handler) => handler(data),
+        None => decode_frame(can_id, data),
+    }
 }
 
-// ============================================================================
-// Internal helpers - optimized for minimal branching
-// ============================================================================
-
-/// Extract raw value from data bytes based on SPN definition.
-/// Uses unsafe get_unchecked after bounds check for optimal codegen.
-/// Hot path: always inlined for zero function call overhead.
-#[inline(always)]
-fn extract_raw_value(data: &[u8], spn_def: &SpnDef) -> Option<u64> {
-    let start = spn_def.start_byte as usize;
-    let required_len = start + spn_def.data_type.byte_size();
+/// Decode all known SPNs from a CAN frame whose ID was already parsed.
+///
+/// Same result as [`decode_frame`], using `id.pgn` directly, for callers
+/// that parse the CAN ID once for routing or filtering.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::decode_frame_with_id;
+/// use voltage_j1939::frame::parse_can_id;
+///
+/// let id = parse_can_id(0x0CF00400);
+/// if id.source_address == 0x00 {
+///     let decoded = decode_frame_with_id(&id, &[0xFF, 0xFF, 0xFF, 0x20, 0x4E, 0xFF, 0xFF, 0xFF]);
+///     assert_eq!(decoded[0].value, 2500.0);
+/// }
+/// ```
+#[inline]
+pub fn decode_frame_with_id(id: &J1939Id, data: &[u8]) -> Vec<DecodedSpn> {
+    decode_pgn(id.pgn, data)
+}
 
-    // Single bounds check - enables optimizer to remove bounds checks below
-    if data.len() < required_len {
-        return None;
-    }
+/// Decode all known SPNs from the payload of a PGN, without a CAN ID.
+///
+/// For sources that deliver (PGN, payload) pairs, such as gateways or DBC
+/// tools. Same result as [`decode_frame`] for a CAN ID carrying `pgn`.
+/// PDU1 PGNs are given with the destination byte cleared (e.g. 0xEA00).
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::decode_pgn;
+///
+/// let decoded = decode_pgn(61444, &[0xFF, 0xFF, 0xFF, 0x20, 0x4E, 0xFF, 0xFF, 0xFF]); // EEC1
+/// assert_eq!(decoded[0].value, 2500.0);
+/// assert!(decode_pgn(0xFF00, &[0; 8]).is_empty()); // Unknown PGN
+/// ```
+#[inline]
+pub fn decode_pgn(pgn: u32, data: &[u8]) -> Vec<DecodedSpn> {
+    present_spns(pgn, data)
+        .filter_map(|spn_def| decode_spn_full(data, spn_def))
+        .collect()
+}
 
-    // SAFETY: We verified data.len() >= start + byte_size above
-    let val = unsafe {
-        match spn_def.data_type {
-            SpnDataType::Uint8 => {
-                let byte = *data.get_unchecked(start);
-                if spn_def.bit_length == 8 && spn_def.start_bit == 0 {
-                    byte as u64
-                } else {
-                    // Bit field extraction
-                    let mask = (1u8 << spn_def.bit_length).wrapping_sub(1);
-                    ((byte >> spn_def.start_bit) & mask) as u64
-                }
-            }
-            SpnDataType::Uint16 => {
-                let ptr = data.as_ptr().add(start) as *const [u8; 2];
-                u16::from_le_bytes(*ptr) as u64
-            }
-            SpnDataType::Uint32 => {
-                let ptr = data.as_ptr().add(start) as *const [u8; 4];
-                u32::from_le_bytes(*ptr) as u64
-            }
-            SpnDataType::Int8 => *data.get_unchecked(start) as i8 as u64,
-            SpnDataType::Int16 => {
-                let ptr = data.as_ptr().add(start) as *const [u8; 2];
-                i16::from_le_bytes(*ptr) as u64
-            }
-            SpnDataType::Int32 => {
-                let ptr = data.as_ptr().add(start) as *const [u8; 4];
-                i32::from_le_bytes(*ptr) as u64
-            }
+/// Decode all known SPNs from a CAN frame, rejecting frames of the wrong length.
+///
+/// Strict variant of [`decode_frame`]: if the PGN has a fixed
+/// [`expected_dlc`] and `data.len()` differs, the frame is rejected instead
+/// of silently decoding fewer SPNs. Unknown and variable-length PGNs skip
+/// the check.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::decode_frame_strict;
+/// use voltage_j1939::DecodeError;
+///
+/// let err = decode_frame_strict(0x0CF00400, &[0, 0, 0, 0x20, 0x4E]).unwrap_err();
+/// assert_eq!(err, DecodeError::LengthMismatch { pgn: 61444, expected: 8, actual: 5 });
+///
+/// let ok = decode_frame_strict(0x0CF00400, &[0, 0, 0, 0x20, 0x4E, 0, 0, 0]).unwrap();
+/// assert!(!ok.is_empty());
+/// ```
+pub fn decode_frame_strict(can_id: u32, data: &[u8]) -> Result<Vec<DecodedSpn>, DecodeError> {
+    let pgn = extract_pgn(can_id);
+    if let Some(expected) = expected_dlc(pgn) {
+        if data.len() != expected as usize {
+            return Err(DecodeError::LengthMismatch {
+                pgn,
+                expected,
+                actual: data.len(),
+            });
         }
-    };
-
-    Some(val)
+    }
+    Ok(decode_frame(can_id, data))
 }
 
-#[cfg(test)]
-mod tests {
+/// Lazily decode a sequence of frames, e.g. a recorded capture.
+///
+/// Yields `(can_id, decoded SPNs)` per frame, skipping frames whose PGN is
+/// not in the database. Frames of a known PGN are yielded even if none of
+/// their SPNs are valid. Accepts any byte container (`Vec<u8>`, `[u8; 8]`, ...).
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::decode_frames;
+///
+/// let capture = vec![
+///     (0x0CF00400, vec![0xFF, 0xFF, 0xFF, 0x20, 0x4E, 0xFF, 0xFF, 0xFF]), // EEC1
+///     (0x18FF0000, vec![0x00; 8]),                                       // unknown
+/// ];
+///
+/// let decoded: Vec<_> = decode_frames(capture).collect();
+/// assert_eq!(decoded.len(), 1);
+/// assert_eq!(decoded[0].0, 0x0CF00400);
+/// assert_eq!(decoded[0].1[0].value, 2500.0);
+/// ```
+pub fn decode_frames<I, D>(frames: I) -> impl Iterator<Item = (u32, Vec<DecodedSpn>)>
+where
+    I: IntoIterator<Item = (u32, D)>,
+    D: AsRef<[u8]>,
+{
+    frames.into_iter().filter_map(|(can_id, data)| {
+        get_spns_for_pgn(extract_pgn(can_id))?;
+        Some((can_id, decode_frame(can_id, data.as_ref())))
+    })
+}
+
+/// Decode all known SPNs from a CAN frame, tagging each with `timestamp`.
+///
+/// Useful for building time series and measuring broadcast intervals. The
+/// timestamp is passed through unchanged, in whatever unit the caller uses.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::decode_frame_at;
+///
+/// let data = [0xFF, 0xFF, 0xFF, 0x20, 0x4E, 0xFF, 0xFF, 0xFF];
+/// let timed = decode_frame_at(0x0CF00400, &data, 1712.345);
+/// assert_eq!(timed[0].timestamp, 1712.345);
+/// assert_eq!(timed[0].spn.value, 2500.0);
+/// ```
+#[inline]
+pub fn decode_frame_at(can_id: u32, data: &[u8], timestamp: f64) -> Vec<TimedDecodedSpn> {
+    decode_frame_iter(can_id, data)
+        .map(|spn| TimedDecodedSpn { timestamp, spn })
+        .collect()
+}
+
+/// Decode all known SPNs from a CAN frame, tagging each with its source.
+///
+/// Each value carries the frame's PGN and source address, so values from
+/// several ECUs or PGNs can be merged and still told apart (e.g. to prefer
+/// one vehicle speed source over another).
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::decode_frame_with_source;
+///
+/// // CCVS from the body controller (0x21): wheel-based speed 80 km/h
+/// let data = [0xFF, 0x00, 0x50, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+/// let sourced = decode_frame_with_source(0x18FEF121, &data);
+/// let speed = sourced.iter().find(|s| s.spn.spn == 84).unwrap();
+/// assert_eq!(speed.pgn, 65265);
+/// assert_eq!(speed.source_address, 0x21);
+/// assert_eq!(speed.spn.value, 80.0);
+/// ```
+#[inline]
+pub fn decode_frame_with_source(can_id: u32, data: &[u8]) -> Vec<SourcedDecodedSpn> {
+    let pgn = extract_pgn(can_id);
+    let source_address = extract_source_address(can_id);
+    decode_frame_iter(can_id, data)
+        .map(|spn| SourcedDecodedSpn {
+            pgn,
+            source_address,
+            spn,
+        })
+        .collect()
+}
+
+/// Decode all known SPNs from the first `valid_len` bytes of a reused buffer.
+///
+/// For receive buffers sized for the largest frame (e.g. `[u8; 64]` for
+/// CAN FD) that are only partly filled: bytes past `valid_len` are left over
+/// from earlier frames and never treated as payload. SPNs that extend past
+/// the valid prefix are skipped, as in [`decode_frame`]. A `valid_len`
+/// larger than the buffer is clamped to its length.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::decode_frame_buffer;
+///
+/// let mut buf = [0x00u8; 64];
+/// buf[..5].copy_from_slice(&[0xFF, 0xFF, 0xFF, 0x20, 0x4E]);
+///
+/// // Only engine speed fits in the 5 valid bytes; stale zeros are ignored
+/// let decoded = decode_frame_buffer(0x0CF00400, &buf, 5);
+/// assert_eq!(decoded.len(), 1);
+/// assert_eq!(decoded[0].value, 2500.0);
+/// ```
+#[inline]
+pub fn decode_frame_buffer(can_id: u32, buf: &[u8], valid_len: usize) -> Vec<DecodedSpn> {
+    decode_frame(can_id, &buf[..valid_len.min(buf.len())])
+}
+
+/// Decode all known SPNs from a fixed 8-byte frame.
+///
+/// Same result as [`decode_frame`], but skips the per-SPN length checks:
+/// every SPN in the database is verified at compile time to fit in 8 bytes.
+/// Use the slice API for variable-length frames.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::decode_frame_array;
+///
+/// let data = [0xFF, 0xFF, 0xFF, 0x20, 0x4E, 0xFF, 0xFF, 0xFF];
+/// let decoded = decode_frame_array(0x0CF00400, &data);
+/// assert_eq!(decoded[0].value, 2500.0);
+/// ```
+#[inline]
+pub fn decode_frame_array(can_id: u32, data: &[u8; 8]) -> Vec<DecodedSpn> {
+    let mut out = Vec::new();
+    decode_frame_array_into(can_id, data, &mut out);
+    out
+}
+
+/// Decode all known SPNs from a fixed 8-byte frame, appending to `out`.
+///
+/// Lets a caller reuse one buffer across frames (clear it between calls).
+/// Returns the number of SPNs appended.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::decode_frame_array_into;
+///
+/// let mut buf = Vec::with_capacity(16);
+/// for data in [[0xFF, 0xFF, 0xFF, 0x20, 0x4E, 0xFF, 0xFF, 0xFF]; 3] {
+///     buf.clear();
+///     assert_eq!(decode_frame_array_into(0x0CF00400, &data, &mut buf), 1);
+/// }
+/// ```
+pub fn decode_frame_array_into(can_id: u32, data: &[u8; 8], out: &mut Vec<DecodedSpn>) -> usize {
+    let before = out.len();
+    for spn_def in present_spns(extract_pgn(can_id), data) {
+        debug_assert!(spn_def.required_len() <= FRAME_LEN);
+        // SAFETY: every database SPN has required_len() <= FRAME_LEN,
+        // asserted at compile time in database.rs
+        let raw = unsafe { read_raw_value(data, spn_def) };
+        if let Some((raw_value, value)) = validate_raw(raw, spn_def) {
+            out.push(make_decoded(spn_def, raw_value, value));
+        }
+    }
+    out.len() - before
+}
+
+/// Decode all known SPNs from a CAN frame into a caller-provided array, for FFI.
+///
+/// Fills `out` from the start with the values [`decode_frame_iter`] yields,
+/// stopping when `out` is full. Returns the number of entries written.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::database::unit_str;
+/// use voltage_j1939::decoder::decode_frame_c;
+/// use voltage_j1939::types::DecodedSpnC;
+///
+/// let mut out = [DecodedSpnC::default(); 16];
+/// let data = [0xFF, 0xFF, 0xFF, 0x20, 0x4E, 0xFF, 0xFF, 0xFF];
+/// let n = decode_frame_c(0x0CF00400, &data, &mut out);
+/// assert_eq!(n, 1);
+/// assert_eq!((out[0].spn, out[0].value), (190, 2500.0));
+/// assert_eq!(unit_str(out[0].unit_id), Some("RPM"));
+/// ```
+pub fn decode_frame_c(can_id: u32, data: &[u8], out: &mut [DecodedSpnC]) -> usize {
+    let pgn = extract_pgn(can_id);
+    let spns = get_spns_for_pgn(pgn).unwrap_or_default();
+    let decoded = spns
+        .iter()
+        .zip(unit_ids_for_pgn(pgn))
+        .filter(|(spn_def, _)| spn_def.is_present(data))
+        .filter_map(|(spn_def, &unit_id)| Some((decode_spn_full(data, spn_def)?, unit_id)));
+
+    let mut written = 0;
+    for (slot, (spn, unit_id)) in out.iter_mut().zip(decoded) {
+        *slot = DecodedSpnC {
+            value: spn.value,
+            raw_value: spn.raw_value,
+            spn: spn.spn,
+            unit_id,
+            _reserved: 0,
+        };
+        written += 1;
+    }
+    written
+}
+
+/// Decode all known SPNs from any [`embedded_can::Frame`].
+///
+/// Works directly on frames from CAN drivers implementing the `embedded-can`
+/// traits, such as `socketcan::CanFrame`, without copying ID and data first.
+/// Standard (11-bit) and remote frames are not J1939 data and decode to an
+/// empty `Vec`.
+///
+/// Requires the `embedded-can` feature.
+///
+/// # Example
+///
+/// ```ignore
+/// use socketcan::{CanSocket, Socket};
+/// use voltage_j1939::decoder::decode_can_frame;
+///
+/// let socket = CanSocket::open("can0")?;
+/// let frame = socket.read_frame()?;
+/// for spn in decode_can_frame(&frame) {
+///     println!("{}", spn);
+/// }
+/// ```
+#[cfg(feature = "embedded-can")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-can")))]
+pub fn decode_can_frame<F: embedded_can::Frame>(frame: &F) -> Vec<DecodedSpn> {
+    match frame.id() {
+        embedded_can::Id::Extended(id) if frame.is_data_frame() => {
+            decode_frame(id.as_raw(), frame.data())
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Decode all known SPNs from a CAN frame into a map keyed by SPN name.
+///
+/// Convenient for scripting and export. Names are unique within the built-in
+/// database's PGNs; if two SPNs in a PGN ever share a name, the one defined
+/// last wins.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::decode_frame_map;
+///
+/// let values = decode_frame_map(0x0CF00400, &[0, 0, 0, 0x20, 0x4E, 0, 0, 0]);
+/// assert_eq!(values["engine_speed"], 2500.0);
+/// ```
+#[inline]
+pub fn decode_frame_map(can_id: u32, data: &[u8]) -> HashMap<&'static str, f64> {
+    decode_frame_iter(can_id, data)
+        .map(|spn| (spn.name, spn.value))
+        .collect()
+}
+
+/// Decode a single SPN and report its status, even if the value is not valid.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::decode_spn_status;
+/// use voltage_j1939::database::get_spn_def;
+/// use voltage_j1939::types::SpnStatus;
+///
+/// let spn_def = get_spn_def(110).unwrap(); // Coolant temperature
+/// assert_eq!(decode_spn_status(&[0xFF], spn_def).status, SpnStatus::NotAvailable);
+/// assert_eq!(decode_spn_status(&[0xFE], spn_def).status, SpnStatus::Error);
+/// assert_eq!(decode_spn_status(&[], spn_def).status, SpnStatus::Truncated);
+/// assert_eq!(decode_spn_status(&[130], spn_def).value, Some(90.0));
+/// ```
+#[inline]
+pub fn decode_spn_status(data: &[u8], spn_def: &'static SpnDef) -> DecodedSpnStatus {
+    let raw_value = extract_raw_value(data, spn_def);
+    let status = match raw_value {
+        Some(raw) => classify_raw(raw, spn_def),
+        None => SpnStatus::Truncated,
+    };
+    let value = match (status, raw_value) {
+        (SpnStatus::Valid, Some(raw)) => {
+            Some(raw_as_f64(raw, spn_def).mul_add(spn_def.scale, spn_def.offset))
+        }
+        _ => None,
+    };
+
+    DecodedSpnStatus {
+        value,
+        raw_value,
+        spn_def,
+        status,
+    }
+}
+
+/// Decode a 2-bit discrete SPN (switch, enable, status) into its state.
+///
+/// Same raw value as [`decode_spn`], but named instead of a float, so
+/// callers need not know that 2 is error and 3 is "not available". A frame
+/// too short to contain the SPN reads as [`SwitchState::NotAvailable`].
+/// 1-bit fields decode as `Off`/`On`. Returns `None` for fields wider than
+/// 2 bits, which are not switches.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::decode_switch;
+/// use voltage_j1939::database::get_spn_def;
+/// use voltage_j1939::types::SwitchState;
+///
+/// let spn_def = get_spn_def(558).unwrap(); // Accelerator pedal 1 low idle switch
+/// assert_eq!(decode_switch(&[0b0000_0001], spn_def), Some(SwitchState::On));
+/// assert_eq!(decode_switch(&[0b0000_0010], spn_def), Some(SwitchState::Error));
+/// assert_eq!(decode_switch(&[0xFF], spn_def), Some(SwitchState::NotAvailable));
+///
+/// let speed = get_spn_def(190).unwrap(); // 16-bit engine speed
+/// assert_eq!(decode_switch(&[0; 8], speed), None);
+/// ```
+#[inline]
+pub fn decode_switch(data: &[u8], spn_def: &SpnDef) -> Option<SwitchState> {
+    if !spn_def.is_discrete() {
+        return None;
+    }
+    Some(match extract_raw_value(data, spn_def) {
+        Some(raw) => SwitchState::from_bits(raw as u8),
+        None => SwitchState::NotAvailable,
+    })
+}
+
+/// Decode all SPNs of a known PGN, including "not available", error and truncated ones.
+///
+/// Useful for troubleshooting: an unknown PGN yields an empty vector, while a
+/// known PGN always yields one entry per SPN in the database, so "unknown PGN"
+/// and "all fields not available" can be told apart.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::decode_frame_all;
+/// use voltage_j1939::types::SpnStatus;
+///
+/// // EEC1 with every byte set to 0xFF
+/// let all = decode_frame_all(0x0CF00400, &[0xFF; 8]);
+/// assert!(!all.is_empty());
+/// assert!(all.iter().all(|s| s.status == SpnStatus::NotAvailable));
+/// ```
+#[inline]
+pub fn decode_frame_all(can_id: u32, data: &[u8]) -> Vec<DecodedSpnStatus> {
+    present_spns(extract_pgn(can_id), data)
+        .map(|spn_def| decode_spn_status(data, spn_def))
+        .collect()
+}
+
+/// Decode all known SPNs from a CAN frame, also listing SPNs cut off by a short frame.
+///
+/// Returns `(decoded, skipped)`, where `skipped` holds the numbers of SPNs
+/// whose [`required_len`](SpnDef::required_len) exceeds `data.len()`. SPNs
+/// omitted because they are "not available" or in error are not listed.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::decode_frame_verbose;
+///
+/// // 3-byte EEC1: engine speed (bytes 3-4) and later fields are missing
+/// let (decoded, skipped) = decode_frame_verbose(0x0CF00400, &[0x01, 0x8C, 0xA0]);
+/// assert_eq!(decoded.len(), 4);
+/// assert!(skipped.contains(&190));
+/// ```
+pub fn decode_frame_verbose(can_id: u32, data: &[u8]) -> (Vec<DecodedSpn>, Vec<u32>) {
+    let mut decoded = Vec::new();
+    let mut skipped = Vec::new();
+    for spn_def in present_spns(extract_pgn(can_id), data) {
+        if spn_def.required_len() > data.len() {
+            skipped.push(spn_def.spn);
+        } else if let Some(spn) = decode_spn_full(data, spn_def) {
+            decoded.push(spn);
+        }
+    }
+    (decoded, skipped)
+}
+
+/// Decode all known SPNs from a CAN frame, zero-filling SPNs cut off by a short frame.
+///
+/// Opt-in for ECUs that send frames shorter than the PGN, with only the low
+/// bytes of a multi-byte SPN meaningful. An SPN that starts inside `data`
+/// but extends past its end is decoded with the missing bytes read as zero
+/// and flagged [`partial`](PartialDecodedSpn::partial). SPNs starting past
+/// the end are skipped, as are "not available" and error values.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::decode_frame_partial;
+///
+/// // 4-byte EEC1: only the low byte of engine speed (bytes 3-4) is present
+/// let decoded = decode_frame_partial(0x0CF00400, &[0xF1, 0x7D, 0x7D, 0x50]);
+/// let speed = decoded.iter().find(|d| d.spn.spn == 190).unwrap();
+/// assert!(speed.partial);
+/// assert_eq!(speed.spn.value, 10.0); // raw 0x0050 * 0.125
+/// assert!(decoded.iter().filter(|d| d.spn.spn != 190).all(|d| !d.partial));
+/// ```
+pub fn decode_frame_partial(can_id: u32, data: &[u8]) -> Vec<PartialDecodedSpn> {
+    let mut padded = [0u8; FRAME_LEN];
+    let len = data.len().min(FRAME_LEN);
+    padded[..len].copy_from_slice(&data[..len]);
+
+    present_spns(extract_pgn(can_id), data)
+        .filter_map(|spn_def| {
+            let partial = spn_def.required_len() > data.len();
+            if partial && spn_def.start_byte as usize >= data.len() {
+                return None;
+            }
+            let source = if partial { &padded[..] } else { data };
+            decode_spn_full(source, spn_def).map(|spn| PartialDecodedSpn { spn, partial })
+        })
+        .collect()
+}
+
+/// Decode all known SPNs from a CAN frame, collecting a per-SPN error for
+/// every SPN that could not be decoded.
+///
+/// Returns `(decoded, errors)`. Errors are [`DecodeError::InvalidDefinition`]
+/// for definitions that can't be decoded (zero bit length, bit length wider
+/// than the data type, zero or non-finite scale, non-finite offset) and
+/// [`DecodeError::SpnTruncated`] for SPNs cut off by a short frame. SPNs
+/// that are "not available" or in error are normal bus traffic and are
+/// omitted without an error, as in [`decode_frame`].
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::decode_frame_collect;
+/// use voltage_j1939::DecodeError;
+///
+/// let (decoded, errors) = decode_frame_collect(0x0CF00400, &[0x01, 0x8C, 0xA0]);
+/// assert_eq!(decoded.len(), 4);
+/// assert!(errors.contains(&(
+///     190,
+///     DecodeError::SpnTruncated { spn: 190, required: 5, actual: 3 }
+/// )));
+/// ```
+pub fn decode_frame_collect(
+    can_id: u32,
+    data: &[u8],
+) -> (Vec<DecodedSpn>, Vec<(u32, DecodeError)>) {
+    let mut decoded = Vec::new();
+    let mut errors = Vec::new();
+    for spn_def in present_spns(extract_pgn(can_id), data) {
+        match check_spn(data, spn_def) {
+            Ok(()) => decoded.extend(decode_spn_full(data, spn_def)),
+            Err(err) => errors.push((spn_def.spn, err)),
+        }
+    }
+    (decoded, errors)
+}
+
+/// Decode a frame given as hex strings, e.g. copied from a bus log.
+///
+/// `id_hex` is the CAN ID (`"0CF00400"`), `data_hex` the payload
+/// (`"000000204E000000"`). Both may carry a `0x` prefix and whitespace
+/// between digits (`"00 00 00 20 4E"`). An empty payload is allowed.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] if the ID is not hex or exceeds 29 bits, or if
+/// the data contains a non-hex character or an odd number of digits.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::decode_hex;
+///
+/// let decoded = decode_hex("0x0CF00400", "FF FF FF 20 4E FF FF FF").unwrap();
+/// assert_eq!(decoded[0].name, "engine_speed");
+/// assert_eq!(decoded[0].value, 2500.0);
+///
+/// assert!(decode_hex("0CF00400", "FFF").is_err());
+/// ```
+pub fn decode_hex(id_hex: &str, data_hex: &str) -> Result<Vec<DecodedSpn>, ParseError> {
+    let can_id = parse_hex_id(id_hex)?;
+    let data = parse_hex_bytes(data_hex)?;
+    Ok(decode_frame(can_id, &data))
+}
+
+/// Decode all known SPNs from a CAN frame together with its parsed CAN ID.
+///
+/// Same as [`decode_frame`], but also returns the [`J1939Id`] so callers can
+/// check the destination address of PDU1 (peer-to-peer) frames without
+/// re-parsing the CAN ID.
+///
+/// # Arguments
+///
+/// * `can_id` - The 29-bit extended CAN ID
+/// * `data` - The CAN frame data (up to 8 bytes)
+///
+/// # Returns
+///
+/// A tuple of (parsed CAN ID, decoded SPNs). SPNs are empty if the PGN is not recognized.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::decode_addressed_frame;
+///
+/// // EEC1 frame from SA=0x00
+/// let (id, decoded) = decode_addressed_frame(0x0CF00400, &[0, 0, 0, 0x20, 0x4E, 0, 0, 0]);
+///
+/// // Ignore frames that are not for us (or global)
+/// let my_sa = 0x21;
+/// if id.is_addressed_to(my_sa) {
+///     for spn in decoded {
+///         println!("{}: {} {}", spn.name, spn.value, spn.unit);
+///     }
+/// }
+/// ```
+#[inline]
+pub fn decode_addressed_frame(can_id: u32, data: &[u8]) -> (J1939Id, Vec<DecodedSpn>) {
+    let id = parse_can_id(can_id);
+    let decoded = decode_pgn(id.pgn, data);
+    (id, decoded)
+}
+
+/// Decode a specific SPN by number from a CAN frame.
+/// Always inlined for direct SPN decoding hot path.
+///
+/// # Arguments
+///
+/// * `spn` - The SPN number to decode
+/// * `data` - The CAN frame data
+///
+/// # Returns
+///
+/// The decoded value, or `None` if the SPN is not found or data is invalid.
+#[inline(always)]
+pub fn decode_spn_by_number(spn: u32, data: &[u8]) -> Option<f64> {
+    decode_spn(data, get_spn_def(spn)?)
+}
+
+/// Decode a CAN frame, reporting why no values were produced.
+///
+/// Unlike [`decode_frame`], an unknown PGN, a transport protocol frame and a
+/// frame too short for its PGN's layout are reported separately instead of
+/// all yielding an empty `Vec`. A truncated frame returns no values; use
+/// [`decode_frame_all`] for per-SPN detail.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::decode_frame_result;
+/// use voltage_j1939::FrameDecode;
+///
+/// // EEC1 with only 4 of 8 bytes
+/// match decode_frame_result(0x0CF00400, &[0, 0, 0, 0x20]) {
+///     FrameDecode::Truncated { pgn, expected_len } => {
+///         assert_eq!(pgn, 61444);
+///         assert_eq!(expected_len, 8);
+///     }
+///     other => panic!("unexpected: {:?}", other),
+/// }
+/// ```
+pub fn decode_frame_result(can_id: u32, data: &[u8]) -> FrameDecode {
+    let pgn = extract_pgn(can_id);
+    let Some(spn_defs) = get_spns_for_pgn(pgn) else {
+        if is_transport_pgn(pgn) {
+            return FrameDecode::Transport(pgn);
+        }
+        return FrameDecode::UnknownPgn(pgn);
+    };
+
+    let expected_len = spn_defs
+        .iter()
+        .map(|def| def.required_len())
+        .max()
+        .unwrap_or(0);
+    if data.len() < expected_len {
+        return FrameDecode::Truncated { pgn, expected_len };
+    }
+
+    FrameDecode::Decoded(decode_frame_iter(can_id, data).collect())
+}
+
+/// Format a CAN frame as a human-readable multi-line block.
+///
+/// The first line shows the parsed CAN ID (priority, PGN with its acronym
+/// if known, destination for PDU1 and source address). Each valid SPN
+/// follows on its own line. Unknown PGNs list the raw bytes in hex.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::format_frame;
+///
+/// let text = format_frame(0x0CF00400, &[0xFF, 0xFF, 0xFF, 0x20, 0x4E, 0xFF, 0xFF, 0xFF]);
+/// assert_eq!(
+///     text,
+///     "CAN ID 0x0CF00400: priority 3, PGN 61444 (EEC1), SA 0x00\n  engine_speed = 2500 RPM\n"
+/// );
+/// ```
+pub fn format_frame(can_id: u32, data: &[u8]) -> String {
+    let id = parse_can_id(can_id);
+    let mut out = String::new();
+
+    // Writing to a String cannot fail
+    let _ = write!(
+        out,
+        "CAN ID 0x{:08X}: priority {}, PGN {}",
+        can_id, id.priority, id.pgn
+    );
+    match get_pgn_info(id.pgn) {
+        Some(info) => {
+            let _ = write!(out, " ({})", info.acronym);
+        }
+        None => out.push_str(" (unknown)"),
+    }
+    if !id.is_broadcast() {
+        let _ = write!(out, ", DA 0x{:02X}", id.destination_address);
+    }
+    let _ = writeln!(out, ", SA 0x{:02X}", id.source_address);
+
+    if get_spns_for_pgn(id.pgn).is_none() {
+        out.push_str("  data:");
+        for byte in data {
+            let _ = write!(out, " {:02X}", byte);
+        }
+        out.push('\n');
+        return out;
+    }
+
+    let mut any = false;
+    for spn in decode_frame_iter(can_id, data) {
+        any = true;
+        let _ = writeln!(out, "  {}", spn);
+    }
+    if !any {
+        out.push_str("  (no valid SPNs)\n");
+    }
+    out
+}
+
+// ============================================================================
+// Internal helpers - optimized for minimal branching
+// ============================================================================
+
+/// Strip an optional `0x` / `0X` prefix.
+fn strip_hex_prefix(s: &str) -> &str {
+    s.strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .unwrap_or(s)
+}
+
+/// Parse a hex CAN ID and check it fits in 29 bits.
+fn parse_hex_id(id_hex: &str) -> Result<u32, ParseError> {
+    let digits = strip_hex_prefix(id_hex.trim());
+    // from_str_radix accepts a leading '+', which is not valid here
+    if digits.starts_with('+') {
+        return Err(ParseError::InvalidId);
+    }
+    let can_id = u32::from_str_radix(digits, 16).map_err(|_| ParseError::InvalidId)?;
+    if !is_valid_j1939_id(can_id) {
+        return Err(ParseError::IdOutOfRange(can_id));
+    }
+    Ok(can_id)
+}
+
+/// Parse hex digits into bytes, ignoring whitespace.
+fn parse_hex_bytes(data_hex: &str) -> Result<Vec<u8>, ParseError> {
+    let digits = strip_hex_prefix(data_hex.trim())
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| {
+            c.to_digit(16)
+                .map(|d| d as u8)
+                .ok_or(ParseError::InvalidHexDigit(c))
+        })
+        .collect::<Result<Vec<u8>, _>>()?;
+    if digits.len() % 2 != 0 {
+        return Err(ParseError::OddLength(digits.len()));
+    }
+    Ok(digits
+        .chunks_exact(2)
+        .map(|pair| (pair[0] << 4) | pair[1])
+        .collect())
+}
+
+/// Check that an SPN definition is decodable and fits in `data`.
+fn check_spn(data: &[u8], spn_def: &SpnDef) -> Result<(), DecodeError> {
+    let reason = if spn_def.bit_length == 0 {
+        Some("bit_length is zero")
+    } else if spn_def.bit_length > spn_def.data_type.bit_size() {
+        Some("bit_length exceeds the data type width")
+    } else if spn_def.scale == 0.0 || !spn_def.scale.is_finite() {
+        Some("scale is zero or not finite")
+    } else if !spn_def.offset.is_finite() {
+        Some("offset is not finite")
+    } else {
+        None
+    };
+    if let Some(reason) = reason {
+        return Err(DecodeError::InvalidDefinition {
+            spn: spn_def.spn,
+            reason,
+        });
+    }
+
+    if data.len() < spn_def.required_len() {
+        return Err(DecodeError::SpnTruncated {
+            spn: spn_def.spn,
+            required: spn_def.required_len(),
+            actual: data.len(),
+        });
+    }
+    Ok(())
+}
+
+/// SPN definitions of a PGN that are present in `data`.
+/// Multiplexed SPNs whose selector does not match are skipped.
+#[inline(always)]
+fn present_spns(pgn: u32, data: &[u8]) -> impl Iterator<Item = &'static SpnDef> + '_ {
+    present_in(get_spns_for_pgn(pgn).unwrap_or_default(), data)
+}
+
+/// Definitions from `spn_defs` that are present in `data`.
+#[inline(always)]
+fn present_in<'a>(
+    spn_defs: &'a [&'static SpnDef],
+    data: &'a [u8],
+) -> impl Iterator<Item = &'static SpnDef> + 'a {
+    spn_defs
+        .iter()
+        .copied()
+        .filter(move |spn_def| spn_def.is_present(data))
+}
+
+/// Extract raw value from data bytes based on SPN definition.
+/// Uses unsafe get_unchecked after bounds check for optimal codegen.
+/// Hot path: always inlined for zero function call overhead.
+#[inline(always)]
+fn extract_raw_value(data: &[u8], spn_def: &SpnDef) -> Option<u64> {
+    // Single bounds check - enables optimizer to remove bounds checks below
+    // (required_len >= start + byte_size of the data type)
+    if data.len() < spn_def.required_len() {
+        return None;
+    }
+
+    // SAFETY: We verified data.len() >= required_len() above
+    Some(unsafe { read_raw_value(data, spn_def) })
+}
+
+/// Read the raw value of an SPN without checking the data length.
+///
+/// Byte-aligned, full-width fields are read directly; anything else is read
+/// as a bit field starting at absolute bit `start_byte * 8 + start_bit`.
+///
+/// # Safety
+///
+/// `data.len()` must be at least `spn_def.required_len()`.
+#[inline(always)]
+unsafe fn read_raw_value(data: &[u8], spn_def: &SpnDef) -> u64 {
+    if spn_def.start_bit != 0 || spn_def.bit_length < spn_def.data_type.bit_size() {
+        // SAFETY: same contract as this function
+        return unsafe { read_bit_field(data, spn_def) };
+    }
+    let start = spn_def.start_byte as usize;
+
+    // SAFETY: caller guarantees data.len() >= required_len() >= start + byte_size
+    unsafe {
+        match spn_def.data_type {
+            SpnDataType::Uint8 => *data.get_unchecked(start) as u64,
+            SpnDataType::Uint16 => {
+                let ptr = data.as_ptr().add(start) as *const [u8; 2];
+                u16::from_le_bytes(*ptr) as u64
+            }
+            SpnDataType::Uint32 => {
+                let ptr = data.as_ptr().add(start) as *const [u8; 4];
+                u32::from_le_bytes(*ptr) as u64
+            }
+            SpnDataType::Int8 => *data.get_unchecked(start) as i8 as u64,
+            SpnDataType::Int16 => {
+                let ptr = data.as_ptr().add(start) as *const [u8; 2];
+                i16::from_le_bytes(*ptr) as u64
+            }
+            SpnDataType::Int32 => {
+                let ptr = data.as_ptr().add(start) as *const [u8; 4];
+                i32::from_le_bytes(*ptr) as u64
+            }
+        }
+    }
+}
+
+/// Read a field that is not byte-aligned or narrower than its data type.
+/// Signed types are sign-extended from the field width.
+///
+/// Fields within one byte (switches, EEC1/EEC2/CCVS states) are a shift and
+/// mask; only multi-byte bit fields go through [`extract_bits`].
+///
+/// # Safety
+///
+/// `data.len()` must be at least `spn_def.required_len()`.
+#[inline(always)]
+unsafe fn read_bit_field(data: &[u8], spn_def: &SpnDef) -> u64 {
+    let bits = spn_def.effective_bits();
+    let raw = if spn_def.start_bit < 8 && spn_def.start_bit as u32 + bits as u32 <= 8 {
+        // SAFETY: required_len() > start_byte, guaranteed by the caller
+        let byte = unsafe { *data.get_unchecked(spn_def.start_byte as usize) };
+        (byte >> spn_def.start_bit) as u64 & bit_mask(bits)
+    } else {
+        let start_bit = spn_def.start_byte as usize * 8 + spn_def.start_bit as usize;
+        extract_bits(data, start_bit, bits as usize).unwrap_or(0)
+    };
+    if spn_def.data_type.is_signed() && bits > 0 && bits < 64 {
+        let unused = 64 - bits as u32;
+        (((raw << unused) as i64) >> unused) as u64
+    } else {
+        raw
+    }
+}
+
+#[cfg(test)]
+mod tests {
     use super::*;
 
     // ========================================================================
-    // Basic decoding tests
+    // Basic decoding tests
+    // ========================================================================
+
+    #[test]
+    fn test_decode_coolant_temp() {
+        // SPN 110 = Engine Coolant Temperature
+        // Raw 130 -> 130 * 1.0 + (-40) = 90°C
+        let spn_def = get_spn_def(110).unwrap();
+        let data = [130u8, 0, 0, 0, 0, 0, 0, 0];
+        let value = decode_spn(&data, spn_def);
+        assert_eq!(value, Some(90.0));
+    }
+
+    #[test]
+    fn test_decode_engine_speed() {
+        // SPN 190 = Engine Speed
+        // Raw 20000 (0x4E20) -> 20000 * 0.125 = 2500 RPM
+        let spn_def = get_spn_def(190).unwrap();
+        let data = [0, 0, 0, 0x20, 0x4E, 0, 0, 0]; // Little-endian at byte 3
+        let value = decode_spn(&data, spn_def);
+        assert_eq!(value, Some(2500.0));
+    }
+
+    #[test]
+    fn test_decode_not_available() {
+        // 0xFF (all 1s) means "not available" for 8-bit values
+        let spn_def = get_spn_def(110).unwrap();
+        let data = [0xFF, 0, 0, 0, 0, 0, 0, 0];
+        let value = decode_spn(&data, spn_def);
+        assert!(value.is_none());
+    }
+
+    #[test]
+    fn test_decode_frame_eec1() {
+        // EEC1 (PGN 61444) with some test data
+        let can_id = 0x0CF00400;
+        let data = [0x00, 0x00, 0x00, 0x20, 0x4E, 0x00, 0x00, 0x00];
+
+        let decoded = decode_frame(can_id, &data);
+        assert!(!decoded.is_empty());
+
+        // Should contain engine speed (SPN 190)
+        let engine_speed = decoded.iter().find(|d| d.spn == 190);
+        assert!(engine_speed.is_some());
+        assert_eq!(engine_speed.unwrap().value, 2500.0);
+    }
+
+    #[test]
+    fn test_decode_frame_map() {
+        // ET1: coolant=90°C, fuel=10°C
+        let data = [130u8, 50, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+        let values = decode_frame_map(0x18FEEE00, &data);
+        assert_eq!(values.len(), 2);
+        assert_eq!(values["engine_coolant_temperature"], 90.0);
+        assert_eq!(values["fuel_temperature"], 10.0);
+
+        assert!(decode_frame_map(0x18FF0000, &data).is_empty());
+    }
+
+    #[test]
+    fn test_decode_frame_all_statuses() {
+        // ET1, 7 bytes: coolant valid, fuel error, oil temp N/A, turbo oil error,
+        // intercooler valid, thermostat truncated
+        let data = [130u8, 0xFE, 0xFF, 0xFF, 0xFE, 0xFF, 40];
+        let all = decode_frame_all(0x18FEEE00, &data);
+        let statuses: Vec<_> = all.iter().map(|s| (s.spn_def.spn, s.status)).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                (110, SpnStatus::Valid),
+                (174, SpnStatus::Error),
+                (175, SpnStatus::NotAvailable),
+                (176, SpnStatus::Error),
+                (52, SpnStatus::Valid),
+                (1134, SpnStatus::Truncated),
+            ]
+        );
+
+        assert_eq!(all[0].value, Some(90.0));
+        assert_eq!(all[1].value, None);
+        assert_eq!(all[1].raw_value, Some(0xFE));
+        assert_eq!(all[5].raw_value, None);
+    }
+
+    #[test]
+    fn test_decode_frame_all_unknown_vs_not_available() {
+        let data = [0xFF; 8];
+        assert!(decode_frame(0x0CF00400, &data).is_empty());
+        assert!(decode_frame(0x18FF0000, &data).is_empty());
+
+        // Only the known PGN reports its (not available) SPNs
+        assert!(decode_frame_all(0x18FF0000, &data).is_empty());
+        let all = decode_frame_all(0x0CF00400, &data);
+        assert_eq!(all.len(), get_spns_for_pgn(61444).unwrap().len());
+        assert!(all.iter().all(|s| s.status == SpnStatus::NotAvailable));
+    }
+
+    #[test]
+    fn test_decode_frame_all_consistent_with_decode_frame() {
+        let can_id = 0x0CF00400;
+        let data = [0x05, 0x10, 0xFE, 0x20, 0x4E, 0x00, 0x30, 0x40];
+
+        let valid: Vec<_> = decode_frame_all(can_id, &data)
+            .into_iter()
+            .filter(|s| s.status == SpnStatus::Valid)
+            .map(|s| (s.spn_def.spn, s.value.unwrap()))
+            .collect();
+        let decoded: Vec<_> = decode_frame(can_id, &data)
+            .into_iter()
+            .map(|d| (d.spn, d.value))
+            .collect();
+        assert_eq!(valid, decoded);
+    }
+
+    #[test]
+    fn test_decode_addressed_frame_pdu1() {
+        // PDU1 frame (Request PGN) from 0xFE to 0x21 - no SPNs, but address is surfaced
+        let (id, decoded) = decode_addressed_frame(0x18EA21FE, &[0xE5, 0xFE, 0x00]);
+        assert_eq!(id.pgn, 0xEA00);
+        assert_eq!(id.destination_address, 0x21);
+        assert_eq!(id.source_address, 0xFE);
+        assert!(decoded.is_empty());
+    }
+
+    #[test]
+    fn test_decode_addressed_frame_pdu2() {
+        let can_id = 0x0CF00400;
+        let data = [0x00, 0x00, 0x00, 0x20, 0x4E, 0x00, 0x00, 0x00];
+
+        let (id, decoded) = decode_addressed_frame(can_id, &data);
+        assert_eq!(id, parse_can_id(can_id));
+        assert_eq!(id.destination_address, 0xFF);
+        assert_eq!(decoded.len(), decode_frame(can_id, &data).len());
+        assert!(decoded.iter().any(|d| d.spn == 190 && d.value == 2500.0));
+    }
+
+    #[test]
+    fn test_decode_spn_by_number() {
+        let data = [130u8, 0, 0, 0, 0, 0, 0, 0];
+        let value = decode_spn_by_number(110, &data);
+        assert_eq!(value, Some(90.0));
+
+        // Unknown SPN
+        let value = decode_spn_by_number(99999, &data);
+        assert!(value.is_none());
+    }
+
+    #[test]
+    fn test_bit_field_extraction() {
+        // SPN 899 = Engine Torque Mode (4 bits at byte 0, bit 0)
+        let spn_def = get_spn_def(899).unwrap();
+
+        // Value 5 in lower nibble
+        let data = [0x05, 0, 0, 0, 0, 0, 0, 0];
+        let value = decode_spn(&data, spn_def);
+        assert_eq!(value, Some(5.0));
+
+        // Value 10 in lower nibble
+        let data = [0x0A, 0, 0, 0, 0, 0, 0, 0];
+        let value = decode_spn(&data, spn_def);
+        assert_eq!(value, Some(10.0));
+    }
+
+    // ========================================================================
+    // Iterator version tests
+    // ========================================================================
+
+    #[test]
+    fn test_decode_frame_ref_matches_decode_frame() {
+        let frames: [(u32, [u8; 8]); 3] = [
+            (0x0CF00400, [0x00, 0x7D, 0x7D, 0x20, 0x4E, 0x00, 0x00, 0x7D]), // EEC1
+            (0x18FEEE00, [130, 50, 0x20, 0x4E, 0xFF, 0xFF, 40, 0xFF]),      // ET1
+            (0x18FE0700, [0x60, 0x5A, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]), // ET, left n/a
+        ];
+        for (can_id, data) in frames {
+            let refs: Vec<DecodedSpn> = decode_frame_ref(can_id, &data).map(Into::into).collect();
+            let full = decode_frame(can_id, &data);
+            assert_eq!(refs.len(), full.len());
+            for (a, b) in refs.iter().zip(&full) {
+                assert_eq!(
+                    (a.spn, a.instance, a.raw_value),
+                    (b.spn, b.instance, b.raw_value)
+                );
+                assert_eq!(
+                    (a.name, a.unit, a.value, a.scale),
+                    (b.name, b.unit, b.value, b.scale)
+                );
+            }
+        }
+
+        // The definition is enough to re-encode the value
+        let data = [0xFF, 0xFF, 0xFF, 0x20, 0x4E, 0xFF, 0xFF, 0xFF];
+        let speed = decode_frame_ref(0x0CF00400, &data)
+            .find(|d| d.spn() == 190)
+            .unwrap();
+        let mut encoded = [0xFF; 8];
+        crate::encoder::encode_spn(&mut encoded, speed.spn_def, speed.value).unwrap();
+        assert_eq!(encoded, data);
+        assert!(decode_spn_ref(&[0xFF; 8], speed.spn_def).is_none());
+    }
+
+    #[test]
+    fn test_decode_frame_iter() {
+        let can_id = 0x0CF00400; // EEC1
+        let data = [0x00, 0x00, 0x00, 0x20, 0x4E, 0x00, 0x00, 0x00];
+
+        // Count decoded SPNs using iterator
+        let count = decode_frame_iter(can_id, &data).count();
+        assert!(count > 0);
+
+        // Find specific SPN using iterator
+        let engine_speed = decode_frame_iter(can_id, &data).find(|d| d.spn == 190);
+        assert!(engine_speed.is_some());
+        assert_eq!(engine_speed.unwrap().value, 2500.0);
+    }
+
+    #[test]
+    fn test_decode_frame_iter_empty() {
+        // Unknown PGN should return empty iterator
+        let can_id = 0x00000000;
+        let data = [0xFF; 8];
+        assert_eq!(decode_frame_iter(can_id, &data).count(), 0);
+    }
+
+    #[test]
+    fn test_decode_frame_iter_consistency() {
+        // Iterator and Vec versions should produce same results
+        let can_id = 0x0CF00400;
+        let data = [0x05, 0x10, 0x20, 0x20, 0x4E, 0x00, 0x30, 0x40];
+
+        let vec_result: Vec<_> = decode_frame(can_id, &data);
+        let iter_result: Vec<_> = decode_frame_iter(can_id, &data).collect();
+
+        assert_eq!(vec_result.len(), iter_result.len());
+        for (v, i) in vec_result.iter().zip(iter_result.iter()) {
+            assert_eq!(v.spn, i.spn);
+            assert_eq!(v.value, i.value);
+            assert_eq!(v.raw_value, i.raw_value);
+        }
+    }
+
+    // ========================================================================
+    // Boundary condition tests
+    // ========================================================================
+
+    #[test]
+    fn test_decode_data_too_short() {
+        // SPN 190 needs bytes 3-4 (16-bit at byte 3)
+        let spn_def = get_spn_def(190).unwrap();
+
+        // Only 4 bytes, needs at least 5
+        let data = [0, 0, 0, 0x20];
+        let value = decode_spn(&data, spn_def);
+        assert!(value.is_none());
+
+        // Exactly enough bytes
+        let data = [0, 0, 0, 0x20, 0x4E];
+        let value = decode_spn(&data, spn_def);
+        assert_eq!(value, Some(2500.0));
+    }
+
+    #[test]
+    fn test_decode_empty_data() {
+        let spn_def = get_spn_def(110).unwrap();
+        let data: [u8; 0] = [];
+        let value = decode_spn(&data, spn_def);
+        assert!(value.is_none());
+    }
+
+    #[test]
+    fn test_decode_not_available_16bit() {
+        // 0xFFFF means "not available" for 16-bit values
+        let spn_def = get_spn_def(190).unwrap(); // Engine speed (16-bit at byte 3)
+        let data = [0, 0, 0, 0xFF, 0xFF, 0, 0, 0];
+        let value = decode_spn(&data, spn_def);
+        assert!(value.is_none());
+
+        // 0xFFFE also means "not available" (error indicator)
+        let data = [0, 0, 0, 0xFE, 0xFF, 0, 0, 0];
+        let value = decode_spn(&data, spn_def);
+        assert!(value.is_none());
+    }
+
+    #[test]
+    fn test_decode_32bit_values() {
+        // SPN 247 = Engine Total Hours (32-bit at byte 0)
+        let spn_def = get_spn_def(247).unwrap();
+
+        // 72000 raw * 0.05 = 3600 hours
+        // 72000 = 0x00011940 -> little-endian [0x40, 0x19, 0x01, 0x00]
+        let data = [0x40, 0x19, 0x01, 0x00, 0, 0, 0, 0];
+        let value = decode_spn(&data, spn_def);
+        assert_eq!(value, Some(3600.0));
+    }
+
+    #[test]
+    fn test_decode_32bit_not_available() {
+        // SPN 247 = Engine Total Hours (32-bit)
+        let spn_def = get_spn_def(247).unwrap();
+
+        // 0xFFFFFFFF means "not available"
+        let data = [0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0];
+        let value = decode_spn(&data, spn_def);
+        assert!(value.is_none());
+    }
+
+    #[test]
+    fn test_decode_32bit_totalizer_special_codes() {
+        // HOURS: total hours (SPN 247, bytes 0-3) and revolutions (SPN 249, bytes 4-7)
+        for (spn, scale) in [(247, 0.05), (249, 1000.0)] {
+            let spn_def = get_spn_def(spn).unwrap();
+            let start = spn_def.start_byte as usize;
+            let frame = |raw: u32| {
+                let mut data = [0u8; 8];
+                data[start..start + 4].copy_from_slice(&raw.to_le_bytes());
+                data
+            };
+
+            let data = frame(0xFFFF_FFFF);
+            assert_eq!(decode_spn(&data, spn_def), None, "SPN {}", spn);
+            let status = decode_spn_status(&data, spn_def).status;
+            assert_eq!(status, SpnStatus::NotAvailable);
+
+            let data = frame(0xFFFF_FFFE);
+            assert_eq!(decode_spn(&data, spn_def), None, "SPN {}", spn);
+            assert_eq!(decode_spn_status(&data, spn_def).status, SpnStatus::Error);
+
+            // Largest valid code decodes without overflow
+            let max = decode_spn(&frame(0xFFFF_FFFD), spn_def).unwrap();
+            assert_eq!(max, 0xFFFF_FFFDu32 as f64 * scale, "SPN {}", spn);
+        }
+    }
+
+    const fn signed_spn_def(data_type: SpnDataType, start_byte: u8, scale: f64) -> SpnDef {
+        SpnDef {
+            scale,
+            offset: 0.0,
+            spn: 9000,
+            pgn: 0xFF00,
+            name: "signed_test",
+            unit: "",
+            start_byte,
+            start_bit: 0,
+            bit_length: data_type.bit_size(),
+            data_type,
+            ..SpnDef::DEFAULT
+        }
+    }
+
+    #[test]
+    fn test_decode_int8_values() {
+        let spn_def = signed_spn_def(SpnDataType::Int8, 1, 1.0);
+
+        let data = [0xFF, 0xCE, 0, 0, 0, 0, 0, 0]; // -50
+        assert_eq!(decode_spn(&data, &spn_def), Some(-50.0));
+
+        let data = [0xFF, 0x7F, 0, 0, 0, 0, 0, 0]; // 127
+        assert_eq!(decode_spn(&data, &spn_def), Some(127.0));
+
+        let data = [0xFF, 0x80, 0, 0, 0, 0, 0, 0]; // -128
+        assert_eq!(decode_spn(&data, &spn_def), Some(-128.0));
+
+        // All ones (0xFF) / 0xFE are still the special codes
+        let data = [0xFF, 0xFF, 0, 0, 0, 0, 0, 0];
+        assert!(decode_spn(&data, &spn_def).is_none());
+        let data = [0xFF, 0xFE, 0, 0, 0, 0, 0, 0];
+        assert!(decode_spn(&data, &spn_def).is_none());
+    }
+
+    #[test]
+    fn test_decode_int32_values() {
+        let spn_def = signed_spn_def(SpnDataType::Int32, 4, 0.5);
+
+        // -1000 raw = 0xFFFFFC18 little-endian at byte 4
+        let data = [0, 0, 0, 0, 0x18, 0xFC, 0xFF, 0xFF];
+        assert_eq!(decode_spn(&data, &spn_def), Some(-500.0));
+
+        let data = [0, 0, 0, 0, 0xE8, 0x03, 0x00, 0x00]; // 1000
+        assert_eq!(decode_spn(&data, &spn_def), Some(500.0));
+
+        let data = [0, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF];
+        assert!(decode_spn(&data, &spn_def).is_none());
+
+        // Too short for a 32-bit value at byte 4
+        assert!(decode_spn(&data[..7], &spn_def).is_none());
+    }
+
+    #[test]
+    fn test_decode_signed_status() {
+        static SPN_DEF: SpnDef = signed_spn_def(SpnDataType::Int16, 0, 1.0);
+        let spn_def = &SPN_DEF;
+
+        let status = decode_spn_status(&[0xCE, 0xFF], spn_def); // -50
+        assert_eq!(status.status, SpnStatus::Valid);
+        assert_eq!(status.value, Some(-50.0));
+        assert_eq!(
+            decode_spn_status(&[0xFF, 0xFF], spn_def).status,
+            SpnStatus::NotAvailable
+        );
+        assert_eq!(
+            decode_spn_status(&[0xFE, 0xFF], spn_def).status,
+            SpnStatus::Error
+        );
+    }
+
+    #[test]
+    fn test_signed_special_codes_roundtrip() {
+        // Signed SPNs reserve the all-ones bit patterns (-1 / -2), never the
+        // top of the positive range
+        static SPN_DEF: SpnDef = signed_spn_def(SpnDataType::Int8, 0, 1.0);
+        let spn_def = &SPN_DEF;
+        for raw in -128..=127i64 {
+            let mut data = [0xFF; 8];
+            data[0] = raw as u8;
+            let status = decode_spn_status(&data, spn_def).status;
+            match raw {
+                -1 => assert_eq!(status, SpnStatus::NotAvailable),
+                -2 => assert_eq!(status, SpnStatus::Error),
+                _ => {
+                    assert_eq!(status, SpnStatus::Valid, "{}", raw);
+                    let mut encoded = [0xFF; 8];
+                    crate::encoder::encode_spn(&mut encoded, spn_def, raw as f64).unwrap();
+                    assert_eq!(encoded, data, "{}", raw);
+                    assert_eq!(decode_spn(&encoded, spn_def), Some(raw as f64));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_database_signed_types_reviewed() {
+        // The built-in database uses unsigned raw values with offsets, as J1939-71 does
+        assert!(crate::database::SPN_DEFINITIONS
+            .iter()
+            .all(|s| !s.data_type.is_signed()));
+    }
+
+    #[test]
+    fn test_decode_net_battery_current() {
+        // SPN 114: unsigned 8-bit, offset -125 A
+        let spn_def = get_spn_def(114).unwrap();
+        assert_eq!(spn_def.data_type, SpnDataType::Uint8);
+
+        let data = [75, 0, 0, 0, 0, 0, 0, 0]; // 75 - 125 = -50 A
+        assert_eq!(decode_spn(&data, spn_def), Some(-50.0));
+
+        // Discharge limit, zero and charge side: no sign extension anywhere
+        assert_eq!(decode_spn(&[0, 0], spn_def), Some(-125.0));
+        assert_eq!(decode_spn(&[125, 0], spn_def), Some(0.0));
+        assert_eq!(decode_spn(&[175, 0], spn_def), Some(50.0));
+        assert_eq!(decode_spn(&[250], spn_def), Some(125.0));
+        assert!(decode_spn(&[0xFF], spn_def).is_none());
+    }
+
+    #[test]
+    fn test_decode_spn_with_order() {
+        let engine_speed = get_spn_def(190).unwrap();
+        let data = [0xFF, 0xFF, 0xFF, 0x20, 0x4E, 0xFF, 0xFF, 0xFF];
+        assert_eq!(
+            decode_spn_with_order(&data, engine_speed, ByteOrder::LittleEndian),
+            decode_spn(&data, engine_speed)
+        );
+        assert_eq!(
+            decode_spn_with_order(&data, engine_speed, ByteOrder::BigEndian),
+            Some(0x204E as f64 * 0.125)
+        );
+
+        // 32-bit counter: full reversal, not just a swap of 16-bit halves
+        let total_distance = get_spn_def(245).unwrap(); // bytes 4-7
+        let data = [0, 0, 0, 0, 0x00, 0x00, 0x10, 0x00];
+        assert_eq!(
+            decode_spn_with_order(&data, total_distance, ByteOrder::BigEndian),
+            Some(512.0)
+        );
+
+        // Single byte: order does not matter; truncated data still rejected
+        let coolant = get_spn_def(110).unwrap();
+        assert_eq!(
+            decode_spn_with_order(&[130], coolant, ByteOrder::BigEndian),
+            Some(90.0)
+        );
+        assert_eq!(
+            decode_spn_with_order(&data[..4], engine_speed, ByteOrder::BigEndian),
+            None
+        );
+        // Not-available check applies after the swap
+        assert_eq!(
+            decode_spn_with_order(&[0, 0, 0, 0xFF, 0xFF], engine_speed, ByteOrder::BigEndian),
+            None
+        );
+    }
+
+    #[test]
+    fn test_read_le_integers() {
+        let data = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x88];
+        assert_eq!(read_le_uint(&data, 0, 1), Some(0x01));
+        assert_eq!(read_le_uint(&data, 0, 4), Some(0x0403_0201));
+        assert_eq!(read_le_uint(&data, 0, 8), Some(0x8807_0605_0403_0201));
+        assert_eq!(
+            read_le_int(&data, 0, 8),
+            Some(0x8807_0605_0403_0201u64 as i64)
+        );
+        assert_eq!(read_le_int(&data, 7, 1), Some(-120));
+        assert_eq!(read_le_int(&data, 6, 2), Some(0x8807u16 as i16 as i64));
+        assert_eq!(read_le_int(&data, 0, 2), Some(0x0201));
+
+        // Out of bounds and invalid widths never panic
+        assert_eq!(read_le_uint(&data, 0, 0), None);
+        assert_eq!(read_le_uint(&data, 0, 9), None);
+        assert_eq!(read_le_uint(&data, 8, 1), None);
+        assert_eq!(read_le_uint(&data, usize::MAX, 2), None);
+        assert_eq!(read_le_int(&data, 5, 4), None);
+
+        // Agrees with the SPN decoder for byte-aligned fields
+        for spn in [190, 245, 110] {
+            let spn_def = get_spn_def(spn).unwrap();
+            let raw = read_le_uint(
+                &data,
+                spn_def.start_byte as usize,
+                spn_def.data_type.byte_size(),
+            );
+            assert_eq!(raw, extract_raw_value(&data, spn_def), "SPN {}", spn);
+        }
+    }
+
+    #[test]
+    fn test_decode_zero_value() {
+        let spn_def = get_spn_def(110).unwrap();
+        let data = [0u8, 0, 0, 0, 0, 0, 0, 0];
+        let value = decode_spn(&data, spn_def);
+        // 0 * 1.0 + (-40) = -40
+        assert_eq!(value, Some(-40.0));
+    }
+
+    #[test]
+    fn test_decode_negative_offset() {
+        // SPN 110 has offset -40
+        let spn_def = get_spn_def(110).unwrap();
+
+        // Raw 40 -> 40 * 1.0 + (-40) = 0°C
+        let data = [40u8, 0, 0, 0, 0, 0, 0, 0];
+        let value = decode_spn(&data, spn_def);
+        assert_eq!(value, Some(0.0));
+    }
+
+    #[test]
+    fn test_decode_full_returns_metadata() {
+        let spn_def = get_spn_def(190).unwrap();
+        let data = [0, 0, 0, 0x20, 0x4E, 0, 0, 0];
+
+        let decoded = decode_spn_full(&data, spn_def).unwrap();
+        assert_eq!(decoded.spn, 190);
+        assert_eq!(decoded.name, "engine_speed");
+        assert_eq!(decoded.unit, "RPM");
+        assert_eq!(decoded.raw_value, 20000);
+        assert_eq!(decoded.value, 2500.0);
+    }
+
+    // ========================================================================
+    // Bit field tests
     // ========================================================================
 
     #[test]
-    fn test_decode_coolant_temp() {
-        // SPN 110 = Engine Coolant Temperature
-        // Raw 130 -> 130 * 1.0 + (-40) = 90°C
-        let spn_def = get_spn_def(110).unwrap();
-        let data = [130u8, 0, 0, 0, 0, 0, 0, 0];
+    fn test_bit_field_with_offset() {
+        // SPN 559 = Accelerator Pedal Kickdown (2 bits at byte 0, bit 2), without
+        // its reports_all_states opt-out.
+        // For 2-bit fields: 0,1 = valid, 2 = error, 3 = n/a
+        static SPN_DEF: SpnDef = SpnDef {
+            spn: 559,
+            name: "accelerator_pedal_kickdown",
+            pgn: 61443,
+            start_bit: 2,
+            bit_length: 2,
+            ..SpnDef::DEFAULT
+        };
+        let spn_def = &SPN_DEF;
+
+        // Value 0b01 at bit position 2 = 0b0100 = 0x04
+        let data = [0x04, 0, 0, 0, 0, 0, 0, 0];
         let value = decode_spn(&data, spn_def);
-        assert_eq!(value, Some(90.0));
+        assert_eq!(value, Some(1.0));
+
+        // Value 0b00 at bit position 2 = 0b0000 = 0x00
+        let data = [0x00, 0, 0, 0, 0, 0, 0, 0];
+        let value = decode_spn(&data, spn_def);
+        assert_eq!(value, Some(0.0));
+
+        // Value 0b11 at bit position 2 = 0b1100 = 0x0C (not available)
+        let data = [0x0C, 0, 0, 0, 0, 0, 0, 0];
+        let value = decode_spn(&data, spn_def);
+        assert!(value.is_none());
+        assert_eq!(
+            decode_spn_status(&data, spn_def).status,
+            SpnStatus::NotAvailable
+        );
+
+        // Value 0b10 = error, also dropped
+        let data = [0x08, 0, 0, 0, 0, 0, 0, 0];
+        assert!(decode_spn(&data, spn_def).is_none());
+        assert_eq!(decode_spn_status(&data, spn_def).status, SpnStatus::Error);
     }
 
     #[test]
-    fn test_decode_engine_speed() {
-        // SPN 190 = Engine Speed
-        // Raw 20000 (0x4E20) -> 20000 * 0.125 = 2500 RPM
-        let spn_def = get_spn_def(190).unwrap();
-        let data = [0, 0, 0, 0x20, 0x4E, 0, 0, 0]; // Little-endian at byte 3
+    fn test_reports_all_states() {
+        // SPN 559 opts out of the special codes: all four 2-bit states decode
+        let spn_def = get_spn_def(559).unwrap();
+        assert!(spn_def.reports_all_states);
+        for raw in 0..4u8 {
+            let data = [raw << 2];
+            assert_eq!(decode_spn(&data, spn_def), Some(raw as f64));
+            assert_eq!(
+                decode_spn_full(&data, spn_def).unwrap().raw_value,
+                raw as u64
+            );
+            assert_eq!(decode_spn_status(&data, spn_def).status, SpnStatus::Valid);
+        }
+
+        // The switch reading still tells the states apart
+        assert_eq!(
+            decode_switch(&[0x0C], spn_def),
+            Some(SwitchState::NotAvailable)
+        );
+        assert_eq!(decode_switch(&[0x08], spn_def), Some(SwitchState::Error));
+    }
+
+    #[test]
+    fn test_bit_field_fast_path_matches_extract_bits() {
+        let mut rng = XorShift(0xB17F_1E1D_0000_0001);
+        for _ in 0..200 {
+            let data = rng.next().to_le_bytes();
+            for start_bit in 0..8u8 {
+                for bit_length in 1..=8 - start_bit {
+                    for data_type in [SpnDataType::Uint8, SpnDataType::Int8] {
+                        let spn_def = SpnDef {
+                            start_bit,
+                            bit_length,
+                            data_type,
+                            ..signed_spn_def(SpnDataType::Int8, 2, 1.0)
+                        };
+                        let field = start_bit as usize + 16;
+                        let expected = extract_bits(&data, field, bit_length as usize).unwrap();
+                        let raw = extract_raw_value(&data, &spn_def).unwrap();
+                        assert_eq!(raw & bit_mask(bit_length), expected);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_switch() {
+        // SPN 559 = Accelerator Pedal Kickdown (2 bits at byte 0, bit 2)
+        let spn_def = get_spn_def(559).unwrap();
+        let states = [
+            SwitchState::Off,
+            SwitchState::On,
+            SwitchState::Error,
+            SwitchState::NotAvailable,
+        ];
+        for (bits, expected) in states.into_iter().enumerate() {
+            // Neighbouring bits are set and must not leak in
+            let data = [0b1111_0011 | (bits as u8) << 2];
+            assert_eq!(decode_switch(&data, spn_def), Some(expected));
+        }
+
+        assert_eq!(decode_switch(&[], spn_def), Some(SwitchState::NotAvailable));
+
+        // Wider fields are not switches, even when their low bits look like one
+        let torque_mode = get_spn_def(899).unwrap(); // 4 bits
+        assert_eq!(decode_switch(&[0x01], torque_mode), None);
+        assert_eq!(SwitchState::from_bits(0b110), SwitchState::Error);
+    }
+
+    #[test]
+    fn test_discrete_not_available_in_frame() {
+        // parking_brake_switch (SPN 70) is a 2-bit discrete in CCVS
+        let spn_def = get_spn_def(70).unwrap();
+        assert!(spn_def.is_discrete());
+
+        // The parking brake reports all four states; the other CCVS SPNs
+        // drop "not available" as usual
+        let data = [0xFF; 8];
+        let decoded = decode_frame(0x18FEF100, &data);
+        assert_eq!(decoded.len(), 1);
+        assert_eq!((decoded[0].spn, decoded[0].value), (70, 3.0));
+        assert_eq!(decode_frame_iter(0x18FEF100, &data).count(), 1);
+
+        // ...and the switch reading still names the state
+        assert_eq!(decode_spn_status(&data, spn_def).status, SpnStatus::Valid);
+        assert_eq!(
+            decode_switch(&data, spn_def),
+            Some(SwitchState::NotAvailable)
+        );
+    }
+
+    #[test]
+    fn test_bit_field_max_valid() {
+        // SPN 899 = Engine Torque Mode (4 bits)
+        // Max valid value for 4 bits is 13 (14 and 15 are "not available")
+        let spn_def = get_spn_def(899).unwrap();
+
+        let data = [0x0D, 0, 0, 0, 0, 0, 0, 0]; // 13
         let value = decode_spn(&data, spn_def);
-        assert_eq!(value, Some(2500.0));
+        assert_eq!(value, Some(13.0));
+
+        let data = [0x0E, 0, 0, 0, 0, 0, 0, 0]; // 14 - not available
+        let value = decode_spn(&data, spn_def);
+        assert!(value.is_none());
+    }
+
+    // ========================================================================
+    // Multiple PGN tests
+    // ========================================================================
+
+    #[test]
+    fn test_decode_multiple_pgns() {
+        // ET1 (PGN 65262) - Engine Temperature
+        let et1_can_id = 0x18FEEE00;
+        let et1_data = [130u8, 50, 0, 0, 0, 0, 40, 0]; // coolant=90°C, fuel=10°C
+
+        let decoded = decode_frame(et1_can_id, &et1_data);
+
+        // Find coolant temperature
+        let coolant = decoded.iter().find(|d| d.spn == 110);
+        assert!(coolant.is_some());
+        assert_eq!(coolant.unwrap().value, 90.0);
+
+        // Find fuel temperature
+        let fuel = decoded.iter().find(|d| d.spn == 174);
+        assert!(fuel.is_some());
+        assert_eq!(fuel.unwrap().value, 10.0);
+    }
+
+    #[test]
+    fn test_decode_instanced_spns() {
+        // ET (PGN 65031): right bank 450 C (0x5A60), left bank 460 C (0x5BA0)
+        let decoded = decode_frame(
+            0x18FE0700,
+            &[0x60, 0x5A, 0xA0, 0x5B, 0xFF, 0xFF, 0xFF, 0xFF],
+        );
+        let banks: Vec<_> = decoded.iter().map(|d| (d.instance, d.value)).collect();
+        assert_eq!(banks, [(Some(1), 450.0), (Some(2), 460.0)]);
+
+        // SPNs outside instanced PGNs carry no instance
+        let et1 = decode_frame(0x18FEEE00, &[130, 0, 0, 0, 0, 0, 0, 0]);
+        assert!(et1.iter().all(|d| d.instance.is_none()));
+    }
+
+    #[test]
+    fn test_decode_frame_with_handler() {
+        fn constant(value: f64) -> crate::database::PgnHandler {
+            Box::new(move |_: &[u8]| {
+                vec![DecodedSpn {
+                    value,
+                    raw_value: 0,
+                    spn: 0,
+                    instance: None,
+                    name: "custom",
+                    unit: "",
+                    scale: 1.0,
+                }]
+            })
+        }
+
+        let mut db = Database::new();
+        db.register_pgn_handler(0xEF00, constant(1.0));
+        // Proprietary A is PDU1: any destination address reaches the handler
+        assert_eq!(decode_frame_with(&db, 0x18EF2180, &[0; 8])[0].value, 1.0);
+        assert_eq!(decode_frame_with(&db, 0x18EFFF80, &[0; 8])[0].value, 1.0);
+
+        // Re-registering replaces, and handlers override built-in PGNs
+        db.register_pgn_handler(0xEF00, constant(2.0));
+        db.register_pgn_handler(61444, constant(3.0));
+        assert_eq!(decode_frame_with(&db, 0x18EF2180, &[0; 8])[0].value, 2.0);
+        assert_eq!(decode_frame_with(&db, 0x0CF00400, &[0; 8])[0].value, 3.0);
+        assert_eq!(format!("{:?}", db), "Database { handlers: [61184, 61444] }");
+
+        // Without a handler the built-in tables are used
+        let et1 = [130, 0, 0, 0, 0, 0, 0, 0];
+        let fallback = decode_frame_with(&db, 0x18FEEE00, &et1);
+        assert_eq!(fallback.len(), decode_frame(0x18FEEE00, &et1).len());
+        assert!(decode_frame_with(&db, 0x18FF0000, &[0; 8]).is_empty());
+    }
+
+    #[test]
+    fn test_decode_pgn_matches_decode_frame() {
+        let mut rng = XorShift(0x9E37_79B9_7F4A_7C15);
+        for pgn in crate::database::list_supported_pgns() {
+            let can_id = crate::frame::can_id_for_pgn(pgn, 6, 0x00, 0xFF);
+            for _ in 0..20 {
+                let data = rng.next().to_le_bytes();
+                let by_pgn: Vec<_> = decode_pgn(pgn, &data).iter().map(|d| d.raw_value).collect();
+                let by_id: Vec<_> = decode_frame(can_id, &data)
+                    .iter()
+                    .map(|d| d.raw_value)
+                    .collect();
+                assert_eq!(by_pgn, by_id, "PGN {}", pgn);
+            }
+        }
+        assert!(decode_pgn(0xFF00, &[0; 8]).is_empty());
+    }
+
+    #[test]
+    fn test_decode_unknown_pgn() {
+        // Unknown PGN should return empty
+        let can_id = 0x18FF0000; // Proprietary PGN
+        let data = [0xFF; 8];
+
+        let decoded = decode_frame(can_id, &data);
+        assert!(decoded.is_empty());
+    }
+
+    // ========================================================================
+    // Panic-safety tests
+    // ========================================================================
+
+    /// Minimal xorshift PRNG so the property tests stay dependency-free and reproducible.
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_decode_random_frames_never_panics() {
+        let mut rng = XorShift(0x9E37_79B9_7F4A_7C15);
+        let known_pgns: Vec<u32> = crate::database::list_supported_pgns().collect();
+
+        for _ in 0..20_000 {
+            let r = rng.next();
+            // Half the frames target a known PGN so the SPN paths are exercised
+            let can_id = if r & 1 == 0 {
+                let pgn = known_pgns[(r >> 8) as usize % known_pgns.len()];
+                ((r >> 32) as u32 & 0x1C00_00FF) | (pgn << 8)
+            } else {
+                (r >> 16) as u32
+            };
+            let len = (rng.next() % 9) as usize;
+            let bytes = rng.next().to_le_bytes();
+            let data = &bytes[..len];
+
+            let _ = decode_frame(can_id, data);
+            let _ = decode_frame_iter(can_id, data).count();
+            let _ = decode_addressed_frame(can_id, data);
+        }
+    }
+
+    #[test]
+    fn test_decode_malformed_spn_defs_never_panics() {
+        let mut rng = XorShift(0xDEAD_BEEF_CAFE_F00D);
+        let data_types = [
+            SpnDataType::Uint8,
+            SpnDataType::Uint16,
+            SpnDataType::Uint32,
+            SpnDataType::Int8,
+            SpnDataType::Int16,
+            SpnDataType::Int32,
+        ];
+
+        for _ in 0..20_000 {
+            let r = rng.next();
+            let spn_def = SpnDef {
+                scale: 1.0,
+                offset: 0.0,
+                spn: 9000,
+                pgn: 0xFF00,
+                name: "fuzz",
+                unit: "",
+                start_byte: r as u8,
+                start_bit: (r >> 8) as u8,
+                bit_length: (r >> 16) as u8,
+                data_type: data_types[(r >> 24) as usize % data_types.len()],
+                ..SpnDef::DEFAULT
+            };
+            let len = (rng.next() % 9) as usize;
+            let bytes = rng.next().to_le_bytes();
+
+            let _ = decode_spn(&bytes[..len], &spn_def);
+            let _ = decode_spn_full(&bytes[..len], &spn_def);
+        }
+    }
+
+    #[test]
+    fn test_decode_bit_length_edge_cases() {
+        let mut spn_def = *get_spn_def(899).unwrap();
+        let data = [0xFF, 0, 0, 0, 0, 0, 0, 0];
+
+        // Full byte behind a non-zero start bit: mask must not overflow
+        spn_def.bit_length = 8;
+        spn_def.start_bit = 4;
+        assert_eq!(decode_spn(&data, &spn_def), Some(15.0));
+
+        // start_bit past the end of the byte continues into the next byte
+        spn_def.bit_length = 4;
+        spn_def.start_bit = 8;
+        assert_eq!(decode_spn(&data, &spn_def), Some(0.0));
+        assert_eq!(decode_spn(&[0xFF, 0x0A], &spn_def), Some(10.0));
+
+        // bit_length beyond 64 is clamped to the type width when checking for
+        // "not available", so 0xFF is still the 8-bit "not available" code
+        spn_def.bit_length = 200;
+        spn_def.start_bit = 0;
+        assert_eq!(decode_spn(&data, &spn_def), None);
+        assert_eq!(decode_spn(&[0x7F], &spn_def), Some(127.0));
+    }
+
+    #[test]
+    fn test_decode_64_bit_custom_spn() {
+        static SPN_DEF_64: SpnDef = SpnDef {
+            bit_length: 64,
+            ..signed_spn_def(SpnDataType::Uint32, 0, 1.0)
+        };
+        let spn_def = SPN_DEF_64;
+        assert_eq!(bit_mask(64), u64::MAX);
+        assert_eq!(NOT_AVAILABLE_THRESHOLD[64], u64::MAX - 2);
+
+        // Checked against the 32-bit special codes of the data type
+        assert_eq!(decode_spn(&[0xFF; 8], &spn_def), None);
+        assert_eq!(decode_spn(&[0xFE, 0xFF, 0xFF, 0xFF], &spn_def), None);
+        assert_eq!(
+            decode_spn_status(&[0xFF; 4], &SPN_DEF_64).status,
+            SpnStatus::NotAvailable
+        );
+        assert_eq!(
+            decode_spn(&[0xFD, 0xFF, 0xFF, 0xFF], &spn_def),
+            Some(4_294_967_293.0)
+        );
+        assert_eq!(decode_spn_milli(&[0x01, 0, 0, 0], &spn_def), Some(1000));
+
+        // Signed 64-bit definition masks sign-extended raws without overflowing
+        let signed = SpnDef {
+            bit_length: 64,
+            ..signed_spn_def(SpnDataType::Int32, 0, 1.0)
+        };
+        assert_eq!(decode_spn(&[0xFF, 0xFF, 0xFF, 0xFF], &signed), None);
+        assert_eq!(decode_spn(&[0xFB, 0xFF, 0xFF, 0xFF], &signed), Some(-5.0));
+    }
+
+    // ========================================================================
+    // NOT_AVAILABLE_THRESHOLD table tests
+    // ========================================================================
+
+    #[test]
+    fn test_not_available_threshold_table() {
+        // Verify the precomputed table is correct
+        // J1939 reserves last two values: (2^N - 2) = error, (2^N - 1) = n/a
+        // So threshold = (2^N - 3), values > threshold are special
+        assert_eq!(NOT_AVAILABLE_THRESHOLD[0], 0);
+        assert_eq!(NOT_AVAILABLE_THRESHOLD[1], 0);
+        assert_eq!(NOT_AVAILABLE_THRESHOLD[2], 1); // 2^2 - 3 = 1
+        assert_eq!(NOT_AVAILABLE_THRESHOLD[4], 13); // 2^4 - 3 = 13
+        assert_eq!(NOT_AVAILABLE_THRESHOLD[8], 253); // 2^8 - 3 = 253
+        assert_eq!(NOT_AVAILABLE_THRESHOLD[16], 65533); // 2^16 - 3
+        assert_eq!(NOT_AVAILABLE_THRESHOLD[32], 0xFFFFFFFD); // 2^32 - 3
+    }
+
+    // ========================================================================
+    // format_frame
+    // ========================================================================
+
+    #[test]
+    fn test_format_frame_known_pgn() {
+        let data = [0xFF, 0xFF, 0xFF, 0x20, 0x4E, 0xFF, 0xFF, 0xFF];
+        let text = format_frame(0x0CF00400, &data);
+        let mut lines = text.lines();
+        assert_eq!(
+            lines.next(),
+            Some("CAN ID 0x0CF00400: priority 3, PGN 61444 (EEC1), SA 0x00")
+        );
+        assert_eq!(lines.next(), Some("  engine_speed = 2500 RPM"));
+        assert_eq!(lines.next(), None);
+    }
+
+    #[test]
+    fn test_format_frame_unknown_pgn() {
+        let text = format_frame(0x18FF0021, &[0x01, 0xAB, 0xFF]);
+        assert_eq!(
+            text,
+            "CAN ID 0x18FF0021: priority 6, PGN 65280 (unknown), SA 0x21\n  data: 01 AB FF\n"
+        );
+    }
+
+    #[test]
+    fn test_format_frame_pdu1_shows_destination() {
+        let text = format_frame(0x18EA0021, &[0x00, 0xEE, 0x00]);
+        assert!(text
+            .starts_with("CAN ID 0x18EA0021: priority 6, PGN 59904 (unknown), DA 0x00, SA 0x21\n"));
+    }
+
+    #[test]
+    fn test_format_frame_no_valid_spns() {
+        let text = format_frame(0x0CF00400, &[0xFF; 8]);
+        assert!(text.ends_with("  (no valid SPNs)\n"));
+    }
+
+    // ========================================================================
+    // decode_frame_result
+    // ========================================================================
+
+    #[test]
+    fn test_decode_frame_result_unknown_pgn() {
+        assert!(matches!(
+            decode_frame_result(0x18FF0000, &[0; 8]),
+            FrameDecode::UnknownPgn(0xFF00)
+        ));
+    }
+
+    #[test]
+    fn test_decode_frame_result_transport() {
+        // BAM announcement and data packet from 0x00 to global
+        let bam = [0x20, 0x0E, 0x00, 0x02, 0xFF, 0xCA, 0xFE, 0x00];
+        assert!(matches!(
+            decode_frame_result(0x1CECFF00, &bam),
+            FrameDecode::Transport(0xEC00)
+        ));
+        assert!(matches!(
+            decode_frame_result(0x1CEBFF00, &[0x01; 8]),
+            FrameDecode::Transport(0xEB00)
+        ));
+        assert!(decode_frame(0x1CEBFF00, &[0x01; 8]).is_empty());
+
+        // Request2 is not transport traffic
+        assert!(matches!(
+            decode_frame_result(0x18C90021, &[0; 8]),
+            FrameDecode::UnknownPgn(0xC900)
+        ));
+    }
+
+    #[test]
+    fn test_decode_frame_result_truncated() {
+        match decode_frame_result(0x0CF00400, &[0, 0, 0, 0x20, 0x4E]) {
+            FrameDecode::Truncated { pgn, expected_len } => {
+                assert_eq!(pgn, 61444);
+                assert_eq!(expected_len, 8);
+            }
+            other => panic!("expected Truncated, got {:?}", other),
+        }
+        assert!(matches!(
+            decode_frame_result(0x0CF00400, &[]),
+            FrameDecode::Truncated { .. }
+        ));
+    }
+
+    #[test]
+    fn test_decode_frame_result_decoded() {
+        let data = [0xFF, 0xFF, 0xFF, 0x20, 0x4E, 0xFF, 0xFF, 0xFF];
+        match decode_frame_result(0x0CF00400, &data) {
+            FrameDecode::Decoded(spns) => {
+                assert_eq!(spns.len(), 1);
+                assert_eq!(spns[0].spn, 190);
+            }
+            other => panic!("expected Decoded, got {:?}", other),
+        }
+
+        // All "not available" is still a well-formed frame
+        assert!(matches!(
+            decode_frame_result(0x0CF00400, &[0xFF; 8]),
+            FrameDecode::Decoded(ref spns) if spns.is_empty()
+        ));
+    }
+
+    #[test]
+    fn test_decode_frame_result_expected_len_covers_all_pgns() {
+        for pgn in crate::database::list_supported_pgns() {
+            let can_id = (6 << 26) | (pgn << 8);
+            let FrameDecode::Decoded(_) = decode_frame_result(can_id, &[0xFF; 8]) else {
+                panic!("PGN {} does not fit in 8 bytes", pgn);
+            };
+        }
+    }
+
+    // ========================================================================
+    // decode_frames
+    // ========================================================================
+
+    #[test]
+    fn test_decode_frames_skips_unknown() {
+        let frames = [
+            (0x0CF00400, [0xFF, 0xFF, 0xFF, 0x20, 0x4E, 0xFF, 0xFF, 0xFF]),
+            (0x18FF0000, [0x00; 8]),
+            (0x0CF00400, [0xFF; 8]),
+        ];
+        let decoded: Vec<_> = decode_frames(frames).collect();
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[0].1.len(), 1);
+        assert_eq!(decoded[0].1[0].spn, 190);
+        // Known PGN with nothing valid is still reported
+        assert!(decoded[1].1.is_empty());
+    }
+
+    #[test]
+    fn test_decode_frames_is_lazy() {
+        let mut calls = 0;
+        let frames = (0..100).map(|_| {
+            calls += 1;
+            (0x0CF00400u32, vec![0u8; 8])
+        });
+        let first: Vec<_> = decode_frames(frames).take(2).collect();
+        assert_eq!(first.len(), 2);
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn test_decode_frame_skips_spns_past_payload() {
+        // EEC1 cut after engine speed (bytes 3-4)
+        let data = [0x01, 0x7D, 0x7D, 0x20, 0x4E];
+        for spn in decode_frame(0x0CF00400, &data) {
+            assert!(get_spn_def(spn.spn).unwrap().required_len() <= data.len());
+        }
+        assert!(decode_frame(0x0CF00400, &data).iter().any(|s| s.spn == 190));
+        assert_eq!(decode_spn_by_number(190, &data[..4]), None);
+    }
+
+    #[test]
+    fn test_decode_eec1_real_payload() {
+        // Torque mode 1 with 0.375% fractional torque, driver's demand 15%,
+        // actual torque 35%, 1500 RPM, controlling SA 0x00, starter mode 0
+        let data = [0x31, 0x8C, 0xA0, 0xE0, 0x2E, 0x00, 0xF0, 0xFF];
+        let values = decode_frame_map(0x0CF00400, &data);
+
+        assert_eq!(values["engine_torque_mode"], 1.0);
+        assert_eq!(values["actual_engine_percent_torque_fractional"], 0.375);
+        assert_eq!(values["drivers_demand_engine_percent"], 15.0);
+        assert_eq!(values["actual_engine_percent_torque"], 35.0);
+        assert_eq!(values["engine_speed"], 1500.0);
+        assert_eq!(values["eec1_source_address"], 0.0);
+        assert_eq!(values["engine_starter_mode"], 0.0);
+        assert!(!values.contains_key("engine_demand_percent_torque"));
+
+        assert_eq!(decode_spn_by_number(512, &data), Some(15.0));
+        assert_eq!(decode_spn_by_number(513, &data), Some(35.0));
+    }
+
+    // ========================================================================
+    // decode_frame_strict
+    // ========================================================================
+
+    #[test]
+    fn test_decode_frame_strict_length() {
+        let data = [0xFF, 0xFF, 0xFF, 0x20, 0x4E, 0xFF, 0xFF, 0xFF];
+        assert_eq!(decode_frame_strict(0x0CF00400, &data).unwrap().len(), 1);
+
+        assert_eq!(
+            decode_frame_strict(0x0CF00400, &data[..7]).unwrap_err(),
+            DecodeError::LengthMismatch {
+                pgn: 61444,
+                expected: 8,
+                actual: 7
+            }
+        );
+        // Longer than expected is also rejected
+        assert!(decode_frame_strict(0x0CF00400, &[0u8; 9]).is_err());
+    }
+
+    #[test]
+    fn test_decode_frame_strict_skips_unchecked_pgns() {
+        // Unknown PGN
+        assert!(decode_frame_strict(0x18FF0000, &[0; 3]).unwrap().is_empty());
+        // Variable-length PGN (VI)
+        assert!(decode_frame_strict(0x18FEEC00, &[b'1'; 17]).is_ok());
+    }
+
+    #[test]
+    fn test_decode_error_display() {
+        let err = DecodeError::LengthMismatch {
+            pgn: 61444,
+            expected: 8,
+            actual: 5,
+        };
+        assert_eq!(err.to_string(), "PGN 61444 expects 8 data bytes, got 5");
+
+        let err = DecodeError::SpnTruncated {
+            spn: 190,
+            required: 5,
+            actual: 3,
+        };
+        assert_eq!(err.to_string(), "SPN 190 needs 5 data bytes, got 3");
+
+        let err = DecodeError::InvalidDefinition {
+            spn: 9001,
+            reason: "bit_length is zero",
+        };
+        assert_eq!(err.to_string(), "SPN 9001: bit_length is zero");
+    }
+
+    #[test]
+    fn test_decode_frame_collect() {
+        let data = [0x01, 0x8C, 0xA0, 0xE0, 0x2E, 0x00, 0xF0, 0xFF];
+        let (decoded, errors) = decode_frame_collect(0x0CF00400, &data);
+        assert!(errors.is_empty());
+        assert_eq!(decoded.len(), decode_frame(0x0CF00400, &data).len());
+
+        let (decoded, errors) = decode_frame_collect(0x0CF00400, &data[..3]);
+        let decoded: Vec<_> = decoded.iter().map(|s| s.spn).collect();
+        assert_eq!(decoded, vec![899, 4154, 512, 513]);
+        assert!(errors
+            .iter()
+            .all(|(spn, err)| matches!(err, DecodeError::SpnTruncated { spn: s, actual: 3, .. } if s == spn)));
+        let truncated: Vec<_> = errors.iter().map(|(spn, _)| *spn).collect();
+        assert_eq!(truncated, vec![190, 1483, 1675, 2432]);
+
+        // "Not available" is not an error
+        let (decoded, errors) = decode_frame_collect(0x0CF00400, &[0xFF; 8]);
+        assert!(decoded.is_empty() && errors.is_empty());
+
+        let (decoded, errors) = decode_frame_collect(0x18FF0000, &[]);
+        assert!(decoded.is_empty() && errors.is_empty());
+    }
+
+    #[test]
+    fn test_check_spn_definitions() {
+        let data = [0u8; 8];
+        // Every built-in SPN definition is valid
+        for spn_def in crate::database::SPN_DEFINITIONS {
+            assert_eq!(check_spn(&data, spn_def), Ok(()), "SPN {}", spn_def.spn);
+        }
+
+        let valid = signed_spn_def(SpnDataType::Uint16, 0, 1.0);
+        let reason = |spn_def: SpnDef| match check_spn(&data, &spn_def) {
+            Err(DecodeError::InvalidDefinition { spn: 9000, reason }) => reason,
+            other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(
+            reason(SpnDef {
+                bit_length: 0,
+                ..valid
+            }),
+            "bit_length is zero"
+        );
+        assert_eq!(
+            reason(SpnDef {
+                bit_length: 17,
+                ..valid
+            }),
+            "bit_length exceeds the data type width"
+        );
+        assert_eq!(
+            reason(SpnDef {
+                scale: 0.0,
+                ..valid
+            }),
+            "scale is zero or not finite"
+        );
+        assert_eq!(
+            reason(SpnDef {
+                offset: f64::NAN,
+                ..valid
+            }),
+            "offset is not finite"
+        );
+    }
+
+    #[test]
+    fn test_decode_frame_at() {
+        let data = [0x01, 0x8C, 0xA0, 0xE0, 0x2E, 0x00, 0xF0, 0xFF];
+        let timed = decode_frame_at(0x0CF00400, &data, 12.5);
+        let plain = decode_frame(0x0CF00400, &data);
+
+        assert_eq!(timed.len(), plain.len());
+        for (t, p) in timed.iter().zip(&plain) {
+            assert_eq!(t.timestamp, 12.5);
+            assert_eq!(t.spn.spn, p.spn);
+            assert_eq!(t.spn.value, p.value);
+        }
+        assert!(decode_frame_at(0x18FF0000, &data, 0.0).is_empty());
+    }
+
+    #[test]
+    fn test_decode_frame_buffer_valid_prefix() {
+        // Previous frame left 0x00 in the tail of the buffer
+        let mut buf = [0x00u8; 64];
+        buf[..5].copy_from_slice(&[0xFF, 0xFF, 0xFF, 0x20, 0x4E]);
+
+        let partial = decode_frame_buffer(0x0CF00400, &buf, 5);
+        let spns: Vec<u32> = partial.iter().map(|s| s.spn).collect();
+        assert_eq!(spns, vec![190]);
+
+        // Without the cut-off, stale zeros in bytes 5-7 decode as real values
+        let stale = decode_frame_buffer(0x0CF00400, &buf, 8);
+        assert!(stale.iter().any(|s| s.spn == 1483));
+
+        assert!(decode_frame_buffer(0x0CF00400, &buf, 0).is_empty());
+        assert_eq!(decode_frame_buffer(0x0CF00400, &buf[..5], 100).len(), 1);
     }
 
     #[test]
-    fn test_decode_not_available() {
-        // 0xFF (all 1s) means "not available" for 8-bit values
-        let spn_def = get_spn_def(110).unwrap();
-        let data = [0xFF, 0, 0, 0, 0, 0, 0, 0];
-        let value = decode_spn(&data, spn_def);
-        assert!(value.is_none());
+    fn test_decode_frame_with_id() {
+        let data = [0x01, 0x8C, 0xA0, 0xE0, 0x2E, 0x00, 0xF0, 0xFF];
+        for can_id in [0x0CF00400, 0x18FEEE00, 0x18FF0000] {
+            let id = parse_can_id(can_id);
+            let with_id = decode_frame_with_id(&id, &data);
+            let plain = decode_frame(can_id, &data);
+            assert_eq!(with_id.len(), plain.len());
+            assert!(with_id.iter().zip(&plain).all(|(a, b)| a.approx_eq(b, 0.0)));
+        }
     }
 
     #[test]
-    fn test_decode_frame_eec1() {
-        // EEC1 (PGN 61444) with some test data
-        let can_id = 0x0CF00400;
-        let data = [0x00, 0x00, 0x00, 0x20, 0x4E, 0x00, 0x00, 0x00];
+    fn test_decode_frame_with_source() {
+        // Wheel-based speed 80 km/h from two different ECUs
+        let data = [0xFF, 0x00, 0x50, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+        let engine = decode_frame_with_source(0x18FEF100, &data);
+        let body = decode_frame_with_source(0x18FEF121, &data);
+        assert_eq!(engine.len(), decode_frame(0x18FEF100, &data).len());
+
+        let speed =
+            |sourced: &[SourcedDecodedSpn]| *sourced.iter().find(|s| s.spn.spn == 84).unwrap();
+        let (engine, body) = (speed(&engine), speed(&body));
+        assert_eq!((engine.pgn, engine.source_address), (65265, 0x00));
+        assert_eq!((body.pgn, body.source_address), (65265, 0x21));
+        assert_eq!(engine.spn.value, 80.0);
+        assert_eq!(body.spn.value, 80.0);
+
+        assert!(decode_frame_with_source(0x18FF0000, &data).is_empty());
+    }
 
-        let decoded = decode_frame(can_id, &data);
-        assert!(!decoded.is_empty());
+    #[test]
+    fn test_decode_multiplexed_spns() {
+        // Byte 0 selects what bytes 1-2 carry: mode 1 = pressure, mode 2 = temperature
+        static PRESSURE: SpnDef = SpnDef {
+            spn: 9101,
+            pgn: 0xFF10,
+            start_byte: 1,
+            bit_length: 16,
+            data_type: SpnDataType::Uint16,
+            scale: 0.5,
+            mux_byte: Some(0),
+            mux_value: Some(1),
+            ..SpnDef::DEFAULT
+        };
+        static TEMPERATURE: SpnDef = SpnDef {
+            spn: 9102,
+            offset: -40.0,
+            mux_value: Some(2),
+            ..PRESSURE
+        };
+        let spn_defs = [&PRESSURE, &TEMPERATURE];
+        let decode = |data: &[u8]| -> Vec<(u32, f64)> {
+            present_in(&spn_defs, data)
+                .filter_map(|spn_def| decode_spn_full(data, spn_def))
+                .map(|d| (d.spn, d.value))
+                .collect()
+        };
+
+        assert_eq!(decode(&[1, 0xC8, 0x00]), vec![(9101, 100.0)]);
+        assert_eq!(decode(&[2, 0xC8, 0x00]), vec![(9102, 60.0)]);
+        // Unknown selector, or no selector byte at all
+        assert!(decode(&[3, 0xC8, 0x00]).is_empty());
+        assert!(decode(&[]).is_empty());
+    }
 
-        // Should contain engine speed (SPN 190)
-        let engine_speed = decoded.iter().find(|d| d.spn == 190);
-        assert!(engine_speed.is_some());
-        assert_eq!(engine_speed.unwrap().value, 2500.0);
+    #[test]
+    fn test_multiplexed_frame_through_public_api() {
+        use crate::database::TEST_MUX_PGN;
+        use crate::encoder::FrameBuilder;
+
+        // Byte 0 selects pressure (1) or temperature (2)
+        let (can_id, data) = FrameBuilder::new(TEST_MUX_PGN, 0x80)
+            .set_spn(9101, 100.0)
+            .unwrap()
+            .build();
+        assert_eq!(data[..2], [1, 25]);
+        let decoded: Vec<_> = decode_frame(can_id, &data)
+            .iter()
+            .map(|s| (s.spn, s.value))
+            .collect();
+        assert_eq!(decoded, vec![(9101, 100.0)]);
+
+        let (_, data) = FrameBuilder::new(TEST_MUX_PGN, 0x80)
+            .set_spn(9102, 60.0)
+            .unwrap()
+            .build();
+        assert_eq!(data[..2], [2, 100]);
+        let decoded: Vec<_> = decode_pgn(TEST_MUX_PGN, &data)
+            .iter()
+            .map(|s| (s.spn, s.value))
+            .collect();
+        assert_eq!(decoded, vec![(9102, 60.0)]);
+
+        // A selector no SPN is defined for decodes nothing
+        let mut data = data;
+        data[0] = 3;
+        assert!(decode_frame(can_id, &data).is_empty());
+        assert!(decode_pgn(TEST_MUX_PGN, &data).is_empty());
     }
 
     #[test]
-    fn test_decode_spn_by_number() {
-        let data = [130u8, 0, 0, 0, 0, 0, 0, 0];
-        let value = decode_spn_by_number(110, &data);
-        assert_eq!(value, Some(90.0));
+    fn test_decode_frame_verbose() {
+        let (decoded, skipped) = decode_frame_verbose(0x0CF00400, &[0x01, 0x8C, 0xA0]);
+        let decoded: Vec<_> = decoded.iter().map(|s| s.spn).collect();
+        assert_eq!(decoded, vec![899, 4154, 512, 513]);
+        assert_eq!(skipped, vec![190, 1483, 1675, 2432]);
+
+        // Full frame: nothing skipped, and "not available" is not reported as skipped
+        let (decoded, skipped) = decode_frame_verbose(0x0CF00400, &[0xFF; 8]);
+        assert!(decoded.is_empty());
+        assert!(skipped.is_empty());
 
-        // Unknown SPN
-        let value = decode_spn_by_number(99999, &data);
-        assert!(value.is_none());
+        let (decoded, skipped) = decode_frame_verbose(0x18FF0000, &[]);
+        assert!(decoded.is_empty() && skipped.is_empty());
     }
 
     #[test]
-    fn test_bit_field_extraction() {
-        // SPN 899 = Engine Torque Mode (4 bits at byte 0, bit 0)
-        let spn_def = get_spn_def(899).unwrap();
+    fn test_decode_frame_c() {
+        let data = [0x01, 0x8C, 0xA0, 0x20, 0x4E, 0x00, 0x00, 0x7D];
+        let expected = decode_frame(0x0CF00400, &data);
+        let mut out = [DecodedSpnC::default(); 16];
+        let n = decode_frame_c(0x0CF00400, &data, &mut out);
+        assert_eq!(n, expected.len());
+        for (c, spn) in out[..n].iter().zip(&expected) {
+            assert_eq!(
+                (c.spn, c.value, c.raw_value),
+                (spn.spn, spn.value, spn.raw_value)
+            );
+            assert_eq!(crate::database::unit_str(c.unit_id), Some(spn.unit));
+        }
+        assert_eq!(out[n], DecodedSpnC::default());
 
-        // Value 5 in lower nibble
-        let data = [0x05, 0, 0, 0, 0, 0, 0, 0];
-        let value = decode_spn(&data, spn_def);
-        assert_eq!(value, Some(5.0));
+        // A short array is filled, not overrun
+        let mut small = [DecodedSpnC::default(); 2];
+        assert_eq!(decode_frame_c(0x0CF00400, &data, &mut small), 2);
+        assert_eq!(small[1].spn, expected[1].spn);
+        assert_eq!(decode_frame_c(0x0CF00400, &data, &mut []), 0);
+    }
 
-        // Value 10 in lower nibble
-        let data = [0x0A, 0, 0, 0, 0, 0, 0, 0];
-        let value = decode_spn(&data, spn_def);
-        assert_eq!(value, Some(10.0));
+    #[test]
+    fn test_decode_frame_partial() {
+        // ET: right manifold complete, left manifold has only its low byte
+        let decoded = decode_frame_partial(0x18FE0700, &[0x60, 0x5A, 0xA0]);
+        let summary: Vec<_> = decoded.iter().map(|d| (d.spn.spn, d.partial)).collect();
+        assert_eq!(summary, [(2433, false), (2434, true)]);
+        assert_eq!(decoded[0].spn.value, 450.0);
+        assert_eq!(decoded[1].spn.raw_value, 0x00A0);
+
+        // Full frames decode exactly like decode_frame
+        let data = [0x01, 0x8C, 0xA0, 0x20, 0x4E, 0x00, 0x00, 0x7D];
+        let full = decode_frame_partial(0x0CF00400, &data);
+        assert!(full.iter().all(|d| !d.partial));
+        let spns: Vec<_> = full.iter().map(|d| d.spn.spn).collect();
+        let expected: Vec<_> = decode_frame(0x0CF00400, &data)
+            .iter()
+            .map(|d| d.spn)
+            .collect();
+        assert_eq!(spns, expected);
+
+        // SPNs starting past the end are skipped, not zero-filled
+        let short = decode_frame_partial(0x0CF00400, &[0x01, 0x8C, 0xA0]);
+        assert!(short.iter().all(|d| d.spn.spn != 190 && !d.partial));
+        assert!(decode_frame_partial(0x18FE0700, &[]).is_empty());
     }
 
     // ========================================================================
-    // Iterator version tests
+    // decode_frame_array
     // ========================================================================
 
     #[test]
-    fn test_decode_frame_iter() {
-        let can_id = 0x0CF00400; // EEC1
-        let data = [0x00, 0x00, 0x00, 0x20, 0x4E, 0x00, 0x00, 0x00];
-
-        // Count decoded SPNs using iterator
-        let count = decode_frame_iter(can_id, &data).count();
-        assert!(count > 0);
-
-        // Find specific SPN using iterator
-        let engine_speed = decode_frame_iter(can_id, &data)
-            .find(|d| d.spn == 190);
-        assert!(engine_speed.is_some());
-        assert_eq!(engine_speed.unwrap().value, 2500.0);
+    fn test_decode_frame_array_matches_slice() {
+        let mut rng = XorShift(0x1234_5678_9ABC_DEF0);
+        for pgn in crate::database::list_supported_pgns() {
+            for _ in 0..200 {
+                let data = rng.next().to_le_bytes();
+                let can_id = (6 << 26) | (pgn << 8);
+                let array = decode_frame_array(can_id, &data);
+                let slice = decode_frame(can_id, &data);
+                assert_eq!(array.len(), slice.len());
+                for (a, b) in array.iter().zip(&slice) {
+                    assert!(a.approx_eq(b, 0.0));
+                }
+            }
+        }
     }
 
     #[test]
-    fn test_decode_frame_iter_empty() {
-        // Unknown PGN should return empty iterator
-        let can_id = 0x00000000;
-        let data = [0xFF; 8];
-        assert_eq!(decode_frame_iter(can_id, &data).count(), 0);
+    fn test_decode_frame_array_into_appends() {
+        let data = [0xFF, 0xFF, 0xFF, 0x20, 0x4E, 0xFF, 0xFF, 0xFF];
+        let mut out = Vec::new();
+        assert_eq!(decode_frame_array_into(0x0CF00400, &data, &mut out), 1);
+        assert_eq!(decode_frame_array_into(0x0CF00400, &data, &mut out), 1);
+        assert_eq!(out.len(), 2);
+        assert_eq!(decode_frame_array_into(0x18FF0000, &data, &mut out), 0);
     }
 
-    #[test]
-    fn test_decode_frame_iter_consistency() {
-        // Iterator and Vec versions should produce same results
-        let can_id = 0x0CF00400;
-        let data = [0x05, 0x10, 0x20, 0x20, 0x4E, 0x00, 0x30, 0x40];
-
-        let vec_result: Vec<_> = decode_frame(can_id, &data);
-        let iter_result: Vec<_> = decode_frame_iter(can_id, &data).collect();
+    // ========================================================================
+    // decode_spn_f32
+    // ========================================================================
 
-        assert_eq!(vec_result.len(), iter_result.len());
-        for (v, i) in vec_result.iter().zip(iter_result.iter()) {
-            assert_eq!(v.spn, i.spn);
-            assert_eq!(v.value, i.value);
-            assert_eq!(v.raw_value, i.raw_value);
+    #[test]
+    fn test_decode_spn_f32() {
+        let mut rng = XorShift(0x0F32_0F32_0F32_0F32);
+        for spn_def in crate::database::SPN_DEFINITIONS {
+            for _ in 0..100 {
+                let data = rng.next().to_le_bytes();
+                let float = decode_spn(&data, spn_def);
+                assert_eq!(
+                    decode_spn_f32(&data, spn_def),
+                    float.map(|v| v as f32),
+                    "SPN {}",
+                    spn_def.spn
+                );
+            }
         }
+        assert_eq!(decode_spn_f32(&[0xFF], get_spn_def(110).unwrap()), None);
+        assert_eq!(
+            decode_spn_f32(&[130], get_spn_def(110).unwrap()),
+            Some(90.0)
+        );
     }
 
     // ========================================================================
-    // Boundary condition tests
+    // decode_spn_temp
     // ========================================================================
 
     #[test]
-    fn test_decode_data_too_short() {
-        // SPN 190 needs bytes 3-4 (16-bit at byte 3)
-        let spn_def = get_spn_def(190).unwrap();
+    fn test_decode_spn_temp() {
+        let coolant = get_spn_def(110).unwrap();
+        assert_eq!(
+            decode_spn_temp(&[130], coolant, TempUnit::Celsius),
+            Some(90.0)
+        );
+        let kelvin = decode_spn_temp(&[0], coolant, TempUnit::Kelvin).unwrap();
+        assert!((kelvin - 233.15).abs() < 1e-9);
+        assert_eq!(
+            decode_spn_temp(&[0], coolant, TempUnit::Fahrenheit),
+            Some(-40.0)
+        );
+        assert_eq!(decode_spn_temp(&[0xFF], coolant, TempUnit::Kelvin), None);
+
+        // Every non-temperature SPN passes through unchanged
+        let data = [0x64; 8];
+        for spn_def in crate::database::SPN_DEFINITIONS {
+            let value = decode_spn(&data, spn_def);
+            let kelvin = decode_spn_temp(&data, spn_def, TempUnit::Kelvin);
+            if spn_def.is_temperature() {
+                assert_eq!(kelvin, value.map(|c| c + 273.15), "SPN {}", spn_def.spn);
+            } else {
+                assert_eq!(kelvin, value, "SPN {}", spn_def.spn);
+            }
+        }
+    }
 
-        // Only 4 bytes, needs at least 5
-        let data = [0, 0, 0, 0x20];
-        let value = decode_spn(&data, spn_def);
-        assert!(value.is_none());
+    // ========================================================================
+    // decode_spn_milli
+    // ========================================================================
 
-        // Exactly enough bytes
-        let data = [0, 0, 0, 0x20, 0x4E];
-        let value = decode_spn(&data, spn_def);
-        assert_eq!(value, Some(2500.0));
+    #[test]
+    fn test_decode_spn_milli_known_values() {
+        // 0.125 scale, exact
+        let data = [0, 0, 0, 0x21, 0x4E, 0, 0, 0];
+        assert_eq!(
+            decode_spn_milli(&data, get_spn_def(190).unwrap()),
+            Some(2_500_125)
+        );
+        // Offset -40
+        assert_eq!(
+            decode_spn_milli(&[0], get_spn_def(110).unwrap()),
+            Some(-40_000)
+        );
+        // Not available
+        assert_eq!(decode_spn_milli(&[0xFF], get_spn_def(110).unwrap()), None);
+        assert_eq!(decode_spn_milli(&[], get_spn_def(110).unwrap()), None);
     }
 
     #[test]
-    fn test_decode_empty_data() {
-        let spn_def = get_spn_def(110).unwrap();
-        let data: [u8; 0] = [];
-        let value = decode_spn(&data, spn_def);
-        assert!(value.is_none());
+    fn test_decode_spn_milli_signed() {
+        let spn_def = signed_spn_def(SpnDataType::Int16, 0, 0.5);
+        assert_eq!(decode_spn_milli(&[0x9C, 0xFF], &spn_def), Some(-50_000)); // -100 * 0.5
     }
 
     #[test]
-    fn test_decode_not_available_16bit() {
-        // 0xFFFF means "not available" for 16-bit values
-        let spn_def = get_spn_def(190).unwrap(); // Engine speed (16-bit at byte 3)
-        let data = [0, 0, 0, 0xFF, 0xFF, 0, 0, 0];
-        let value = decode_spn(&data, spn_def);
-        assert!(value.is_none());
-
-        // 0xFFFE also means "not available" (error indicator)
-        let data = [0, 0, 0, 0xFE, 0xFF, 0, 0, 0];
-        let value = decode_spn(&data, spn_def);
-        assert!(value.is_none());
+    fn test_decode_spn_milli_matches_float() {
+        let mut rng = XorShift(0xDEAD_BEEF_1234_5678);
+        for spn_def in crate::database::SPN_DEFINITIONS {
+            for _ in 0..500 {
+                let data = rng.next().to_le_bytes();
+                let milli = decode_spn_milli(&data, spn_def);
+                let float = decode_spn(&data, spn_def);
+                assert_eq!(milli.is_some(), float.is_some(), "SPN {}", spn_def.spn);
+                if let (Some(m), Some(f)) = (milli, float) {
+                    // Off by one only when the float result sits on a rounding boundary
+                    assert!(
+                        (m as f64 - f * 1000.0).abs() <= 0.5 + 1e-6,
+                        "SPN {}: {} vs {}",
+                        spn_def.spn,
+                        m,
+                        f
+                    );
+                }
+            }
+        }
     }
 
     #[test]
-    fn test_decode_32bit_values() {
-        // SPN 247 = Engine Total Hours (32-bit at byte 0)
-        let spn_def = get_spn_def(247).unwrap();
-
-        // 72000 raw * 0.05 = 3600 hours
-        // 72000 = 0x00011940 -> little-endian [0x40, 0x19, 0x01, 0x00]
-        let data = [0x40, 0x19, 0x01, 0x00, 0, 0, 0, 0];
-        let value = decode_spn(&data, spn_def);
-        assert_eq!(value, Some(3600.0));
+    fn test_f64_parts_and_rounding() {
+        assert_eq!(f64_parts(0.125), Some((1 << 52, -55)));
+        assert_eq!(f64_parts(0.0), Some((0, -1074)));
+        assert_eq!(f64_parts(f64::NAN), None);
+        assert_eq!(mul_pow2_round(5, -1), Some(3)); // 2.5 -> 3
+        assert_eq!(mul_pow2_round(-5, -1), Some(-2)); // -2.5 -> -2
+        assert_eq!(mul_pow2_round(3, 2), Some(12));
+        assert_eq!(mul_pow2_round(1, 200), None);
+        assert_eq!(mul_pow2_round(0, 200), Some(0));
+        assert_eq!(mul_pow2_round(1, -200), Some(0));
     }
 
     #[test]
-    fn test_decode_32bit_not_available() {
-        // SPN 247 = Engine Total Hours (32-bit)
-        let spn_def = get_spn_def(247).unwrap();
-
-        // 0xFFFFFFFF means "not available"
-        let data = [0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0];
-        let value = decode_spn(&data, spn_def);
-        assert!(value.is_none());
+    fn test_decode_ccvs_brake_and_clutch() {
+        // Parking brake off, 80 km/h, cruise enabled, brake pedal pressed,
+        // clutch released, remaining fields not available
+        let data = [0xF0, 0x00, 0x50, 0x14, 0xFF, 0xFF, 0xFF, 0xFF];
+        let values = decode_frame_map(0x18FEF100, &data);
+
+        assert_eq!(values["wheel_based_vehicle_speed"], 80.0);
+        assert_eq!(values["parking_brake_switch"], 0.0);
+        assert_eq!(values["cruise_control_active"], 0.0);
+        assert_eq!(values["cruise_control_enable_switch"], 1.0);
+        assert_eq!(values["brake_switch"], 1.0);
+        assert_eq!(values["clutch_switch"], 0.0);
+        assert!(!values.contains_key("pto_state"));
     }
 
     #[test]
-    fn test_decode_zero_value() {
-        let spn_def = get_spn_def(110).unwrap();
-        let data = [0u8, 0, 0, 0, 0, 0, 0, 0];
-        let value = decode_spn(&data, spn_def);
-        // 0 * 1.0 + (-40) = -40
-        assert_eq!(value, Some(-40.0));
+    fn test_decode_def_tank_level() {
+        // DEF level raw 200 * 0.4 = 80%, tank temperature 65 - 40 = 25 C
+        let data = [200, 65, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+        let values = decode_frame_map(0x18FE563D, &data);
+
+        assert_eq!(values["aftertreatment_1_def_tank_level"], 80.0);
+        assert_eq!(values["aftertreatment_1_def_tank_temperature"], 25.0);
+        assert_eq!(values.len(), 2);
+
+        // Empty tank is a valid reading, not "not available"
+        let values = decode_frame_map(0x18FE563D, &[0x00, 0xFF]);
+        assert_eq!(values["aftertreatment_1_def_tank_level"], 0.0);
     }
 
     #[test]
-    fn test_decode_negative_offset() {
-        // SPN 110 has offset -40
-        let spn_def = get_spn_def(110).unwrap();
-
-        // Raw 40 -> 40 * 1.0 + (-40) = 0°C
-        let data = [40u8, 0, 0, 0, 0, 0, 0, 0];
-        let value = decode_spn(&data, spn_def);
-        assert_eq!(value, Some(0.0));
+    fn test_decode_dd_fuel_level() {
+        // Fuel level 1 raw 150 * 0.4 = 60%, cargo temperature 0x2630 = 32.5 C
+        let data = [0xFF, 150, 0xFF, 0xFF, 0x30, 0x26, 0xFF, 0xFF];
+        let values = decode_frame_map(0x18FEFC17, &data);
+
+        assert_eq!(values["fuel_level_1"], 60.0);
+        assert_eq!(values["cargo_ambient_temperature"], 32.5);
+        assert_eq!(values.len(), 2);
     }
 
+    #[cfg(feature = "embedded-can")]
     #[test]
-    fn test_decode_full_returns_metadata() {
-        let spn_def = get_spn_def(190).unwrap();
-        let data = [0, 0, 0, 0x20, 0x4E, 0, 0, 0];
+    fn test_decode_can_frame() {
+        use embedded_can::{ExtendedId, Frame, Id, StandardId};
 
-        let decoded = decode_spn_full(&data, spn_def).unwrap();
-        assert_eq!(decoded.spn, 190);
-        assert_eq!(decoded.name, "engine_speed");
-        assert_eq!(decoded.unit, "RPM");
-        assert_eq!(decoded.raw_value, 20000);
-        assert_eq!(decoded.value, 2500.0);
-    }
+        struct TestFrame {
+            id: Id,
+            data: [u8; 8],
+            remote: bool,
+        }
 
-    // ========================================================================
-    // Bit field tests
-    // ========================================================================
+        impl Frame for TestFrame {
+            fn new(id: impl Into<Id>, data: &[u8]) -> Option<Self> {
+                let mut buf = [0u8; 8];
+                buf.get_mut(..data.len())?.copy_from_slice(data);
+                Some(Self {
+                    id: id.into(),
+                    data: buf,
+                    remote: false,
+                })
+            }
+            fn new_remote(id: impl Into<Id>, _dlc: usize) -> Option<Self> {
+                let mut frame = Self::new(id, &[])?;
+                frame.remote = true;
+                Some(frame)
+            }
+            fn is_extended(&self) -> bool {
+                matches!(self.id, Id::Extended(_))
+            }
+            fn is_remote_frame(&self) -> bool {
+                self.remote
+            }
+            fn id(&self) -> Id {
+                self.id
+            }
+            fn dlc(&self) -> usize {
+                self.data.len()
+            }
+            fn data(&self) -> &[u8] {
+                &self.data
+            }
+        }
 
-    #[test]
-    fn test_bit_field_with_offset() {
-        // SPN 559 = Accelerator Pedal Kickdown (2 bits at byte 0, bit 2)
-        // For 2-bit fields: 0,1 = valid, 2 = error, 3 = n/a
-        let spn_def = get_spn_def(559).unwrap();
+        let data = [0xFF, 0xFF, 0xFF, 0x20, 0x4E, 0xFF, 0xFF, 0xFF];
+        let eec1 = ExtendedId::new(0x0CF00400).unwrap();
+        let decoded = decode_can_frame(&TestFrame::new(eec1, &data).unwrap());
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].value, 2500.0);
 
-        // Value 0b01 at bit position 2 = 0b0100 = 0x04
-        let data = [0x04, 0, 0, 0, 0, 0, 0, 0];
-        let value = decode_spn(&data, spn_def);
-        assert_eq!(value, Some(1.0));
+        let standard = StandardId::new(0x400).unwrap();
+        assert!(decode_can_frame(&TestFrame::new(standard, &data).unwrap()).is_empty());
+        assert!(decode_can_frame(&TestFrame::new_remote(eec1, 8).unwrap()).is_empty());
+    }
 
-        // Value 0b00 at bit position 2 = 0b0000 = 0x00
-        let data = [0x00, 0, 0, 0, 0, 0, 0, 0];
-        let value = decode_spn(&data, spn_def);
-        assert_eq!(value, Some(0.0));
+    #[test]
+    fn test_torque_percent_boundaries() {
+        // 1-byte percent torque SPNs: raw 0..=250 spans -125..=+125%,
+        // 0xFE is the error indicator and 0xFF "not available" (J1939-71).
+        for spn in [512, 513, 514, 2432, 2978] {
+            let spn_def = get_spn_def(spn).unwrap();
+            let mut data = [0xFFu8; 8];
+            let byte = spn_def.start_byte as usize;
+
+            data[byte] = 250;
+            assert_eq!(decode_spn(&data, spn_def), Some(125.0), "SPN {}", spn);
+            data[byte] = 0;
+            assert_eq!(decode_spn(&data, spn_def), Some(-125.0), "SPN {}", spn);
+
+            data[byte] = 0xFE;
+            assert_eq!(decode_spn(&data, spn_def), None, "SPN {}", spn);
+            assert_eq!(decode_spn_status(&data, spn_def).status, SpnStatus::Error);
+            data[byte] = 0xFF;
+            assert_eq!(decode_spn(&data, spn_def), None, "SPN {}", spn);
+            assert_eq!(
+                decode_spn_status(&data, spn_def).status,
+                SpnStatus::NotAvailable
+            );
+        }
+    }
 
-        // Value 0b11 at bit position 2 = 0b1100 = 0x0C (not available)
-        let data = [0x0C, 0, 0, 0, 0, 0, 0, 0];
-        let value = decode_spn(&data, spn_def);
-        assert!(value.is_none());
+    #[test]
+    fn test_extract_bits() {
+        let data = [0x21, 0x43, 0x65, 0x87, 0xA9, 0xCB, 0xED, 0x0F];
+        assert_eq!(extract_bits(&data, 0, 8), Some(0x21));
+        assert_eq!(extract_bits(&data, 0, 64), Some(0x0FED_CBA9_8765_4321));
+        assert_eq!(extract_bits(&data, 4, 16), Some(0x5432));
+        assert_eq!(extract_bits(&data, 63, 1), Some(0));
+        assert_eq!(extract_bits(&data, 59, 4), Some(0x1));
+
+        // Unaligned 64-bit field spanning 9 bytes
+        let mut wide = [0xFF; 9];
+        wide[0] = 0x0F;
+        wide[8] = 0x00;
+        assert_eq!(extract_bits(&wide, 4, 64), Some(0x0FFF_FFFF_FFFF_FFF0));
+
+        assert_eq!(extract_bits(&data, 0, 0), None);
+        assert_eq!(extract_bits(&data, 0, 65), None);
+        assert_eq!(extract_bits(&data, 60, 5), None);
+        assert_eq!(extract_bits(&data, usize::MAX, 1), None);
+        assert_eq!(extract_bits(&[], 0, 1), None);
     }
 
     #[test]
-    fn test_bit_field_max_valid() {
-        // SPN 899 = Engine Torque Mode (4 bits)
-        // Max valid value for 4 bits is 13 (14 and 15 are "not available")
-        let spn_def = get_spn_def(899).unwrap();
+    fn test_extract_bits_with_sawtooth() {
+        // Reference: walk the signal bit by bit from its MSB, as DBC tools do
+        fn walk(data: &[u8], start_bit: usize, len: usize) -> Option<u64> {
+            let (mut pos, mut value) = (start_bit, 0u64);
+            for i in 0..len {
+                let bit = (data.get(pos / 8)? >> (pos % 8)) & 1;
+                value = value << 1 | bit as u64;
+                if i + 1 < len {
+                    pos = if pos % 8 == 0 { pos + 15 } else { pos - 1 };
+                }
+            }
+            Some(value)
+        }
 
-        let data = [0x0D, 0, 0, 0, 0, 0, 0, 0]; // 13
-        let value = decode_spn(&data, spn_def);
-        assert_eq!(value, Some(13.0));
+        let mut rng = XorShift(0x5A77_7007_0DBC_0DBC);
+        for _ in 0..200 {
+            let data = rng.next().to_le_bytes();
+            for start_bit in 0..64 {
+                for len in 1..=64 {
+                    assert_eq!(
+                        extract_bits_with(&data, start_bit, len, BitNumbering::DbcSawtooth),
+                        walk(&data, start_bit, len),
+                        "{}|{}@0",
+                        start_bit,
+                        len
+                    );
+                }
+            }
+        }
 
-        let data = [0x0E, 0, 0, 0, 0, 0, 0, 0]; // 14 - not available
-        let value = decode_spn(&data, spn_def);
-        assert!(value.is_none());
+        let data = [0x21, 0x43, 0x65, 0x87, 0xA9, 0xCB, 0xED, 0x0F];
+        assert_eq!(
+            extract_bits_with(&data, 7, 64, BitNumbering::DbcSawtooth),
+            Some(0x2143_6587_A9CB_ED0F)
+        );
+        assert_eq!(
+            extract_bits_with(&data, 4, 16, BitNumbering::J1939Lsb),
+            extract_bits(&data, 4, 16)
+        );
+        assert_eq!(
+            extract_bits_with(&data, 7, 0, BitNumbering::DbcSawtooth),
+            None
+        );
+        assert_eq!(
+            extract_bits_with(&data, 7, 65, BitNumbering::DbcSawtooth),
+            None
+        );
+        assert_eq!(
+            extract_bits_with(&data, usize::MAX, 1, BitNumbering::DbcSawtooth),
+            None
+        );
     }
 
-    // ========================================================================
-    // Multiple PGN tests
-    // ========================================================================
-
     #[test]
-    fn test_decode_multiple_pgns() {
-        // ET1 (PGN 65262) - Engine Temperature
-        let et1_can_id = 0x18FEEE00;
-        let et1_data = [130u8, 50, 0, 0, 0, 0, 40, 0]; // coolant=90°C, fuel=10°C
-
-        let decoded = decode_frame(et1_can_id, &et1_data);
-
-        // Find coolant temperature
-        let coolant = decoded.iter().find(|d| d.spn == 110);
-        assert!(coolant.is_some());
-        assert_eq!(coolant.unwrap().value, 90.0);
+    fn test_extract_bits_matches_spn_layout() {
+        // Engine speed is bytes 3-4; coolant temperature byte 0
+        let data = [0x82, 0xFF, 0xFF, 0x20, 0x4E, 0xFF, 0xFF, 0xFF];
+        let speed = get_spn_def(190).unwrap();
+        assert_eq!(extract_bits(&data, 24, 16), extract_raw_value(&data, speed));
+        assert_eq!(extract_bits(&data, 0, 8), Some(0x82));
+
+        // 2-bit kickdown switch at byte 0 bit 2
+        let kickdown = get_spn_def(559).unwrap();
+        assert_eq!(
+            extract_bits(&data, 2, 2),
+            extract_raw_value(&data, kickdown)
+        );
+    }
 
-        // Find fuel temperature
-        let fuel = decoded.iter().find(|d| d.spn == 174);
-        assert!(fuel.is_some());
-        assert_eq!(fuel.unwrap().value, 10.0);
+    #[test]
+    fn test_kelvin_offset_temperatures_are_exact() {
+        // scale 0.03125 = 1/32 and offset -273 are exact in binary, and
+        // mul_add rounds once, so every raw code decodes bit-exactly.
+        for spn in [79, 170, 171, 173, 175, 176] {
+            let spn_def = get_spn_def(spn).unwrap();
+            assert_eq!((spn_def.scale, spn_def.offset), (0.03125, -273.0));
+            let start = spn_def.start_byte as usize;
+            let decode = |raw: u16| {
+                let mut data = [0xFFu8; 8];
+                data[start..start + 2].copy_from_slice(&raw.to_le_bytes());
+                decode_spn(&data, spn_def)
+            };
+
+            assert_eq!(decode(0), Some(-273.0), "SPN {}", spn);
+            assert_eq!(decode(8736), Some(0.0), "SPN {}", spn);
+            assert_eq!(decode(0xFAFF), Some(1734.96875), "SPN {}", spn);
+
+            for raw in 0..=spn_def.max_valid_raw() as u16 {
+                // Integer-then-divide reference: (raw - 273 * 32) / 32
+                let expected = (raw as i32 - 8736) as f64 / 32.0;
+                assert_eq!(decode(raw), Some(expected), "SPN {} raw {}", spn, raw);
+            }
+        }
     }
 
     #[test]
-    fn test_decode_unknown_pgn() {
-        // Unknown PGN should return empty
-        let can_id = 0x18FF0000; // Proprietary PGN
-        let data = [0xFF; 8];
+    fn test_decode_unaligned_multi_byte_fields() {
+        // 16-bit value starting at bit 4 of byte 1: raw 0x1234 spans
+        // byte 1 high nibble, byte 2 and byte 3 low nibble
+        let spn_def = SpnDef {
+            start_byte: 1,
+            start_bit: 4,
+            bit_length: 16,
+            ..signed_spn_def(SpnDataType::Uint16, 0, 1.0)
+        };
+        let data = [0x00, 0x4F, 0x23, 0xF1, 0, 0, 0, 0];
+        assert_eq!(spn_def.required_len(), 4);
+        assert_eq!(decode_spn(&data, &spn_def), Some(0x1234 as f64));
+        assert_eq!(decode_spn(&data[..3], &spn_def), None);
+
+        // Signed fields are sign-extended from their own width
+        let signed = SpnDef {
+            data_type: SpnDataType::Int16,
+            ..spn_def
+        };
+        let data = [0x00, 0x40, 0xED, 0x0F, 0, 0, 0, 0]; // raw 0xFED4 = -300
+        assert_eq!(decode_spn(&data, &signed), Some(-300.0));
+
+        // 12-bit field in a 16-bit type: only bit_length bits are read
+        let narrow = SpnDef {
+            start_bit: 0,
+            bit_length: 12,
+            ..spn_def
+        };
+        let data = [0x00, 0x34, 0xF2, 0, 0, 0, 0, 0];
+        assert_eq!(decode_spn(&data, &narrow), Some(0x234 as f64));
+        let narrow_signed = SpnDef {
+            data_type: SpnDataType::Int16,
+            ..narrow
+        };
+        let data = [0x00, 0xFF, 0xF8, 0, 0, 0, 0, 0]; // raw 0x8FF = -1793
+        assert_eq!(decode_spn(&data, &narrow_signed), Some(-1793.0));
+    }
 
-        let decoded = decode_frame(can_id, &data);
-        assert!(decoded.is_empty());
+    #[test]
+    fn test_decode_hex() {
+        let expected = decode_frame(
+            0x0CF00400,
+            &[0x00, 0x00, 0x00, 0x20, 0x4E, 0x00, 0x00, 0x00],
+        );
+        for (id, data) in [
+            ("0CF00400", "000000204E000000"),
+            ("0x0CF00400", "0x000000204e000000"),
+            (" 0X0cf00400 ", "00 00 00 20 4E 00 00 00"),
+        ] {
+            let decoded = decode_hex(id, data).unwrap();
+            assert_eq!(decoded.len(), expected.len(), "{:?}", (id, data));
+            assert!(decoded
+                .iter()
+                .zip(&expected)
+                .all(|(a, b)| a.approx_eq(b, 0.0)));
+        }
+        assert!(decode_hex("18FF0000", "").unwrap().is_empty());
     }
 
-    // ========================================================================
-    // NOT_AVAILABLE_THRESHOLD table tests
-    // ========================================================================
+    #[test]
+    fn test_decode_hex_errors() {
+        assert_eq!(decode_hex("", "00").unwrap_err(), ParseError::InvalidId);
+        assert_eq!(decode_hex("0x", "00").unwrap_err(), ParseError::InvalidId);
+        assert_eq!(
+            decode_hex("+CF00400", "00").unwrap_err(),
+            ParseError::InvalidId
+        );
+        assert_eq!(
+            decode_hex("CF0040G", "00").unwrap_err(),
+            ParseError::InvalidId
+        );
+        assert_eq!(
+            decode_hex("1CF004000", "00").unwrap_err(),
+            ParseError::InvalidId
+        );
+        assert_eq!(
+            decode_hex("2CF00400", "00").unwrap_err(),
+            ParseError::IdOutOfRange(0x2CF00400)
+        );
+        assert_eq!(
+            decode_hex("0CF00400", "00 2G").unwrap_err(),
+            ParseError::InvalidHexDigit('G')
+        );
+        assert_eq!(
+            decode_hex("0CF00400", "000").unwrap_err(),
+            ParseError::OddLength(3)
+        );
+        assert_eq!(
+            ParseError::IdOutOfRange(0x2CF00400).to_string(),
+            "CAN ID 0x2CF00400 exceeds 29 bits"
+        );
+    }
 
     #[test]
-    fn test_not_available_threshold_table() {
-        // Verify the precomputed table is correct
-        // J1939 reserves last two values: (2^N - 2) = error, (2^N - 1) = n/a
-        // So threshold = (2^N - 3), values > threshold are special
-        assert_eq!(NOT_AVAILABLE_THRESHOLD[0], 0);
-        assert_eq!(NOT_AVAILABLE_THRESHOLD[1], 0);
-        assert_eq!(NOT_AVAILABLE_THRESHOLD[2], 1); // 2^2 - 3 = 1
-        assert_eq!(NOT_AVAILABLE_THRESHOLD[4], 13); // 2^4 - 3 = 13
-        assert_eq!(NOT_AVAILABLE_THRESHOLD[8], 253); // 2^8 - 3 = 253
-        assert_eq!(NOT_AVAILABLE_THRESHOLD[16], 65533); // 2^16 - 3
-        assert_eq!(NOT_AVAILABLE_THRESHOLD[32], 0xFFFFFFFD); // 2^32 - 3
+    fn test_decode_eec2_packed_switches() {
+        // EEC2 byte 0 packs four 2-bit SPNs at bits 0/2/4/6, every state meaningful.
+        // 558 = 1, 559 = 2, 1437 = 3, 2970 = 1
+        let data = [0b01_11_10_01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+        let switches: Vec<(u32, f64)> = decode_frame(0x0CF00300, &data)
+            .iter()
+            .map(|s| (s.spn, s.value))
+            .collect();
+        assert_eq!(
+            switches,
+            vec![(558, 1.0), (559, 2.0), (1437, 3.0), (2970, 1.0)]
+        );
+
+        // All-zero switches are reported too
+        let zeros: Vec<(u32, f64)> = decode_frame(0x0CF00300, &[0x00, 0xFF, 0xFF, 0xFF])
+            .iter()
+            .map(|s| (s.spn, s.value))
+            .collect();
+        assert_eq!(
+            zeros,
+            vec![(558, 0.0), (559, 0.0), (1437, 0.0), (2970, 0.0)]
+        );
     }
 }
@@ -0,0 +1,358 @@
+//! Derived metrics computed from decoded SPNs.
+//!
+//! Telematics math that combines several samples of the same SPN, such as
+//! a fuel rate from two total-fuel readings, or a totalizer that survives
+//! counter rollover.
+
+use crate::database::get_spn_def;
+use crate::types::{DecodedSpn, SpnDef};
+
+/// Seconds per hour.
+const SECS_PER_HOUR: f64 = 3600.0;
+
+/// Vehicle speed (km/h) below which instantaneous fuel economy is meaningless.
+pub const MIN_FUEL_ECONOMY_SPEED_KMH: f64 = 5.0;
+
+/// Engine speed (RPM) above which the engine counts as running.
+pub const ENGINE_RUNNING_RPM: f64 = 50.0;
+
+/// Largest valid fuel economy reading (km/L), from the range of SPN 184
+/// (SPN 185 shares its encoding).
+#[inline]
+fn max_fuel_economy_km_l() -> f64 {
+    get_spn_def(184).map_or(f64::INFINITY, |spn_def| spn_def.value_range().1)
+}
+
+/// Increase of a cumulative counter between two samples of the same SPN.
+///
+/// Returns `None` if the samples are from different SPNs or the counter
+/// went backwards (ECU reset or rollover).
+#[inline]
+fn counter_delta(prev: &DecodedSpn, curr: &DecodedSpn) -> Option<f64> {
+    if prev.spn != curr.spn || curr.value < prev.value {
+        return None;
+    }
+    Some(curr.value - prev.value)
+}
+
+/// Average fuel rate in L/h from two samples of a cumulative fuel counter.
+///
+/// Works with any fuel counter in liters, e.g. total fuel used (SPN 250)
+/// or trip fuel (SPN 182). `dt_secs` is the time between the two samples.
+///
+/// Returns `None` if the samples are from different SPNs, the counter is not
+/// in liters, the counter went backwards, or `dt_secs` is not positive.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::decode_spn_full;
+/// use voltage_j1939::database::get_spn_def;
+/// use voltage_j1939::derived::fuel_rate_from_consumption;
+///
+/// let total_fuel = get_spn_def(250).unwrap();
+/// // 1000.0 L, then 1000.5 L one minute later
+/// let prev = decode_spn_full(&[0, 0, 0, 0, 0xD0, 0x07, 0, 0], total_fuel).unwrap();
+/// let curr = decode_spn_full(&[0, 0, 0, 0, 0xD1, 0x07, 0, 0], total_fuel).unwrap();
+///
+/// assert_eq!(fuel_rate_from_consumption(&prev, &curr, 60.0), Some(30.0));
+/// ```
+pub fn fuel_rate_from_consumption(
+    prev: &DecodedSpn,
+    curr: &DecodedSpn,
+    dt_secs: f64,
+) -> Option<f64> {
+    if curr.unit != "L" || dt_secs.is_nan() || dt_secs <= 0.0 {
+        return None;
+    }
+    Some(counter_delta(prev, curr)? / dt_secs * SECS_PER_HOUR)
+}
+
+/// Distance travelled between two samples of a cumulative distance counter.
+///
+/// Works with trip distance (SPN 244) or total vehicle distance (SPN 245);
+/// the result is in the counter's unit (km).
+///
+/// Returns `None` if the samples are from different SPNs or the counter
+/// went backwards.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decoder::decode_spn_full;
+/// use voltage_j1939::database::get_spn_def;
+/// use voltage_j1939::derived::distance_delta;
+///
+/// let total_distance = get_spn_def(245).unwrap();
+/// let prev = decode_spn_full(&[0, 0, 0, 0, 0x00, 0x10, 0, 0], total_distance).unwrap();
+/// let curr = decode_spn_full(&[0, 0, 0, 0, 0x10, 0x10, 0, 0], total_distance).unwrap();
+///
+/// assert_eq!(distance_delta(&prev, &curr), Some(2.0));
+/// ```
+#[inline]
+pub fn distance_delta(prev: &DecodedSpn, curr: &DecodedSpn) -> Option<f64> {
+    counter_delta(prev, curr)
+}
+
+/// Whether a fuel economy reading (SPN 184 or 185, km/L) is worth keeping.
+///
+/// At standstill ECUs report instantaneous economy as 0 or pegged at the top
+/// of the range, which turns into noise in km/L averages. A reading is
+/// plausible when the vehicle moves at [`MIN_FUEL_ECONOMY_SPEED_KMH`] or more
+/// (`vehicle_speed` from SPN 84) and the value lies strictly inside the valid
+/// range. Readings that were "not available" or "error" never decode to a
+/// value in the first place; use
+/// [`decode_spn_status`](crate::decoder::decode_spn_status) to tell them apart.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::derived::is_plausible_fuel_economy;
+///
+/// assert!(is_plausible_fuel_economy(3.2, 80.0));
+/// assert!(!is_plausible_fuel_economy(3.2, 0.0)); // Stopped
+/// assert!(!is_plausible_fuel_economy(0.0, 80.0)); // Coasting, no fuel flow reported
+/// ```
+#[inline]
+pub fn is_plausible_fuel_economy(value: f64, vehicle_speed: f64) -> bool {
+    vehicle_speed >= MIN_FUEL_ECONOMY_SPEED_KMH && value > 0.0 && value < max_fuel_economy_km_l()
+}
+
+/// Whether the engine is running, from engine speed (SPN 190).
+///
+/// True above [`ENGINE_RUNNING_RPM`], which is well under any idle speed
+/// but above cranking noise. See [`engine_running_above`] for a custom
+/// cutoff.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::derived::engine_running;
+///
+/// assert!(engine_running(650.0)); // Idle
+/// assert!(!engine_running(0.0));
+/// ```
+#[inline]
+pub fn engine_running(engine_speed_rpm: f64) -> bool {
+    engine_running_above(engine_speed_rpm, ENGINE_RUNNING_RPM)
+}
+
+/// Whether the engine is running, with a caller-chosen RPM threshold.
+///
+/// True when `engine_speed_rpm` is strictly above `threshold_rpm`; NaN is
+/// never running.
+#[inline]
+pub fn engine_running_above(engine_speed_rpm: f64, threshold_rpm: f64) -> bool {
+    engine_speed_rpm > threshold_rpm
+}
+
+/// Monotonic total from a cumulative counter SPN that wraps around.
+///
+/// Feed successive decoded values of one totalizer (distance, fuel, hours,
+/// revolutions) to [`update`](Self::update). A drop of more than half the
+/// counter's span is taken as a rollover and the full span is added to the
+/// running total. Smaller backward steps (a glitch or an ECU reset) are
+/// clamped: the total holds until the counter passes its previous value.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::database::get_spn_def;
+/// use voltage_j1939::derived::RollingTotalizer;
+///
+/// // Total vehicle distance: 32-bit counter, 0.125 km/bit
+/// let mut odometer = RollingTotalizer::for_spn(get_spn_def(245).unwrap());
+/// let span = odometer.span();
+///
+/// assert_eq!(odometer.update(span - 10.0), span - 10.0);
+/// assert_eq!(odometer.update(5.0), span + 5.0); // Wrapped
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RollingTotalizer {
+    /// Counter range in engineering units, added once per rollover
+    span: f64,
+    /// Last value seen, `None` before the first update
+    last: Option<f64>,
+    /// Number of rollovers detected
+    wraps: u64,
+}
+
+impl RollingTotalizer {
+    /// Create a totalizer for a counter that wraps after `span` units.
+    pub fn new(span: f64) -> Self {
+        Self {
+            span,
+            last: None,
+            wraps: 0,
+        }
+    }
+
+    /// Create a totalizer for an SPN, spanning its valid raw range
+    /// (`(max_valid_raw + 1) * scale`, e.g. (2^32 - 2) * 0.125 km for SPN 245):
+    /// the counter wraps to 0 after its largest valid value, never reaching
+    /// the error and "not available" codes.
+    pub fn for_spn(spn_def: &SpnDef) -> Self {
+        Self::new((spn_def.max_valid_raw() as f64 + 1.0) * spn_def.scale.abs())
+    }
+
+    /// Record the next counter value and return the accumulated total.
+    ///
+    /// The total never decreases: a backward step that is not a rollover is
+    /// ignored until the counter passes its previous value again.
+    pub fn update(&mut self, value: f64) -> f64 {
+        match self.last {
+            Some(last) if last - value > self.span / 2.0 => self.wraps += 1,
+            Some(last) if value < last => return self.total().unwrap_or(last),
+            _ => {}
+        }
+        self.last = Some(value);
+        self.total().unwrap_or(value)
+    }
+
+    /// Accumulated total, or `None` before the first update.
+    pub fn total(&self) -> Option<f64> {
+        self.last
+            .map(|last| (self.wraps as f64).mul_add(self.span, last))
+    }
+
+    /// Counter span in engineering units.
+    pub fn span(&self) -> f64 {
+        self.span
+    }
+
+    /// Number of rollovers detected so far.
+    pub fn wraps(&self) -> u64 {
+        self.wraps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::get_spn_def;
+    use crate::decoder::decode_spn_full;
+    use crate::encoder::encode_spn;
+
+    fn sample(spn: u32, value: f64) -> DecodedSpn {
+        let spn_def = get_spn_def(spn).unwrap();
+        let mut data = [0u8; 8];
+        encode_spn(&mut data, spn_def, value).unwrap();
+        decode_spn_full(&data, spn_def).unwrap()
+    }
+
+    #[test]
+    fn test_fuel_rate_from_total_fuel() {
+        // 12.5 L used over 30 minutes = 25 L/h
+        let prev = sample(250, 48_000.0);
+        let curr = sample(250, 48_012.5);
+        assert_eq!(fuel_rate_from_consumption(&prev, &curr, 1800.0), Some(25.0));
+
+        // Engine off: no consumption
+        assert_eq!(fuel_rate_from_consumption(&prev, &prev, 10.0), Some(0.0));
+    }
+
+    #[test]
+    fn test_fuel_rate_invalid_inputs() {
+        let prev = sample(250, 100.0);
+        let curr = sample(250, 101.0);
+
+        assert_eq!(fuel_rate_from_consumption(&prev, &curr, 0.0), None);
+        assert_eq!(fuel_rate_from_consumption(&prev, &curr, -1.0), None);
+        assert_eq!(fuel_rate_from_consumption(&prev, &curr, f64::NAN), None);
+        // Counter reset
+        assert_eq!(fuel_rate_from_consumption(&curr, &prev, 60.0), None);
+        // Mixed SPNs
+        let trip = sample(182, 101.0);
+        assert_eq!(fuel_rate_from_consumption(&prev, &trip, 60.0), None);
+        // Not a fuel counter
+        let distance = sample(245, 101.0);
+        assert_eq!(fuel_rate_from_consumption(&distance, &distance, 60.0), None);
+    }
+
+    #[test]
+    fn test_distance_delta() {
+        let prev = sample(245, 150_000.0);
+        let curr = sample(245, 150_012.375);
+        assert_eq!(distance_delta(&prev, &curr), Some(12.375));
+        assert_eq!(distance_delta(&curr, &prev), None);
+        assert_eq!(distance_delta(&prev, &sample(244, 150_012.375)), None);
+    }
+
+    #[test]
+    fn test_fuel_economy_plausibility() {
+        let spn_def = get_spn_def(184).unwrap();
+        let (min, max) = spn_def.value_range();
+        assert_eq!(max, max_fuel_economy_km_l());
+        // Average fuel economy (SPN 185) has the same range
+        assert_eq!(get_spn_def(185).unwrap().value_range().1, max);
+
+        assert!(is_plausible_fuel_economy(sample(184, 2.5).value, 90.0));
+        assert!(is_plausible_fuel_economy(2.5, MIN_FUEL_ECONOMY_SPEED_KMH));
+
+        // Standstill, idle creep
+        assert!(!is_plausible_fuel_economy(2.5, 0.0));
+        assert!(!is_plausible_fuel_economy(2.5, 4.9));
+        // Pegged at either end of the range
+        assert!(!is_plausible_fuel_economy(min, 90.0));
+        assert!(!is_plausible_fuel_economy(max, 90.0));
+        assert!(!is_plausible_fuel_economy(f64::NAN, 90.0));
+        assert!(!is_plausible_fuel_economy(2.5, f64::NAN));
+    }
+
+    #[test]
+    fn test_engine_running_threshold() {
+        assert!(!engine_running(ENGINE_RUNNING_RPM));
+        assert!(engine_running(ENGINE_RUNNING_RPM + 0.125)); // One SPN 190 step
+        assert!(!engine_running(f64::NAN));
+        assert!(engine_running(sample(190, 700.0).value));
+
+        assert!(engine_running_above(350.0, 300.0));
+        assert!(!engine_running_above(300.0, 300.0));
+        assert!(!engine_running_above(150.0, 300.0)); // Cranking
+    }
+
+    #[test]
+    fn test_rolling_totalizer_wraps() {
+        let mut totalizer = RollingTotalizer::new(1000.0);
+        assert_eq!(totalizer.total(), None);
+
+        assert_eq!(totalizer.update(990.0), 990.0);
+        assert_eq!(totalizer.update(995.0), 995.0);
+        assert_eq!(totalizer.update(3.0), 1003.0);
+        assert_eq!(totalizer.update(998.0), 1998.0);
+        assert_eq!(totalizer.update(1.0), 2001.0);
+        assert_eq!(totalizer.wraps(), 2);
+        assert_eq!(totalizer.total(), Some(2001.0));
+    }
+
+    #[test]
+    fn test_rolling_totalizer_small_decrease_is_clamped() {
+        let mut totalizer = RollingTotalizer::new(1000.0);
+        totalizer.update(100.0);
+        assert_eq!(totalizer.update(90.0), 100.0);
+        assert_eq!(totalizer.update(95.0), 100.0);
+        assert_eq!(totalizer.update(105.0), 105.0);
+        assert_eq!(totalizer.wraps(), 0);
+
+        // Still wraps from the held value
+        assert_eq!(totalizer.update(600.0), 600.0);
+        assert_eq!(totalizer.update(550.0), 600.0);
+        assert_eq!(totalizer.update(20.0), 1020.0);
+        assert_eq!(totalizer.wraps(), 1);
+    }
+
+    #[test]
+    fn test_rolling_totalizer_for_spn() {
+        // Total vehicle distance: raw 0..=2^32 - 3, 0.125 km/bit
+        let spn_def = get_spn_def(245).unwrap();
+        let mut odometer = RollingTotalizer::for_spn(spn_def);
+        assert_eq!(odometer.span(), 536_870_911.75);
+
+        // The largest valid value is followed by 0: one step of 0.125 km
+        let (_, max) = spn_def.value_range();
+        odometer.update(sample(245, max).value);
+        assert_eq!(odometer.update(0.0), max + 0.125);
+        let total = odometer.update(sample(245, 2.5).value);
+        assert_eq!(total, 536_870_911.75 + 2.5);
+    }
+}
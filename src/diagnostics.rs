@@ -0,0 +1,822 @@
+//! J1939-73 diagnostic messages.
+//!
+//! Builders and parsers for the diagnostic messages (DMs) used to inspect and
+//! control ECUs on the bus.
+
+use crate::frame::GLOBAL_ADDRESS;
+
+/// DM1 - Active Diagnostic Trouble Codes PGN (65226 / 0xFECA)
+pub const DM1_PGN: u32 = 0xFECA;
+
+/// DM2 - Previously Active Diagnostic Trouble Codes PGN (65227 / 0xFECB)
+pub const DM2_PGN: u32 = 0xFECB;
+
+/// DM4 - Freeze Frame Parameters PGN (65229 / 0xFECD)
+pub const DM4_PGN: u32 = 0xFECD;
+
+/// DM13 - Stop Start Broadcast PGN (57088 / 0xDF00)
+pub const DM13_PGN: u32 = 0xDF00;
+
+/// Number of 2-bit network fields in a DM13 message.
+const DM13_NETWORK_COUNT: usize = 12;
+
+/// Hold signal value meaning "all devices" (bits 8-5 of byte 4).
+const DM13_HOLD_ALL_DEVICES: u8 = 0x0;
+
+/// Value of a 4-bit DM13 field meaning "not available / no action".
+const DM13_NIBBLE_NOT_AVAILABLE: u8 = 0xF;
+
+/// Command carried by each 2-bit network field of a DM13 message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum BroadcastCommand {
+    /// Stop broadcast (00).
+    Stop = 0,
+    /// Start broadcast (01).
+    Start = 1,
+    /// Reserved (10).
+    Reserved = 2,
+    /// Don't care / take no action (11).
+    DontCare = 3,
+}
+
+impl BroadcastCommand {
+    /// Convert the low 2 bits of a value into a command.
+    #[inline]
+    pub const fn from_bits(bits: u8) -> Self {
+        match bits & 0x03 {
+            0 => Self::Stop,
+            1 => Self::Start,
+            2 => Self::Reserved,
+            _ => Self::DontCare,
+        }
+    }
+}
+
+/// Parsed DM13 (Stop Start Broadcast) message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dm13 {
+    /// Per-network commands, in J1939-73 order:
+    ///
+    /// 0. J1939 network #1 (primary vehicle network)
+    /// 1. SAE J1922
+    /// 2. SAE J1587
+    /// 3. Current data link
+    /// 4. Other manufacturer specified port
+    /// 5. SAE J1850
+    /// 6. ISO 9141
+    /// 7. J1939 network #2
+    /// 8. J1939 network #4
+    /// 9. Proprietary network #2
+    /// 10. Proprietary network #1
+    /// 11. J1939 network #3
+    pub networks: [BroadcastCommand; DM13_NETWORK_COUNT],
+    /// Hold signal (4 bits): 0 = all devices, 1 = modified devices, 0xF = not available.
+    pub hold_signal: u8,
+}
+
+impl Dm13 {
+    /// Command for the data link the message was received on.
+    #[inline]
+    pub const fn current_data_link(&self) -> BroadcastCommand {
+        self.networks[3]
+    }
+
+    /// Check if this is a hold signal keeping devices in their modified state.
+    #[inline]
+    pub const fn is_hold(&self) -> bool {
+        self.hold_signal != DM13_NIBBLE_NOT_AVAILABLE
+    }
+
+    /// Check if every network field carries `command`.
+    #[inline]
+    pub fn is_all(&self, command: BroadcastCommand) -> bool {
+        self.networks.iter().all(|&c| c == command)
+    }
+}
+
+/// Build a DM13 frame with the same command for every network.
+fn build_dm13(source_address: u8, command: BroadcastCommand, hold_signal: u8) -> (u32, [u8; 8]) {
+    // Priority 6, DP=0, PF=0xDF, PS=global, SA=source_address
+    let can_id = (6u32 << 26)
+        | ((DM13_PGN & 0xFF00) << 8)
+        | ((GLOBAL_ADDRESS as u32) << 8)
+        | (source_address as u32);
+
+    let bits = command as u8;
+    let network_byte = bits | (bits << 2) | (bits << 4) | (bits << 6);
+    let data = [
+        network_byte,
+        network_byte,
+        network_byte,
+        // Hold signal in bits 8-5, suspend signal (not used) in bits 4-1
+        (hold_signal << 4) | DM13_NIBBLE_NOT_AVAILABLE,
+        0xFF,
+        0xFF,
+        0xFF,
+        0xFF,
+    ];
+
+    (can_id, data)
+}
+
+/// Build a DM13 "stop broadcast" command for all networks, sent to the global address.
+///
+/// Devices resume broadcasting after about 6 seconds unless they keep
+/// receiving [`build_dm13_hold_signal`].
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::diagnostics::build_dm13_stop_broadcast;
+///
+/// let (can_id, data) = build_dm13_stop_broadcast(0xF9);
+/// assert_eq!(can_id, 0x18DFFFF9);
+/// assert_eq!(data, [0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+/// ```
+#[inline]
+pub fn build_dm13_stop_broadcast(source_address: u8) -> (u32, [u8; 8]) {
+    build_dm13(
+        source_address,
+        BroadcastCommand::Stop,
+        DM13_NIBBLE_NOT_AVAILABLE,
+    )
+}
+
+/// Build a DM13 "start broadcast" command for all networks, sent to the global address.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::diagnostics::build_dm13_start_broadcast;
+///
+/// let (_, data) = build_dm13_start_broadcast(0xF9);
+/// assert_eq!(data, [0x55, 0x55, 0x55, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+/// ```
+#[inline]
+pub fn build_dm13_start_broadcast(source_address: u8) -> (u32, [u8; 8]) {
+    build_dm13(
+        source_address,
+        BroadcastCommand::Start,
+        DM13_NIBBLE_NOT_AVAILABLE,
+    )
+}
+
+/// Build a DM13 hold signal for all devices.
+///
+/// Must be repeated (typically every 5 seconds) to keep devices in the
+/// state set by a previous stop command.
+#[inline]
+pub fn build_dm13_hold_signal(source_address: u8) -> (u32, [u8; 8]) {
+    build_dm13(
+        source_address,
+        BroadcastCommand::DontCare,
+        DM13_HOLD_ALL_DEVICES,
+    )
+}
+
+/// Parse a DM13 (Stop Start Broadcast) message.
+///
+/// Returns `None` if the payload is shorter than the 4 bytes carrying the
+/// network fields and hold signal.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::diagnostics::{build_dm13_stop_broadcast, parse_dm13, BroadcastCommand};
+///
+/// let (_, data) = build_dm13_stop_broadcast(0xF9);
+/// let dm13 = parse_dm13(&data).unwrap();
+/// assert!(dm13.is_all(BroadcastCommand::Stop));
+/// ```
+pub fn parse_dm13(data: &[u8]) -> Option<Dm13> {
+    let bytes = data.get(..4)?;
+
+    let mut networks = [BroadcastCommand::DontCare; DM13_NETWORK_COUNT];
+    for (i, network) in networks.iter_mut().enumerate() {
+        *network = BroadcastCommand::from_bits(bytes[i / 4] >> ((i % 4) * 2));
+    }
+
+    Some(Dm13 {
+        networks,
+        hold_signal: bytes[3] >> 4,
+    })
+}
+
+/// Size of the lamp status header shared by DM1, DM2, DM4, ...
+const LAMP_STATUS_LEN: usize = 2;
+
+/// Size of one DTC in a diagnostic message.
+const DTC_LEN: usize = 4;
+
+/// On/off state of a diagnostic lamp (2-bit field).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum LampState {
+    /// Lamp off (00).
+    Off = 0,
+    /// Lamp on (01).
+    On = 1,
+    /// Reserved (10).
+    Reserved = 2,
+    /// Not available (11).
+    NotAvailable = 3,
+}
+
+impl LampState {
+    /// Convert the low 2 bits of a value into a lamp state.
+    #[inline]
+    pub const fn from_bits(bits: u8) -> Self {
+        match bits & 0x03 {
+            0 => Self::Off,
+            1 => Self::On,
+            2 => Self::Reserved,
+            _ => Self::NotAvailable,
+        }
+    }
+}
+
+/// Flash state of a diagnostic lamp (2-bit field).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum FlashState {
+    /// Slow flash, 1 Hz (00).
+    Slow = 0,
+    /// Fast flash, 2 Hz (01).
+    Fast = 1,
+    /// Reserved (10).
+    Reserved = 2,
+    /// Not flashing / unavailable (11).
+    Off = 3,
+}
+
+impl FlashState {
+    /// Convert the low 2 bits of a value into a flash state.
+    #[inline]
+    pub const fn from_bits(bits: u8) -> Self {
+        match bits & 0x03 {
+            0 => Self::Slow,
+            1 => Self::Fast,
+            2 => Self::Reserved,
+            _ => Self::Off,
+        }
+    }
+}
+
+/// State and flash state of a single diagnostic lamp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Lamp {
+    /// On/off state.
+    pub state: LampState,
+    /// Flash state.
+    pub flash: FlashState,
+}
+
+impl Lamp {
+    /// Check if the lamp is on.
+    #[inline]
+    pub const fn is_on(&self) -> bool {
+        matches!(self.state, LampState::On)
+    }
+}
+
+/// Lamp status header (first two bytes) of DM1, DM2, DM4 and other diagnostic messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LampStatus {
+    /// Malfunction Indicator Lamp.
+    pub mil: Lamp,
+    /// Red Stop Lamp.
+    pub red_stop: Lamp,
+    /// Amber Warning Lamp.
+    pub amber_warning: Lamp,
+    /// Protect Lamp.
+    pub protect: Lamp,
+}
+
+impl LampStatus {
+    /// Decode the lamp status from the first two bytes of a diagnostic message.
+    ///
+    /// `b0` holds the lamp states and `b1` the flash states, each as four
+    /// 2-bit fields: MIL (bits 8-7), red stop (6-5), amber warning (4-3)
+    /// and protect (2-1).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use voltage_j1939::diagnostics::{FlashState, LampState, LampStatus};
+    ///
+    /// // Amber warning lamp on and flashing fast, others off
+    /// let lamps = LampStatus::from_bytes(0x04, 0xF7);
+    /// assert_eq!(lamps.amber_warning.state, LampState::On);
+    /// assert_eq!(lamps.amber_warning.flash, FlashState::Fast);
+    /// assert!(!lamps.mil.is_on());
+    /// ```
+    #[inline]
+    pub const fn from_bytes(b0: u8, b1: u8) -> Self {
+        const fn lamp(b0: u8, b1: u8, shift: u8) -> Lamp {
+            Lamp {
+                state: LampState::from_bits(b0 >> shift),
+                flash: FlashState::from_bits(b1 >> shift),
+            }
+        }
+
+        Self {
+            mil: lamp(b0, b1, 6),
+            red_stop: lamp(b0, b1, 4),
+            amber_warning: lamp(b0, b1, 2),
+            protect: lamp(b0, b1, 0),
+        }
+    }
+
+    /// Check if any lamp is on.
+    #[inline]
+    pub const fn any_on(&self) -> bool {
+        self.mil.is_on()
+            || self.red_stop.is_on()
+            || self.amber_warning.is_on()
+            || self.protect.is_on()
+    }
+}
+
+/// Diagnostic Trouble Code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Dtc {
+    /// Suspect Parameter Number (19 bits).
+    pub spn: u32,
+    /// Failure Mode Identifier (5 bits).
+    pub fmi: u8,
+    /// Occurrence count (7 bits, 127 = not available).
+    pub occurrence_count: u8,
+    /// SPN conversion method bit (byte 4 bit 8).
+    ///
+    /// `false` for the version 4 layout used by current ECUs. `true` means
+    /// the sender uses one of the older version 1-3 layouts, where `spn` as
+    /// decoded here is not the real SPN.
+    pub conversion_method: bool,
+}
+
+impl Dtc {
+    /// Decode a 4-byte DTC (SPN conversion method version 4 layout).
+    ///
+    /// Byte 1 holds SPN bits 0-7, byte 2 SPN bits 8-15, byte 3 bits 8-6
+    /// SPN bits 16-18 and bits 5-1 the FMI, byte 4 bit 8 the conversion
+    /// method and bits 7-1 the occurrence count.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use voltage_j1939::diagnostics::Dtc;
+    ///
+    /// // SPN 3226 (NOx sensor) FMI 2, occurred 5 times
+    /// let dtc = Dtc::from_bytes([0x9A, 0x0C, 0x02, 0x05]);
+    /// assert_eq!((dtc.spn, dtc.fmi, dtc.occurrence_count), (3226, 2, 5));
+    /// assert!(!dtc.conversion_method);
+    /// ```
+    #[inline]
+    pub const fn from_bytes(bytes: [u8; 4]) -> Self {
+        let spn = bytes[0] as u32 | (bytes[1] as u32) << 8 | ((bytes[2] >> 5) as u32) << 16;
+        Self {
+            spn,
+            fmi: bytes[2] & 0x1F,
+            occurrence_count: bytes[3] & 0x7F,
+            conversion_method: bytes[3] & 0x80 != 0,
+        }
+    }
+
+    /// Standard description of this DTC's FMI.
+    #[inline]
+    pub fn fmi_description(&self) -> &'static str {
+        fmi_description(self.fmi)
+    }
+}
+
+/// Contents of a DM1 / DM2 message: lamp status and trouble codes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiagnosticMessage {
+    /// Lamp status.
+    pub lamps: LampStatus,
+    /// Trouble codes (empty when no DTC is active).
+    pub dtcs: Vec<Dtc>,
+}
+
+/// Read just the lamp status of a diagnostic message (DM1, DM2, DM6, ...).
+///
+/// Returns `None` if the payload is shorter than 2 bytes.
+#[inline]
+pub fn parse_lamp_status(data: &[u8]) -> Option<LampStatus> {
+    match data {
+        [b0, b1, ..] => Some(LampStatus::from_bytes(*b0, *b1)),
+        _ => None,
+    }
+}
+
+/// Parse a lamp status header followed by 4-byte DTCs.
+fn parse_dtc_message(data: &[u8]) -> Option<DiagnosticMessage> {
+    let lamps = parse_lamp_status(data)?;
+    let dtcs = data[LAMP_STATUS_LEN..]
+        .chunks_exact(DTC_LEN)
+        .map(|chunk| Dtc::from_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        // SPN 0 means "no DTC" (single-frame message with nothing active),
+        // SPN 0x7FFFF is padding
+        .filter(|dtc| dtc.spn != 0 && dtc.spn != 0x7FFFF)
+        .collect();
+
+    Some(DiagnosticMessage { lamps, dtcs })
+}
+
+/// Parse a DM1 (Active Diagnostic Trouble Codes) message.
+///
+/// Accepts a single 8-byte frame or a payload reassembled from the transport
+/// protocol when more than one DTC is active. Returns `None` if the payload
+/// is shorter than the 2-byte lamp status.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::diagnostics::parse_dm1;
+///
+/// // Amber lamp on, SPN 100 (oil pressure) FMI 1, occurred 3 times
+/// let dm1 = parse_dm1(&[0x04, 0xFF, 0x64, 0x00, 0x01, 0x03, 0xFF, 0xFF]).unwrap();
+/// assert!(dm1.lamps.amber_warning.is_on());
+/// assert_eq!(dm1.dtcs[0].spn, 100);
+/// assert_eq!(dm1.dtcs[0].fmi, 1);
+/// assert_eq!(dm1.dtcs[0].occurrence_count, 3);
+/// ```
+#[inline]
+pub fn parse_dm1(data: &[u8]) -> Option<DiagnosticMessage> {
+    parse_dtc_message(data)
+}
+
+/// Parse a DM2 (Previously Active Diagnostic Trouble Codes) message.
+///
+/// Same layout as DM1; see [`parse_dm1`].
+#[inline]
+pub fn parse_dm2(data: &[u8]) -> Option<DiagnosticMessage> {
+    parse_dtc_message(data)
+}
+
+/// One DM4 freeze frame: the DTC and the parameters captured with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FreezeFrame<'a> {
+    /// Trouble code the freeze frame was captured for.
+    pub dtc: Dtc,
+    /// Freeze frame parameters following the DTC, uninterpreted.
+    ///
+    /// Starts with the standard parameters (engine torque mode, boost,
+    /// engine speed, load, coolant temperature, vehicle speed), followed by
+    /// manufacturer-specific data.
+    pub data: &'a [u8],
+}
+
+/// Contents of a DM4 message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dm4Message<'a> {
+    /// Freeze frames in message order (empty if none are stored).
+    pub freeze_frames: Vec<FreezeFrame<'a>>,
+}
+
+/// Parse a DM4 (Freeze Frame Parameters) message.
+///
+/// The payload is a sequence of freeze frames, each a length byte (counting
+/// the bytes after it) followed by a 4-byte DTC and the captured parameters.
+/// Multiple freeze frames arrive via the transport protocol, like DM1. A
+/// length of 0 or 0xFF ends the list. Unlike DM1, DM4 has no lamp status.
+///
+/// Returns `None` if a freeze frame is shorter than its DTC or runs past the
+/// end of the payload.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::diagnostics::parse_dm4;
+///
+/// let data = [
+///     0x0D, // 13 bytes follow
+///     0x6E, 0x00, 0x00, 0x02, // SPN 110 FMI 0, occurred twice
+///     0xFF, 0x20, 0x00, 0x1C, 0x50, 0xC8, 0x00, 0x4B, 0xFF,
+/// ];
+/// let dm4 = parse_dm4(&data).unwrap();
+/// assert_eq!(dm4.freeze_frames[0].dtc.spn, 110);
+/// assert_eq!(dm4.freeze_frames[0].data.len(), 9);
+/// ```
+pub fn parse_dm4(data: &[u8]) -> Option<Dm4Message<'_>> {
+    let mut freeze_frames = Vec::new();
+    let mut rest = data;
+
+    while let Some((&len, tail)) = rest.split_first() {
+        if len == 0 || len == 0xFF {
+            break;
+        }
+        let len = len as usize;
+        if len < DTC_LEN || tail.len() < len {
+            return None;
+        }
+        let (frame, tail) = tail.split_at(len);
+        let dtc = Dtc::from_bytes([frame[0], frame[1], frame[2], frame[3]]);
+        // SPN 0 means "no DTC", as in DM1
+        if dtc.spn != 0 {
+            freeze_frames.push(FreezeFrame {
+                dtc,
+                data: &frame[DTC_LEN..],
+            });
+        }
+        rest = tail;
+    }
+
+    Some(Dm4Message { freeze_frames })
+}
+
+/// Failure Mode Identifier descriptions (J1939-73), indexed by FMI.
+const FMI_DESCRIPTIONS: [&str; 32] = [
+    "Data valid but above normal operational range - most severe level",
+    "Data valid but below normal operational range - most severe level",
+    "Data erratic, intermittent or incorrect",
+    "Voltage above normal, or shorted to high source",
+    "Voltage below normal, or shorted to low source",
+    "Current below normal or open circuit",
+    "Current above normal or grounded circuit",
+    "Mechanical system not responding or out of adjustment",
+    "Abnormal frequency or pulse width or period",
+    "Abnormal update rate",
+    "Abnormal rate of change",
+    "Root cause not known",
+    "Bad intelligent device or component",
+    "Out of calibration",
+    "Special instructions",
+    "Data valid but above normal operating range - least severe level",
+    "Data valid but above normal operating range - moderately severe level",
+    "Data valid but below normal operating range - least severe level",
+    "Data valid but below normal operating range - moderately severe level",
+    "Received network data in error",
+    "Data drifted high",
+    "Data drifted low",
+    "Reserved for SAE assignment",
+    "Reserved for SAE assignment",
+    "Reserved for SAE assignment",
+    "Reserved for SAE assignment",
+    "Reserved for SAE assignment",
+    "Reserved for SAE assignment",
+    "Reserved for SAE assignment",
+    "Reserved for SAE assignment",
+    "Reserved for SAE assignment",
+    "Condition exists",
+];
+
+/// Get the standard description of a Failure Mode Identifier (FMI).
+///
+/// FMIs are 5-bit values (0-31); larger values return `"Invalid FMI"`.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::diagnostics::fmi_description;
+///
+/// assert_eq!(fmi_description(4), "Voltage below normal, or shorted to low source");
+/// assert_eq!(fmi_description(9), "Abnormal update rate");
+/// ```
+#[inline]
+pub fn fmi_description(fmi: u8) -> &'static str {
+    FMI_DESCRIPTIONS
+        .get(fmi as usize)
+        .copied()
+        .unwrap_or("Invalid FMI")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::parse_can_id;
+
+    #[test]
+    fn test_dm13_can_id() {
+        let (can_id, _) = build_dm13_stop_broadcast(0xF9);
+        let id = parse_can_id(can_id);
+        assert_eq!(id.pgn, DM13_PGN);
+        assert_eq!(id.priority, 6);
+        assert_eq!(id.destination_address, 0xFF);
+        assert_eq!(id.source_address, 0xF9);
+    }
+
+    #[test]
+    fn test_dm13_stop_start_roundtrip() {
+        let (_, data) = build_dm13_stop_broadcast(0xF9);
+        let dm13 = parse_dm13(&data).unwrap();
+        assert!(dm13.is_all(BroadcastCommand::Stop));
+        assert_eq!(dm13.current_data_link(), BroadcastCommand::Stop);
+        assert!(!dm13.is_hold());
+
+        let (_, data) = build_dm13_start_broadcast(0xF9);
+        let dm13 = parse_dm13(&data).unwrap();
+        assert!(dm13.is_all(BroadcastCommand::Start));
+        assert!(!dm13.is_all(BroadcastCommand::Stop));
+    }
+
+    #[test]
+    fn test_dm13_hold_signal() {
+        let (_, data) = build_dm13_hold_signal(0xF9);
+        assert_eq!(data[..4], [0xFF, 0xFF, 0xFF, 0x0F]);
+
+        let dm13 = parse_dm13(&data).unwrap();
+        assert!(dm13.is_hold());
+        assert_eq!(dm13.hold_signal, 0);
+        assert!(dm13.is_all(BroadcastCommand::DontCare));
+    }
+
+    #[test]
+    fn test_parse_dm13_per_network() {
+        // Stop only the current data link (byte 1, bits 8-7), others don't care
+        let data = [0x3F, 0xFF, 0xFF, 0xFF];
+        let dm13 = parse_dm13(&data).unwrap();
+        assert_eq!(dm13.current_data_link(), BroadcastCommand::Stop);
+        assert_eq!(dm13.networks[0], BroadcastCommand::DontCare);
+        assert!(dm13.networks[4..]
+            .iter()
+            .all(|&c| c == BroadcastCommand::DontCare));
+    }
+
+    #[test]
+    fn test_parse_dm13_too_short() {
+        assert!(parse_dm13(&[]).is_none());
+        assert!(parse_dm13(&[0x00, 0x00, 0x00]).is_none());
+    }
+
+    #[test]
+    fn test_fmi_description() {
+        assert_eq!(
+            fmi_description(0),
+            "Data valid but above normal operational range - most severe level"
+        );
+        assert_eq!(fmi_description(12), "Bad intelligent device or component");
+        assert_eq!(fmi_description(21), "Data drifted low");
+        for fmi in 22..=30 {
+            assert_eq!(fmi_description(fmi), "Reserved for SAE assignment");
+        }
+        assert_eq!(fmi_description(31), "Condition exists");
+        assert_eq!(fmi_description(32), "Invalid FMI");
+        assert_eq!(fmi_description(u8::MAX), "Invalid FMI");
+    }
+
+    // ========================================================================
+    // Lamp status / DM1 / DM2
+    // ========================================================================
+
+    #[test]
+    fn test_lamp_status_from_bytes() {
+        // MIL on, red stop off, amber not available, protect on
+        // Flash: MIL fast, others not flashing
+        let lamps = LampStatus::from_bytes(0b01_00_11_01, 0b01_11_11_11);
+        assert_eq!(lamps.mil.state, LampState::On);
+        assert_eq!(lamps.mil.flash, FlashState::Fast);
+        assert_eq!(lamps.red_stop.state, LampState::Off);
+        assert_eq!(lamps.red_stop.flash, FlashState::Off);
+        assert_eq!(lamps.amber_warning.state, LampState::NotAvailable);
+        assert_eq!(lamps.protect.state, LampState::On);
+        assert!(lamps.any_on());
+
+        let lamps = LampStatus::from_bytes(0x00, 0xFF);
+        assert!(!lamps.any_on());
+        assert_eq!(lamps.protect.flash, FlashState::Off);
+
+        let lamps = LampStatus::from_bytes(0x00, 0b00_10_00_00);
+        assert_eq!(lamps.mil.flash, FlashState::Slow);
+        assert_eq!(lamps.red_stop.flash, FlashState::Reserved);
+    }
+
+    #[test]
+    fn test_parse_lamp_status() {
+        assert_eq!(
+            parse_lamp_status(&[0x10, 0xFF, 0x00])
+                .unwrap()
+                .red_stop
+                .state,
+            LampState::On
+        );
+        assert!(parse_lamp_status(&[0x10]).is_none());
+    }
+
+    #[test]
+    fn test_dtc_from_bytes() {
+        // SPN 520192 (0x7F000), FMI 31, OC 126
+        let dtc = Dtc::from_bytes([0x00, 0xF0, 0xFF, 0x7E]);
+        assert_eq!(dtc.spn, 0x7F000);
+        assert_eq!(dtc.fmi, 31);
+        assert_eq!(dtc.occurrence_count, 126);
+        assert!(!dtc.conversion_method);
+
+        // SPN 110 (coolant temperature) FMI 0
+        let dtc = Dtc::from_bytes([0x6E, 0x00, 0x00, 0x01]);
+        assert_eq!((dtc.spn, dtc.fmi), (110, 0));
+        assert_eq!(
+            dtc.fmi_description(),
+            "Data valid but above normal operational range - most severe level"
+        );
+    }
+
+    #[test]
+    fn test_dtc_version_4_layout() {
+        // SPN 521 (brake pedal position, 0x209) FMI 7: SPN bits 16-18 are
+        // zero, so bits 8-15 must come from byte 2 and not be shifted
+        let dtc = Dtc::from_bytes([0x09, 0x02, 0x07, 0x01]);
+        assert_eq!((dtc.spn, dtc.fmi), (521, 7));
+
+        // SPN 524287 (0x7FFFF): top 3 bits live in byte 3 bits 8-6
+        let dtc = Dtc::from_bytes([0xFF, 0xFF, 0xE4, 0x01]);
+        assert_eq!((dtc.spn, dtc.fmi), (0x7FFFF, 4));
+        let dtc = Dtc::from_bytes([0x00, 0x00, 0xA0, 0x01]);
+        assert_eq!(dtc.spn, 0x50000);
+    }
+
+    #[test]
+    fn test_dtc_conversion_method_bit() {
+        // Same DTC from an older ECU: CM bit set, occurrence count unaffected
+        let dtc = Dtc::from_bytes([0x6E, 0x00, 0x03, 0x82]);
+        assert!(dtc.conversion_method);
+        assert_eq!(dtc.occurrence_count, 2);
+
+        let dm1 = parse_dm1(&[0x04, 0xFF, 0x6E, 0x00, 0x03, 0x82, 0xFF, 0xFF]).unwrap();
+        assert!(dm1.dtcs[0].conversion_method);
+        assert_eq!((dm1.dtcs[0].spn, dm1.dtcs[0].fmi), (110, 3));
+    }
+
+    #[test]
+    fn test_parse_dm1_no_active_dtcs() {
+        let dm1 = parse_dm1(&[0x00, 0xFF, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF]).unwrap();
+        assert!(!dm1.lamps.any_on());
+        assert!(dm1.dtcs.is_empty());
+    }
+
+    #[test]
+    fn test_parse_dm1_multi_packet() {
+        let data = [
+            0x14, 0xFF, // MIL off, red stop on, amber on
+            0x64, 0x00, 0x01, 0x03, // SPN 100 FMI 1 OC 3
+            0xBE, 0x00, 0x00, 0x01, // SPN 190 FMI 0 OC 1
+            0xFF, 0xFF, 0xFF, 0xFF, // Padding
+        ];
+        let dm1 = parse_dm1(&data).unwrap();
+        assert!(dm1.lamps.red_stop.is_on());
+        assert_eq!(dm1.dtcs.len(), 2);
+        assert_eq!((dm1.dtcs[0].spn, dm1.dtcs[0].fmi), (100, 1));
+        assert_eq!((dm1.dtcs[1].spn, dm1.dtcs[1].fmi), (190, 0));
+        assert_eq!(parse_dm2(&data), Some(dm1));
+    }
+
+    #[test]
+    fn test_parse_dm1_truncated() {
+        assert!(parse_dm1(&[]).is_none());
+        assert!(parse_dm1(&[0x00]).is_none());
+        // Trailing partial DTC is ignored
+        assert!(parse_dm1(&[0x00, 0xFF, 0x64, 0x00])
+            .unwrap()
+            .dtcs
+            .is_empty());
+    }
+
+    // ========================================================================
+    // DM4 tests
+    // ========================================================================
+
+    #[test]
+    fn test_parse_dm4_multiple_freeze_frames() {
+        let data = [
+            0x0D, // Freeze frame 1: 13 bytes
+            0x64, 0x00, 0x01, 0x03, // SPN 100 FMI 1 OC 3
+            0x00, 0x20, 0x80, 0x3E, 0x32, 0x7D, 0x00, 0x28, 0xAA,
+            0x06, // Freeze frame 2: 6 bytes
+            0xBE, 0x00, 0x00, 0x01, // SPN 190 FMI 0 OC 1
+            0x01, 0x02, 0xFF, 0xFF, 0xFF, // Padding
+        ];
+        let dm4 = parse_dm4(&data).unwrap();
+        assert_eq!(dm4.freeze_frames.len(), 2);
+
+        let first = &dm4.freeze_frames[0];
+        assert_eq!(
+            (first.dtc.spn, first.dtc.fmi, first.dtc.occurrence_count),
+            (100, 1, 3)
+        );
+        assert_eq!(first.data, &data[5..14]);
+
+        let second = &dm4.freeze_frames[1];
+        assert_eq!((second.dtc.spn, second.dtc.fmi), (190, 0));
+        assert_eq!(second.data, &[0x01, 0x02]);
+    }
+
+    #[test]
+    fn test_parse_dm4_empty() {
+        assert!(parse_dm4(&[]).unwrap().freeze_frames.is_empty());
+        // No stored freeze frames: zero length, rest padding
+        let dm4 = parse_dm4(&[0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]).unwrap();
+        assert!(dm4.freeze_frames.is_empty());
+        // Length covering a DTC of SPN 0 only
+        let dm4 = parse_dm4(&[0x04, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF]).unwrap();
+        assert!(dm4.freeze_frames.is_empty());
+    }
+
+    #[test]
+    fn test_parse_dm4_malformed() {
+        // Length runs past the end of the payload
+        assert!(parse_dm4(&[0x0D, 0x64, 0x00, 0x01, 0x03, 0x00]).is_none());
+        // Length too short to hold a DTC
+        assert!(parse_dm4(&[0x02, 0x64, 0x00]).is_none());
+    }
+}
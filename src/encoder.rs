@@ -0,0 +1,408 @@
+//! J1939 SPN encoder.
+//!
+//! Inverse of the decoder: converts engineering values back into raw values
+//! and writes them into CAN frame data.
+
+use crate::database::get_spn_def_in_pgn;
+use crate::decoder::bit_mask;
+use crate::frame::{DEFAULT_PRIORITY, GLOBAL_ADDRESS};
+use crate::types::{J1939Id, SpnDef};
+
+/// Convert an engineering value into the raw value for an SPN.
+///
+/// Computes `raw = (value - offset) / scale`, rounds to the nearest integer and
+/// clamps into [`SpnDef::raw_range`], so out-of-range values saturate instead of
+/// wrapping into the reserved error / "not available" codes. Works for negative
+/// `scale` as well, since clamping happens in the raw domain.
+///
+/// Returns `None` for non-finite values or a zero scale.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::database::get_spn_def;
+/// use voltage_j1939::encoder::value_to_raw;
+///
+/// let spn_def = get_spn_def(110).unwrap(); // Coolant temp, offset -40
+/// assert_eq!(value_to_raw(spn_def, 90.0), Some(130));
+/// assert_eq!(value_to_raw(spn_def, 1000.0), Some(253)); // Clamped to max valid
+/// ```
+#[inline]
+pub fn value_to_raw(spn_def: &SpnDef, value: f64) -> Option<i64> {
+    let raw = ((value - spn_def.offset) / spn_def.scale).round();
+    if !raw.is_finite() {
+        return None;
+    }
+
+    let (min, max) = spn_def.raw_range();
+    let raw = (raw as i64).clamp(min, max);
+
+    // Step off the signed special codes to the nearest valid value
+    Some(match raw {
+        -1 if spn_def.is_reserved_raw(raw) => 0,
+        -2 if spn_def.is_reserved_raw(raw) => -3,
+        _ => raw,
+    })
+}
+
+/// Encode an engineering value into CAN frame data.
+///
+/// Bytes not covered by the SPN are left untouched, so several SPNs can be
+/// encoded into the same buffer. Returns the raw value written (as its
+/// unsigned bit pattern), or `None` if `data` is too short or the value
+/// can't be converted (see [`value_to_raw`]).
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::database::get_spn_def;
+/// use voltage_j1939::decoder::decode_spn;
+/// use voltage_j1939::encoder::encode_spn;
+///
+/// let spn_def = get_spn_def(190).unwrap(); // Engine speed
+/// let mut data = [0xFF; 8];
+///
+/// encode_spn(&mut data, spn_def, 2500.0).unwrap();
+/// assert_eq!(decode_spn(&data, spn_def), Some(2500.0));
+/// ```
+#[inline]
+pub fn encode_spn(data: &mut [u8], spn_def: &SpnDef, value: f64) -> Option<u64> {
+    let raw = value_to_raw(spn_def, value)? as u64;
+    write_raw_value(data, spn_def, raw)?;
+    Some(raw & bit_mask(spn_def.effective_bits()))
+}
+
+/// Builds a complete J1939 data frame from engineering values.
+///
+/// Transmit-side counterpart of [`decode_frame`](crate::decoder::decode_frame).
+/// Bytes not covered by any set SPN are left at 0xFF ("not available").
+/// Priority defaults to 6 and the destination to the global address; the
+/// destination only appears in the CAN ID of PDU1 (peer-to-peer) PGNs.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::decode_frame;
+/// use voltage_j1939::encoder::FrameBuilder;
+///
+/// let (can_id, data) = FrameBuilder::new(61444, 0x00)
+///     .priority(3)
+///     .set_spn(190, 2500.0) // Engine speed
+///     .unwrap()
+///     .build();
+///
+/// assert_eq!(can_id, 0x0CF00400);
+/// assert_eq!(data, [0xFF, 0xFF, 0xFF, 0x20, 0x4E, 0xFF, 0xFF, 0xFF]);
+/// assert_eq!(decode_frame(can_id, &data)[0].value, 2500.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameBuilder {
+    id: J1939Id,
+    data: [u8; 8],
+}
+
+impl FrameBuilder {
+    /// Start a frame for `pgn` sent from `source_address`.
+    pub fn new(pgn: u32, source_address: u8) -> Self {
+        Self {
+            id: J1939Id {
+                pgn,
+                priority: DEFAULT_PRIORITY,
+                source_address,
+                destination_address: GLOBAL_ADDRESS,
+            },
+            data: [0xFF; 8],
+        }
+    }
+
+    /// Set the priority (clamped to 0-7).
+    pub fn priority(mut self, priority: u8) -> Self {
+        self.id = self.id.with_priority(priority);
+        self
+    }
+
+    /// Set the destination address (PDU1 PGNs only).
+    pub fn destination_address(mut self, destination_address: u8) -> Self {
+        self.id.destination_address = destination_address;
+        self
+    }
+
+    /// Encode `value` into the frame for an SPN of this builder's PGN.
+    ///
+    /// For multiplexed SPNs the selector byte is set as well. Returns `None`
+    /// if the SPN is not defined in this PGN or the value can't be encoded
+    /// (see [`encode_spn`]).
+    pub fn set_spn(mut self, spn: u32, value: f64) -> Option<Self> {
+        let spn_def = get_spn_def_in_pgn(spn, self.id.pgn)?;
+        encode_spn(&mut self.data, spn_def, value)?;
+        if let (Some(byte), Some(selector)) = (spn_def.mux_byte, spn_def.mux_value) {
+            *self.data.get_mut(byte as usize)? = selector;
+        }
+        Some(self)
+    }
+
+    /// Finish the frame as `(can_id, data)`.
+    pub fn build(self) -> (u32, [u8; 8]) {
+        (self.id.to_can_id(), self.data)
+    }
+}
+
+// ============================================================================
+// Internal helpers
+// ============================================================================
+
+/// Write a raw value into data bytes based on SPN definition.
+/// Mirrors `read_raw_value` in the decoder.
+#[inline]
+fn write_raw_value(data: &mut [u8], spn_def: &SpnDef, raw: u64) -> Option<()> {
+    if data.len() < spn_def.required_len() {
+        return None;
+    }
+    let start = spn_def.start_byte as usize;
+
+    if spn_def.start_bit != 0 || spn_def.bit_length < spn_def.data_type.bit_size() {
+        // Bit field: merge into the existing bytes
+        let start_bit = start * 8 + spn_def.start_bit as usize;
+        return write_bits(data, start_bit, spn_def.effective_bits(), raw);
+    }
+    let size = spn_def.data_type.byte_size();
+    data.get_mut(start..start + size)?
+        .copy_from_slice(&raw.to_le_bytes()[..size]);
+    Some(())
+}
+
+/// Write the low `len` bits of `value` at absolute bit `start_bit`,
+/// leaving all other bits untouched. Inverse of `extract_bits`.
+fn write_bits(data: &mut [u8], start_bit: usize, len: u8, value: u64) -> Option<()> {
+    let shift = start_bit % 8;
+    let bytes = data.get_mut(start_bit / 8..(start_bit + len as usize).div_ceil(8))?;
+
+    // Up to 9 bytes for an unaligned 64-bit field
+    let mask = (bit_mask(len) as u128) << shift;
+    let word = bytes
+        .iter()
+        .rev()
+        .fold(0u128, |acc, &byte| (acc << 8) | byte as u128);
+    let word = (word & !mask) | (((value as u128) << shift) & mask);
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = (word >> (8 * i)) as u8;
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::get_spn_def;
+    use crate::decoder::decode_spn;
+    use crate::types::SpnDataType;
+
+    fn test_spn_def(scale: f64, offset: f64) -> SpnDef {
+        SpnDef {
+            scale,
+            offset,
+            spn: 9000,
+            pgn: 0xFF00,
+            name: "reversed_sensor",
+            unit: "%",
+            start_byte: 2,
+            start_bit: 0,
+            bit_length: 8,
+            data_type: SpnDataType::Uint8,
+            ..SpnDef::DEFAULT
+        }
+    }
+
+    #[test]
+    fn test_encode_decode_roundtrip() {
+        let spn_def = get_spn_def(110).unwrap();
+        let mut data = [0xFF; 8];
+        assert_eq!(encode_spn(&mut data, spn_def, 90.0), Some(130));
+        assert_eq!(data, [130, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+        assert_eq!(decode_spn(&data, spn_def), Some(90.0));
+    }
+
+    #[test]
+    fn test_roundtrip_every_database_spn() {
+        use crate::database::SPN_DEFINITIONS;
+        use crate::decoder::decode_spn_full;
+
+        for spn_def in SPN_DEFINITIONS {
+            let (raw_min, raw_max) = spn_def.raw_range();
+            let mask = bit_mask(spn_def.effective_bits());
+            for raw in [raw_min, raw_min / 2 + raw_max / 2, raw_max] {
+                let value = (raw as f64).mul_add(spn_def.scale, spn_def.offset);
+                // Both fill patterns, so stray bits on either side show up
+                for fill in [0x00, 0xFF] {
+                    let mut data = [fill; 8];
+                    let written = encode_spn(&mut data, spn_def, value);
+                    assert_eq!(written, Some(raw as u64 & mask), "SPN {}", spn_def.spn);
+
+                    let decoded = decode_spn_full(&data, spn_def)
+                        .unwrap_or_else(|| panic!("SPN {} raw {} not decoded", spn_def.spn, raw));
+                    assert_eq!(decoded.raw_value, raw as u64 & mask, "SPN {}", spn_def.spn);
+                    assert!(
+                        (decoded.value - value).abs() <= spn_def.scale.abs() / 2.0,
+                        "SPN {} raw {}: {} != {}",
+                        spn_def.spn,
+                        raw,
+                        decoded.value,
+                        value
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_encode_bit_field_preserves_neighbors() {
+        // SPN 559 = Accelerator Pedal Kickdown (2 bits at byte 0, bit 2)
+        let spn_def = get_spn_def(559).unwrap();
+        let mut data = [0xFF; 8];
+        assert_eq!(encode_spn(&mut data, spn_def, 1.0), Some(1));
+        assert_eq!(data[0], 0b1111_0111);
+        assert_eq!(decode_spn(&data, spn_def), Some(1.0));
+    }
+
+    #[test]
+    fn test_encode_negative_scale() {
+        // Reversed polarity: raw 0 = 100%, raw 200 = 0%
+        let spn_def = test_spn_def(-0.5, 100.0);
+        let mut data = [0xFF; 8];
+
+        assert_eq!(encode_spn(&mut data, &spn_def, 25.0), Some(150));
+        assert_eq!(decode_spn(&data, &spn_def), Some(25.0));
+
+        // Clamped at both ends of the value range, never into N/A codes
+        let (min, max) = spn_def.value_range();
+        assert_eq!(encode_spn(&mut data, &spn_def, max + 50.0), Some(0));
+        assert_eq!(decode_spn(&data, &spn_def), Some(max));
+        assert_eq!(encode_spn(&mut data, &spn_def, min - 50.0), Some(253));
+        assert_eq!(decode_spn(&data, &spn_def), Some(min));
+    }
+
+    #[test]
+    fn test_encode_negative_offset_percent() {
+        // SPN 513 = Actual Engine Percent Torque, unsigned raw with offset -125
+        let spn_def = get_spn_def(513).unwrap();
+        let mut data = [0xFF; 8];
+
+        assert_eq!(encode_spn(&mut data, spn_def, -50.0), Some(75));
+        assert_eq!(data[2], 75);
+        assert_eq!(decode_spn(&data, spn_def), Some(-50.0));
+
+        // Clamped into [0, max_valid], not wrapped through the sign
+        assert_eq!(value_to_raw(spn_def, -125.0), Some(0));
+        assert_eq!(value_to_raw(spn_def, -200.0), Some(0));
+        assert_eq!(value_to_raw(spn_def, 200.0), Some(253));
+        assert_eq!(spn_def.value_range(), (-125.0, 128.0));
+    }
+
+    #[test]
+    fn test_encode_signed_clamps_to_type_range() {
+        let spn_def = SpnDef {
+            data_type: SpnDataType::Int16,
+            bit_length: 16,
+            ..test_spn_def(1.0, 0.0)
+        };
+        assert_eq!(value_to_raw(&spn_def, -1000.0), Some(-1000));
+        assert_eq!(value_to_raw(&spn_def, -40000.0), Some(i16::MIN as i64));
+        assert_eq!(value_to_raw(&spn_def, 40000.0), Some(i16::MAX as i64));
+
+        let mut data = [0xFF; 8];
+        encode_spn(&mut data, &spn_def, -1000.0).unwrap();
+        assert_eq!(decode_spn(&data, &spn_def), Some(-1000.0));
+    }
+
+    #[test]
+    fn test_encode_signed_avoids_special_codes() {
+        let spn_def = SpnDef {
+            data_type: SpnDataType::Int16,
+            bit_length: 16,
+            ..test_spn_def(1.0, 0.0)
+        };
+        // -1 / -2 would be written as 0xFFFF / 0xFFFE ("not available" / error)
+        assert_eq!(value_to_raw(&spn_def, -1.0), Some(0));
+        assert_eq!(value_to_raw(&spn_def, -2.0), Some(-3));
+
+        for (value, expected) in [(-1.0, 0.0), (-2.0, -3.0), (-3.0, -3.0)] {
+            let mut data = [0xFF; 8];
+            encode_spn(&mut data, &spn_def, value).unwrap();
+            assert_eq!(decode_spn(&data, &spn_def), Some(expected), "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_frame_builder_roundtrip() {
+        use crate::decoder::decode_frame_map;
+
+        let (can_id, data) = FrameBuilder::new(65262, 0x00) // ET1
+            .set_spn(110, 90.0)
+            .and_then(|b| b.set_spn(175, 105.5))
+            .unwrap()
+            .build();
+
+        assert_eq!(can_id, 0x18FEEE00);
+        let values = decode_frame_map(can_id, &data);
+        assert_eq!(values["engine_coolant_temperature"], 90.0);
+        assert_eq!(values["engine_oil_temperature_1"], 105.5);
+        assert_eq!(values.len(), 2); // Everything else left "not available"
+    }
+
+    #[test]
+    fn test_frame_builder_rejects_foreign_spn() {
+        let builder = FrameBuilder::new(61444, 0x00);
+        assert_eq!(builder.set_spn(110, 90.0), None); // Coolant temp is in ET1
+        assert_eq!(builder.set_spn(190, f64::NAN), None);
+        assert_eq!(FrameBuilder::new(0xFF00, 0x00).set_spn(190, 0.0), None);
+    }
+
+    #[test]
+    fn test_frame_builder_addressing() {
+        // PDU1: destination is part of the CAN ID
+        let (can_id, _) = FrameBuilder::new(0xEA00, 0x21)
+            .destination_address(0x00)
+            .priority(9)
+            .build();
+        assert_eq!(can_id, 0x1CEA0021);
+
+        // PDU2: destination is ignored
+        let (can_id, data) = FrameBuilder::new(61444, 0x00)
+            .destination_address(0x17)
+            .build();
+        assert_eq!(can_id, 0x18F00400);
+        assert_eq!(data, [0xFF; 8]);
+    }
+
+    #[test]
+    fn test_encode_unaligned_multi_byte_field() {
+        // 16 bits at byte 1, bit 4: neighbouring nibbles are preserved
+        let spn_def = SpnDef {
+            start_byte: 1,
+            start_bit: 4,
+            bit_length: 16,
+            data_type: SpnDataType::Uint16,
+            ..test_spn_def(1.0, 0.0)
+        };
+        let mut data = [0xAA; 8];
+        assert_eq!(encode_spn(&mut data, &spn_def, 0x1234 as f64), Some(0x1234));
+        assert_eq!(data[..5], [0xAA, 0x4A, 0x23, 0xA1, 0xAA]);
+        assert_eq!(decode_spn(&data, &spn_def), Some(0x1234 as f64));
+    }
+
+    #[test]
+    fn test_encode_data_too_short() {
+        let spn_def = get_spn_def(190).unwrap(); // bytes 3-4
+        let mut data = [0u8; 4];
+        assert_eq!(encode_spn(&mut data, spn_def, 2500.0), None);
+        assert_eq!(data, [0; 4]);
+    }
+
+    #[test]
+    fn test_encode_non_finite() {
+        let spn_def = get_spn_def(110).unwrap();
+        let mut data = [0xFF; 8];
+        assert_eq!(encode_spn(&mut data, spn_def, f64::NAN), None);
+        assert_eq!(value_to_raw(&test_spn_def(0.0, 0.0), 1.0), None);
+    }
+}
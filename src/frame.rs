@@ -3,11 +3,21 @@
 //! Provides utilities for parsing and building J1939 29-bit extended CAN IDs.
 //! All functions are `#[inline]` for zero-cost abstraction.
 
-use crate::types::J1939Id;
+use crate::types::{J1939Id, ProprietaryPgn, Request2};
 
 /// PDU2 format threshold (PF >= 240 means broadcast)
 const PDU2_THRESHOLD: u8 = 240;
 
+/// Global destination address: a PDU1 message sent to every node.
+pub const GLOBAL_ADDRESS: u8 = 0xFF;
+
+/// Null address, used as source address by nodes that have not (or cannot)
+/// claim an address.
+pub const NULL_ADDRESS: u8 = 0xFE;
+
+/// Default priority for PGNs without a more specific one (J1939-21).
+pub(crate) const DEFAULT_PRIORITY: u8 = 6;
+
 /// Parse a 29-bit J1939 CAN ID into its components.
 ///
 /// J1939 CAN ID format (29 bits):
@@ -48,7 +58,10 @@ pub fn parse_can_id(can_id: u32) -> J1939Id {
     // PDU2 (PF >= 240): PGN = DP.PF.PS, PS is part of PGN
     let (pgn, destination_address) = if pf >= PDU2_THRESHOLD {
         // PDU2 format - broadcast
-        ((dp << 16) | ((pf as u32) << 8) | (ps as u32), 0xFF)
+        (
+            (dp << 16) | ((pf as u32) << 8) | (ps as u32),
+            GLOBAL_ADDRESS,
+        )
     } else {
         // PDU1 format - peer-to-peer
         ((dp << 16) | ((pf as u32) << 8), ps)
@@ -84,8 +97,34 @@ pub fn build_can_id(id: &J1939Id) -> u32 {
     id.to_can_id()
 }
 
-/// Request PGN constant
-const REQUEST_PGN: u32 = 0xEA00;
+/// Build a 29-bit CAN ID for a PGN from its addressing components.
+///
+/// Shorthand for [`J1939Id::to_can_id`]. `destination_address` is only used
+/// for PDU1 (peer-to-peer) PGNs. See
+/// [`default_can_id_for_pgn`](crate::database::default_can_id_for_pgn) to
+/// use the PGN's standard priority.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::frame::can_id_for_pgn;
+///
+/// assert_eq!(can_id_for_pgn(61444, 3, 0x00, 0xFF), 0x0CF00400); // EEC1
+/// assert_eq!(can_id_for_pgn(0xEA00, 6, 0xFE, 0x00), 0x18EA00FE); // Request
+/// ```
+#[inline]
+pub fn can_id_for_pgn(pgn: u32, priority: u8, source_address: u8, destination_address: u8) -> u32 {
+    J1939Id {
+        priority,
+        pgn,
+        source_address,
+        destination_address,
+    }
+    .to_can_id()
+}
+
+/// Request PGN (0xEA00).
+pub const REQUEST_PGN: u32 = 0xEA00;
 
 /// Build a Request PGN CAN frame.
 ///
@@ -135,6 +174,173 @@ pub fn build_request_pgn(
     (can_id, data)
 }
 
+/// Parse the requested PGN out of a Request PGN (0xEA00) message.
+///
+/// This is the inverse of [`build_request_pgn`]. Returns `None` if the payload
+/// is shorter than the 3 bytes carrying the PGN.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::frame::{build_request_pgn, parse_request_pgn};
+///
+/// let (_, data) = build_request_pgn(0xFE, 0x00, 65253);
+/// assert_eq!(parse_request_pgn(&data), Some(65253));
+/// assert_eq!(parse_request_pgn(&data[..2]), None);
+/// ```
+#[inline]
+pub fn parse_request_pgn(data: &[u8]) -> Option<u32> {
+    match *data {
+        [b0, b1, b2, ..] => Some(u32::from_le_bytes([b0, b1, b2, 0])),
+        _ => None,
+    }
+}
+
+/// Check if a received frame answers a request for `requested_pgn`.
+///
+/// True when the frame carries `requested_pgn` and was sent by
+/// `expected_sa`, the address the request was sent to. Pass
+/// [`GLOBAL_ADDRESS`] for a request sent to all nodes to accept the response
+/// from any sender.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::frame::is_response_to;
+///
+/// // Engine hours were requested from the engine (0x00)
+/// assert!(is_response_to(0x18FEE500, 65253, 0x00)); // HOURS from 0x00
+/// assert!(!is_response_to(0x18FEE503, 65253, 0x00)); // From another ECU
+/// assert!(!is_response_to(0x18FEEE00, 65253, 0x00)); // Another PGN
+/// ```
+#[inline]
+pub const fn is_response_to(can_id: u32, requested_pgn: u32, expected_sa: u8) -> bool {
+    extract_pgn(can_id) == requested_pgn
+        && (expected_sa == GLOBAL_ADDRESS || extract_source_address(can_id) == expected_sa)
+}
+
+/// Build a Request PGN frame together with a matcher for its response.
+///
+/// Returns the frame from [`build_request_pgn`] and a closure that
+/// recognizes the response CAN ID (see [`is_response_to`]).
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::frame::request_and_match;
+///
+/// let ((can_id, data), is_response) = request_and_match(0xF9, 0x00, 65253);
+/// assert_eq!(can_id, 0x18EA00F9);
+/// assert_eq!(data, [0xE5, 0xFE, 0x00]);
+///
+/// assert!(is_response(0x18FEE500));
+/// assert!(!is_response(0x18FEE517));
+/// ```
+#[inline]
+pub fn request_and_match(
+    source_address: u8,
+    destination_address: u8,
+    requested_pgn: u32,
+) -> ((u32, [u8; 3]), impl Fn(u32) -> bool) {
+    let request = build_request_pgn(source_address, destination_address, requested_pgn);
+    let matcher = move |can_id: u32| is_response_to(can_id, requested_pgn, destination_address);
+    (request, matcher)
+}
+
+/// Request2 PGN (0xC900).
+pub const REQUEST2_PGN: u32 = 0xC900;
+
+/// "Use transfer mode" control bits (byte 4, bits 1-2) of a Request2 message
+const REQUEST2_USE_TRANSFER: u8 = 0b01;
+
+/// Build a Request2 PGN CAN frame.
+///
+/// The Request2 PGN (0xC900) works like the basic Request PGN but can ask the
+/// responder to deliver the data through the Transfer PGN instead of the
+/// requested PGN itself.
+///
+/// Data layout:
+/// - Bytes 1-3: requested PGN (little-endian)
+/// - Byte 4: bits 1-2 "use transfer mode" (00 = no, 01 = yes), bits 3-8 reserved (1s)
+/// - Bytes 5-7: transfer PGN (little-endian, 0xFFFFFF when not used)
+/// - Byte 8: reserved (0xFF)
+///
+/// # Arguments
+///
+/// * `source_address` - Our source address
+/// * `destination_address` - Target ECU address (0xFF for broadcast)
+/// * `requested_pgn` - The PGN we want to receive
+/// * `transfer_pgn` - PGN the data should be transferred with, or `None` for a direct response
+///
+/// # Returns
+///
+/// A tuple of (CAN ID, data bytes).
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::frame::{build_request2, parse_request2};
+///
+/// let (can_id, data) = build_request2(0xFE, 0x00, 65253, Some(0xCA00));
+/// assert_eq!(can_id, 0x18C900FE);
+///
+/// let request = parse_request2(&data).unwrap();
+/// assert_eq!(request.requested_pgn, 65253);
+/// assert_eq!(request.transfer_pgn, Some(0xCA00));
+/// ```
+#[inline]
+pub fn build_request2(
+    source_address: u8,
+    destination_address: u8,
+    requested_pgn: u32,
+    transfer_pgn: Option<u32>,
+) -> (u32, [u8; 8]) {
+    // Priority 6, DP=0, PF=0xC9, PS=destination_address, SA=source_address
+    let can_id = (6u32 << 26)
+        | ((REQUEST2_PGN & 0xFF00) << 8)
+        | ((destination_address as u32) << 8)
+        | (source_address as u32);
+
+    let (control, transfer) = match transfer_pgn {
+        Some(pgn) => (REQUEST2_USE_TRANSFER, pgn),
+        None => (0b00, 0xFF_FFFF),
+    };
+
+    let data = [
+        requested_pgn as u8,
+        (requested_pgn >> 8) as u8,
+        (requested_pgn >> 16) as u8,
+        0xFC | control,
+        transfer as u8,
+        (transfer >> 8) as u8,
+        (transfer >> 16) as u8,
+        0xFF,
+    ];
+
+    (can_id, data)
+}
+
+/// Parse the data of a Request2 (PGN 0xC900) message.
+///
+/// Returns `None` if the payload is shorter than the 8 bytes of a Request2 frame.
+/// See [`build_request2`] for the data layout.
+#[inline]
+pub fn parse_request2(data: &[u8]) -> Option<Request2> {
+    let data: &[u8; 8] = data.get(..8)?.try_into().ok()?;
+
+    let requested_pgn = u32::from_le_bytes([data[0], data[1], data[2], 0]);
+    let transfer_pgn = if data[3] & 0x03 == REQUEST2_USE_TRANSFER {
+        Some(u32::from_le_bytes([data[4], data[5], data[6], 0]))
+    } else {
+        None
+    };
+
+    Some(Request2 {
+        requested_pgn,
+        transfer_pgn,
+    })
+}
+
 /// Maximum valid 29-bit CAN ID
 const MAX_29BIT_ID: u32 = 0x1FFFFFFF;
 
@@ -153,23 +359,201 @@ pub const fn is_valid_j1939_id(can_id: u32) -> bool {
 /// Hot path: always inlined for frame decoding.
 #[inline(always)]
 pub const fn extract_pgn(can_id: u32) -> u32 {
-    let ps = (can_id >> 8) as u8;
-    let pf = (can_id >> 16) as u8;
-    let dp = (can_id >> 24) & 0x01;
+    pgn_from_components(
+        (can_id >> 24) as u8,
+        (can_id >> 16) as u8,
+        (can_id >> 8) as u8,
+    )
+}
 
+/// Build a PGN from its data page, PDU format and PDU specific fields.
+///
+/// Same PDU1/PDU2 rule as [`extract_pgn`]: for PDU1 (`pf < 240`) `ps` is a
+/// destination address and not part of the PGN. Only the low bit of `dp` is
+/// used.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::frame::pgn_from_components;
+///
+/// assert_eq!(pgn_from_components(0, 0xF0, 0x04), 61444); // EEC1
+/// assert_eq!(pgn_from_components(0, 0xEA, 0x21), 0xEA00); // Request, DA ignored
+/// ```
+#[inline(always)]
+pub const fn pgn_from_components(dp: u8, pf: u8, ps: u8) -> u32 {
+    let base = ((dp as u32 & 0x01) << 16) | ((pf as u32) << 8);
     if pf >= PDU2_THRESHOLD {
-        (dp << 16) | ((pf as u32) << 8) | (ps as u32)
+        base | ps as u32
     } else {
-        (dp << 16) | ((pf as u32) << 8)
+        base
     }
 }
 
+/// Split a PGN into `(dp, pf, ps)`.
+///
+/// `ps` is `None` for PDU1 PGNs, where that field carries the destination
+/// address instead. Inverse of [`pgn_from_components`] for PGNs that fit in
+/// 18 bits.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::frame::pgn_to_components;
+///
+/// assert_eq!(pgn_to_components(61444), (0, 0xF0, Some(0x04))); // EEC1
+/// assert_eq!(pgn_to_components(0xEA00), (0, 0xEA, None)); // Request
+/// ```
+#[inline]
+pub const fn pgn_to_components(pgn: u32) -> (u8, u8, Option<u8>) {
+    let dp = ((pgn >> 16) & 0x01) as u8;
+    let pf = (pgn >> 8) as u8;
+    let ps = if pf >= PDU2_THRESHOLD {
+        Some(pgn as u8)
+    } else {
+        None
+    };
+    (dp, pf, ps)
+}
+
 /// Extract just the source address from a CAN ID.
 #[inline]
 pub const fn extract_source_address(can_id: u32) -> u8 {
     can_id as u8
 }
 
+/// PDU Format of the Proprietary A / A2 PGNs (0xEF00 / 0x1EF00)
+const PROPRIETARY_A_PF: u32 = 0xEF;
+
+/// PDU Format of the Proprietary B PGN range (0xFF00-0xFFFF)
+const PROPRIETARY_B_PF: u32 = 0xFF;
+
+/// Classify a PGN into one of the J1939-21 proprietary ranges.
+///
+/// For the PDU1 ranges (A / A2) the low byte is ignored, since it carries the
+/// destination address rather than being part of the PGN.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::frame::proprietary_pgn_kind;
+/// use voltage_j1939::types::ProprietaryPgn;
+///
+/// assert_eq!(proprietary_pgn_kind(0xEF00), Some(ProprietaryPgn::ProprietaryA));
+/// assert_eq!(proprietary_pgn_kind(0x1EF00), Some(ProprietaryPgn::ProprietaryA2));
+/// assert_eq!(proprietary_pgn_kind(0xFF42), Some(ProprietaryPgn::ProprietaryB));
+/// assert_eq!(proprietary_pgn_kind(61444), None); // EEC1
+/// ```
+#[inline]
+pub const fn proprietary_pgn_kind(pgn: u32) -> Option<ProprietaryPgn> {
+    let dp = (pgn >> 16) & 0x01;
+    let pf = (pgn >> 8) & 0xFF;
+
+    if pgn > 0x3FFFF {
+        None
+    } else if pf == PROPRIETARY_A_PF {
+        if dp == 0 {
+            Some(ProprietaryPgn::ProprietaryA)
+        } else {
+            Some(ProprietaryPgn::ProprietaryA2)
+        }
+    } else if pf == PROPRIETARY_B_PF && dp == 0 {
+        Some(ProprietaryPgn::ProprietaryB)
+    } else {
+        None
+    }
+}
+
+/// Check if a PGN falls in one of the proprietary ranges (A, A2 or B).
+#[inline]
+pub const fn is_proprietary_pgn(pgn: u32) -> bool {
+    proprietary_pgn_kind(pgn).is_some()
+}
+
+/// Transport Protocol connection management PGN (TP.CM, 0xEC00).
+pub const TP_CM_PGN: u32 = 0xEC00;
+
+/// Transport Protocol data transfer PGN (TP.DT, 0xEB00).
+pub const TP_DT_PGN: u32 = 0xEB00;
+
+/// Extended Transport Protocol connection management PGN (ETP.CM, 0xC800).
+pub const ETP_CM_PGN: u32 = 0xC800;
+
+/// Extended Transport Protocol data transfer PGN (ETP.DT, 0xC700).
+pub const ETP_DT_PGN: u32 = 0xC700;
+
+/// Check if a PGN belongs to the transport protocols (TP or ETP).
+///
+/// Frames with these PGNs carry segments of a larger message and belong in a
+/// reassembler, not the SPN decoder. Request2 (0xC900) is not a transport
+/// PGN.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::frame::{extract_pgn, is_transport_pgn};
+///
+/// assert!(is_transport_pgn(extract_pgn(0x1CECFF00))); // TP.CM BAM
+/// assert!(is_transport_pgn(extract_pgn(0x1CEBFF00))); // TP.DT
+/// assert!(!is_transport_pgn(extract_pgn(0x0CF00400))); // EEC1
+/// ```
+#[inline]
+pub const fn is_transport_pgn(pgn: u32) -> bool {
+    matches!(pgn, TP_CM_PGN | TP_DT_PGN | ETP_CM_PGN | ETP_DT_PGN)
+}
+
+/// Acknowledgement PGN (ACKM, 0xE800).
+pub const ACKNOWLEDGEMENT_PGN: u32 = 0xE800;
+
+/// Address Claimed PGN (0xEE00).
+pub const ADDRESS_CLAIM_PGN: u32 = 0xEE00;
+
+/// Check if a CAN frame is a Request (PGN 0xEA00), whatever its destination.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::frame::is_request_pgn;
+///
+/// assert!(is_request_pgn(0x18EA00FE)); // Request to 0x00
+/// assert!(is_request_pgn(0x18EAFFFE)); // Global request
+/// assert!(!is_request_pgn(0x0CF00400)); // EEC1
+/// ```
+#[inline]
+pub const fn is_request_pgn(can_id: u32) -> bool {
+    extract_pgn(can_id) == REQUEST_PGN
+}
+
+/// Check if a CAN frame is an Acknowledgement (PGN 0xE800).
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::frame::is_acknowledgement;
+///
+/// assert!(is_acknowledgement(0x18E8FF00));
+/// assert!(!is_acknowledgement(0x18EA00FE));
+/// ```
+#[inline]
+pub const fn is_acknowledgement(can_id: u32) -> bool {
+    extract_pgn(can_id) == ACKNOWLEDGEMENT_PGN
+}
+
+/// Check if a CAN frame is an Address Claimed message (PGN 0xEE00).
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::frame::is_address_claim;
+///
+/// assert!(is_address_claim(0x18EEFF00));
+/// assert!(!is_address_claim(0x18E8FF00));
+/// ```
+#[inline]
+pub const fn is_address_claim(can_id: u32) -> bool {
+    extract_pgn(can_id) == ADDRESS_CLAIM_PGN
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -249,6 +633,27 @@ mod tests {
         assert_eq!(original, rebuilt);
     }
 
+    #[test]
+    fn test_can_id_for_pgn() {
+        assert_eq!(can_id_for_pgn(65262, 6, 0x00, GLOBAL_ADDRESS), 0x18FEEE00);
+        // PDU2: destination is ignored
+        assert_eq!(can_id_for_pgn(65262, 6, 0x00, 0x21), 0x18FEEE00);
+        // PDU1: destination goes into PS
+        assert_eq!(can_id_for_pgn(0xEA00, 3, 0x21, 0x00), 0x0CEA0021);
+        for can_id in [0x0CF00400, 0x18FECA17, 0x1CEA0021] {
+            let id = parse_can_id(can_id);
+            assert_eq!(
+                can_id_for_pgn(
+                    id.pgn,
+                    id.priority,
+                    id.source_address,
+                    id.destination_address
+                ),
+                can_id
+            );
+        }
+    }
+
     #[test]
     fn test_build_can_id_pdu1_roundtrip() {
         // PDU1 format with specific destination address
@@ -306,6 +711,92 @@ mod tests {
         assert_eq!(data[2], 0x01); // MSB
     }
 
+    #[test]
+    fn test_request_pgn_roundtrip() {
+        for pgn in [61444, 65253, 65262, 0xEA00, 0x010203] {
+            let (_, data) = build_request_pgn(0xFE, 0x00, pgn);
+            assert_eq!(parse_request_pgn(&data), Some(pgn));
+        }
+    }
+
+    #[test]
+    fn test_parse_request_pgn_short_payload() {
+        assert_eq!(parse_request_pgn(&[]), None);
+        assert_eq!(parse_request_pgn(&[0xE5]), None);
+        assert_eq!(parse_request_pgn(&[0xE5, 0xFE]), None);
+
+        // Longer payloads (e.g. padded to 8 bytes) only use the first 3 bytes
+        let data = [0xE5, 0xFE, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+        assert_eq!(parse_request_pgn(&data), Some(65253));
+    }
+
+    #[test]
+    fn test_is_response_to() {
+        // Engine hours from the engine ECU, any priority
+        assert!(is_response_to(0x18FEE500, 65253, 0x00));
+        assert!(is_response_to(0x0CFEE500, 65253, 0x00));
+        assert!(!is_response_to(0x18FEE501, 65253, 0x00));
+
+        // Broadcast request: any responder matches
+        assert!(is_response_to(0x18FEE517, 65253, GLOBAL_ADDRESS));
+        assert!(!is_response_to(0x18FEE617, 65253, GLOBAL_ADDRESS));
+
+        // PDU1 response: destination is not part of the PGN
+        assert!(is_response_to(0x18EEF900, 0xEE00, 0x00));
+    }
+
+    #[test]
+    fn test_request_and_match() {
+        let (request, is_response) = request_and_match(0xF9, 0x00, 65253);
+        assert_eq!(request, build_request_pgn(0xF9, 0x00, 65253));
+        assert!(is_response(0x18FEE500));
+        assert!(!is_response(0x18FEE5F9));
+        // The request itself is not a response
+        assert!(!is_response(request.0));
+    }
+
+    // ========================================================================
+    // Request2 tests
+    // ========================================================================
+
+    #[test]
+    fn test_build_request2_can_id() {
+        let (can_id, _) = build_request2(0xFE, 0x00, 65253, None);
+        assert_eq!(can_id, 0x18C900FE);
+
+        let id = parse_can_id(can_id);
+        assert_eq!(id.priority, 6);
+        assert_eq!(id.pgn, 0xC900);
+        assert_eq!(id.destination_address, 0x00);
+        assert_eq!(id.source_address, 0xFE);
+    }
+
+    #[test]
+    fn test_build_request2_data_format() {
+        let (_, data) = build_request2(0xFE, 0x00, 0x010203, Some(0xCA00));
+        assert_eq!(data, [0x03, 0x02, 0x01, 0xFD, 0x00, 0xCA, 0x00, 0xFF]);
+
+        // Without transfer: control bits 00, transfer PGN not available
+        let (_, data) = build_request2(0xFE, 0x00, 0x010203, None);
+        assert_eq!(data, [0x03, 0x02, 0x01, 0xFC, 0xFF, 0xFF, 0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn test_request2_roundtrip() {
+        for transfer_pgn in [None, Some(0xCA00)] {
+            let (_, data) = build_request2(0x10, 0xFF, 65262, transfer_pgn);
+            let request = parse_request2(&data).unwrap();
+            assert_eq!(request.requested_pgn, 65262);
+            assert_eq!(request.transfer_pgn, transfer_pgn);
+        }
+    }
+
+    #[test]
+    fn test_parse_request2_too_short() {
+        assert!(parse_request2(&[]).is_none());
+        assert!(parse_request2(&[0xE5, 0xFE, 0x00, 0xFC, 0xFF, 0xFF, 0xFF]).is_none());
+    }
+
     // ========================================================================
     // extract_pgn tests
     // ========================================================================
@@ -335,6 +826,31 @@ mod tests {
         assert_eq!(PGN, 61444);
     }
 
+    #[test]
+    fn test_pgn_components_roundtrip() {
+        for pgn in [
+            0u32, 0xEA00, 0xEC00, 0xF004, 0xFEEE, 0xFFFF, 0x1EF00, 0x1FEFF,
+        ] {
+            let (dp, pf, ps) = pgn_to_components(pgn);
+            assert_eq!(
+                pgn_from_components(dp, pf, ps.unwrap_or(0x42)),
+                pgn,
+                "PGN 0x{:X}",
+                pgn
+            );
+        }
+        // Components of a parsed CAN ID rebuild its PGN
+        for can_id in [0x18EA00FE, 0x0CF00400, 0x19FEEE00, 0x1CECFF3D] {
+            let id = parse_can_id(can_id);
+            let (dp, pf, ps) = pgn_to_components(id.pgn);
+            assert_eq!(dp as u32, (can_id >> 24) & 0x01);
+            assert_eq!(pf, (can_id >> 16) as u8);
+            assert_eq!(ps.unwrap_or(id.destination_address), (can_id >> 8) as u8);
+        }
+        assert_eq!(pgn_from_components(0xFF, 0xF0, 0x04), 0x1F004); // Only DP bit 0
+        assert_eq!(pgn_to_components(0x1EF00), (1, 0xEF, None));
+    }
+
     // ========================================================================
     // extract_source_address tests
     // ========================================================================
@@ -369,12 +885,55 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_is_valid_j1939_id_const() {
         // Verify it can be used in const context
         const IS_VALID: bool = is_valid_j1939_id(0x0CF00400);
         assert!(IS_VALID);
     }
 
+    // ========================================================================
+    // Proprietary PGN tests
+    // ========================================================================
+
+    #[test]
+    fn test_proprietary_pgn_kind() {
+        assert_eq!(
+            proprietary_pgn_kind(0xEF00),
+            Some(ProprietaryPgn::ProprietaryA)
+        );
+        assert_eq!(
+            proprietary_pgn_kind(0x1EF00),
+            Some(ProprietaryPgn::ProprietaryA2)
+        );
+        assert_eq!(
+            proprietary_pgn_kind(0xFF00),
+            Some(ProprietaryPgn::ProprietaryB)
+        );
+        assert_eq!(
+            proprietary_pgn_kind(0xFFFF),
+            Some(ProprietaryPgn::ProprietaryB)
+        );
+
+        // Standard PGNs are not proprietary
+        assert_eq!(proprietary_pgn_kind(61444), None); // EEC1
+        assert_eq!(proprietary_pgn_kind(0xEA00), None); // Request
+        assert_eq!(proprietary_pgn_kind(0xFEFF), None);
+        assert_eq!(proprietary_pgn_kind(0x1FF00), None); // DP=1 is not PropB
+    }
+
+    #[test]
+    fn test_proprietary_pgn_from_can_id() {
+        // Proprietary A to 0x21 from 0x80: PS is the destination, not part of the PGN
+        let id = parse_can_id(0x18EF2180);
+        assert_eq!(id.pgn, 0xEF00);
+        assert!(is_proprietary_pgn(id.pgn));
+
+        // Proprietary B broadcast
+        assert!(is_proprietary_pgn(extract_pgn(0x18FF1080)));
+        assert!(!is_proprietary_pgn(extract_pgn(0x0CF00400)));
+    }
+
     // ========================================================================
     // Edge case tests
     // ========================================================================
@@ -395,4 +954,40 @@ mod tests {
         assert_eq!(id.source_address, 0xFF);
         assert!(id.is_broadcast()); // PF=0xFF >= 240
     }
+
+    // ========================================================================
+    // Transport PGN tests
+    // ========================================================================
+
+    #[test]
+    fn test_is_transport_pgn() {
+        // Destination address is not part of a PDU1 PGN
+        for can_id in [0x1CECFF00, 0x1CEC0021, 0x1CEB0021, 0x1CC80021, 0x1CC70021] {
+            assert!(is_transport_pgn(extract_pgn(can_id)), "0x{:08X}", can_id);
+        }
+        assert!(!is_transport_pgn(REQUEST_PGN));
+        assert!(!is_transport_pgn(REQUEST2_PGN));
+        assert!(!is_transport_pgn(0x1EC00)); // Data page 1
+        assert!(!is_transport_pgn(0xFECA)); // DM1
+    }
+
+    #[test]
+    fn test_control_pgn_checks() {
+        // PDU1: the destination address is not part of the PGN
+        for da in [0x00, 0x21, 0xFF] {
+            let id = |pgn: u32| can_id_for_pgn(pgn, 6, 0x80, da);
+            assert!(is_request_pgn(id(REQUEST_PGN)));
+            assert!(is_acknowledgement(id(ACKNOWLEDGEMENT_PGN)));
+            assert!(is_address_claim(id(ADDRESS_CLAIM_PGN)));
+        }
+
+        let checks: [fn(u32) -> bool; 3] = [is_request_pgn, is_acknowledgement, is_address_claim];
+        for check in checks {
+            assert!(!check(0x0CF00400)); // EEC1
+            assert!(!check(0x18C9FF00)); // Request2
+            assert!(!check(0x19EAFF00)); // Data page 1
+        }
+        assert!(!is_request_pgn(0x18EEFF00));
+        assert!(!is_address_claim(0x18EA00FE));
+    }
 }
@@ -0,0 +1,250 @@
+//! Identification PGNs.
+//!
+//! Parsers for the ASCII identification messages (software version,
+//! component make/model/serial, VIN).
+//! These are usually longer than 8 bytes and arrive via the transport
+//! protocol, so the parsers accept the reassembled payload.
+
+/// SOFT - Software Identification PGN (65242 / 0xFEDA)
+pub const SOFT_PGN: u32 = 0xFEDA;
+
+/// CI - Component Identification PGN (65259 / 0xFEEB)
+pub const CI_PGN: u32 = 0xFEEB;
+
+/// VI - Vehicle Identification PGN (65260 / 0xFEEC)
+pub const VI_PGN: u32 = 0xFEEC;
+
+/// Contents of a Component Identification (CI) message.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ComponentId {
+    /// Manufacturer code.
+    pub make: String,
+    /// Model.
+    pub model: String,
+    /// Serial number.
+    pub serial_number: String,
+    /// Unit number (power unit), often empty.
+    pub unit_number: String,
+}
+
+/// Field delimiter used by the ASCII identification PGNs.
+const FIELD_DELIMITER: u8 = b'*';
+
+/// Check if a byte is trailing padding (unused bytes are 0xFF, some ECUs use NUL or spaces).
+#[inline]
+fn is_padding(b: u8) -> bool {
+    matches!(b, 0xFF | 0x00 | b' ')
+}
+
+/// Convert a field to a string, trimming trailing padding.
+fn field_to_string(field: &[u8]) -> String {
+    let end = field
+        .iter()
+        .rposition(|&b| !is_padding(b))
+        .map_or(0, |i| i + 1);
+    String::from_utf8_lossy(&field[..end]).into_owned()
+}
+
+/// Parse a Software Identification (SOFT) payload.
+///
+/// Byte 1 holds the number of software identification fields, followed by
+/// the ASCII fields, each terminated by `*`. Pass the reassembled transport
+/// protocol buffer when the message is multi-packet.
+///
+/// At most the advertised number of fields is returned; a final field
+/// missing its delimiter is still returned. Trailing padding (0xFF, NUL,
+/// spaces) is trimmed from each field.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::identification::parse_software_id;
+///
+/// let data = b"\x02ECM 1.2.3*BOOT 4.5*\xFF\xFF";
+/// assert_eq!(parse_software_id(data), vec!["ECM 1.2.3", "BOOT 4.5"]);
+/// ```
+pub fn parse_software_id(data: &[u8]) -> Vec<String> {
+    let Some((&count, rest)) = data.split_first() else {
+        return Vec::new();
+    };
+
+    // Drop padding after the last field so it isn't reported as a field
+    let end = rest
+        .iter()
+        .rposition(|&b| !is_padding(b))
+        .map_or(0, |i| i + 1);
+    let rest = &rest[..end];
+    if rest.is_empty() {
+        return Vec::new();
+    }
+
+    let rest = rest.strip_suffix(&[FIELD_DELIMITER]).unwrap_or(rest);
+    rest.split(|&b| b == FIELD_DELIMITER)
+        .take(count as usize)
+        .map(field_to_string)
+        .collect()
+}
+
+/// Parse a Component Identification (CI) payload.
+///
+/// The payload is `make*model*serial number*unit number*` in ASCII. Missing
+/// trailing fields are left empty and trailing padding is stripped from
+/// each field. Returns `None` if every field is empty.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::identification::parse_component_id;
+///
+/// let ci = parse_component_id(b"CMMNS*ISX15*79123456**").unwrap();
+/// assert_eq!(ci.make, "CMMNS");
+/// assert_eq!(ci.model, "ISX15");
+/// assert_eq!(ci.serial_number, "79123456");
+/// assert!(ci.unit_number.is_empty());
+/// ```
+pub fn parse_component_id(data: &[u8]) -> Option<ComponentId> {
+    let mut fields = data.split(|&b| b == FIELD_DELIMITER).map(field_to_string);
+    let mut next = || fields.next().unwrap_or_default();
+
+    let ci = ComponentId {
+        make: next(),
+        model: next(),
+        serial_number: next(),
+        unit_number: next(),
+    };
+
+    if ci == ComponentId::default() {
+        return None;
+    }
+    Some(ci)
+}
+
+/// Parse a Vehicle Identification (VI) payload into the VIN.
+///
+/// The VIN is read up to the `*` delimiter, or to the end of the buffer
+/// when the VIN fills it without one. Trailing padding is stripped.
+/// Returns `None` if the VIN is empty or not ASCII.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::identification::parse_vin;
+///
+/// let data = b"1FUJGLDR5CLBP8834*\xFF\xFF";
+/// assert_eq!(parse_vin(data).as_deref(), Some("1FUJGLDR5CLBP8834"));
+/// ```
+pub fn parse_vin(data: &[u8]) -> Option<String> {
+    let field = data.split(|&b| b == FIELD_DELIMITER).next().unwrap_or(data);
+    let vin = field_to_string(field);
+
+    if vin.is_empty() || !vin.is_ascii() {
+        return None;
+    }
+    Some(vin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_software_id_single_frame() {
+        let data = [0x01, b'V', b'1', b'.', b'0', b'*', 0xFF, 0xFF];
+        assert_eq!(parse_software_id(&data), vec!["V1.0"]);
+    }
+
+    #[test]
+    fn test_parse_software_id_multi_packet() {
+        let mut data = vec![0x03];
+        data.extend_from_slice(b"ENGINE_SW 12.04.001*CAL 2024-03*BOOT 1.1*");
+        data.extend_from_slice(&[0xFF; 5]);
+        assert_eq!(
+            parse_software_id(&data),
+            vec!["ENGINE_SW 12.04.001", "CAL 2024-03", "BOOT 1.1"]
+        );
+    }
+
+    #[test]
+    fn test_parse_software_id_trims_field_padding() {
+        let data = b"\x02ABC  *DEF\x00\x00*";
+        assert_eq!(parse_software_id(data), vec!["ABC", "DEF"]);
+    }
+
+    #[test]
+    fn test_parse_software_id_respects_count() {
+        let data = b"\x01FIRST*SECOND*";
+        assert_eq!(parse_software_id(data), vec!["FIRST"]);
+    }
+
+    #[test]
+    fn test_parse_software_id_missing_final_delimiter() {
+        let data = b"\x02ONE*TWO";
+        assert_eq!(parse_software_id(data), vec!["ONE", "TWO"]);
+    }
+
+    #[test]
+    fn test_parse_component_id() {
+        let mut data = b"CMMNS*ISX15*79123456*UNIT7*".to_vec();
+        data.extend_from_slice(&[0xFF; 4]);
+        let ci = parse_component_id(&data).unwrap();
+        assert_eq!(
+            ci,
+            ComponentId {
+                make: "CMMNS".into(),
+                model: "ISX15".into(),
+                serial_number: "79123456".into(),
+                unit_number: "UNIT7".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_component_id_missing_fields() {
+        let ci = parse_component_id(b"CAT*C15").unwrap();
+        assert_eq!(ci.make, "CAT");
+        assert_eq!(ci.model, "C15");
+        assert!(ci.serial_number.is_empty());
+        assert!(ci.unit_number.is_empty());
+    }
+
+    #[test]
+    fn test_parse_component_id_empty() {
+        assert!(parse_component_id(&[]).is_none());
+        assert!(parse_component_id(b"****").is_none());
+        assert!(parse_component_id(&[0xFF; 8]).is_none());
+    }
+
+    #[test]
+    fn test_parse_vin_delimited() {
+        let mut data = b"1FUJGLDR5CLBP8834*".to_vec();
+        data.extend_from_slice(&[0xFF; 3]);
+        assert_eq!(parse_vin(&data).as_deref(), Some("1FUJGLDR5CLBP8834"));
+    }
+
+    #[test]
+    fn test_parse_vin_fills_buffer() {
+        let data = b"1FUJGLDR5CLBP8834";
+        assert_eq!(parse_vin(data).as_deref(), Some("1FUJGLDR5CLBP8834"));
+    }
+
+    #[test]
+    fn test_parse_vin_padding_without_delimiter() {
+        let data = b"1FUJGLDR5CLBP8834\xFF\xFF\xFF";
+        assert_eq!(parse_vin(data).as_deref(), Some("1FUJGLDR5CLBP8834"));
+    }
+
+    #[test]
+    fn test_parse_vin_invalid() {
+        assert!(parse_vin(&[]).is_none());
+        assert!(parse_vin(b"*").is_none());
+        assert!(parse_vin(&[0xFF; 8]).is_none());
+        assert!(parse_vin(&[b'1', 0x80, b'2', b'*']).is_none());
+    }
+
+    #[test]
+    fn test_parse_software_id_empty() {
+        assert!(parse_software_id(&[]).is_empty());
+        assert!(parse_software_id(&[0x00]).is_empty());
+        assert!(parse_software_id(&[0x01, 0xFF, 0xFF]).is_empty());
+    }
+}
@@ -6,12 +6,17 @@
 //!
 //! # Features
 //!
-//! - **Zero dependencies**: Pure Rust, no external crates required
+//! - **Zero dependencies**: Pure Rust, no external crates required by default
 //! - **Built-in SPN database**: 60+ SPNs across 12+ PGNs for engine/generator monitoring
 //! - **CAN ID parsing**: Parse and build 29-bit extended J1939 CAN IDs
 //! - **Bit-level decoding**: Extract values with scale, offset, and bit field support
 //! - **"Not available" detection**: Automatic handling of J1939 special values
 //!
+//! # Optional Features
+//!
+//! - **`embedded-can`**: [`decode_can_frame`] decodes any
+//!   `embedded_can::Frame` directly, e.g. `socketcan::CanFrame`
+//!
 //! # Quick Start
 //!
 //! ```rust
@@ -32,6 +37,20 @@
 //! println!("PGN: {}, SA: 0x{:02X}", id.pgn, id.source_address);
 //! ```
 //!
+//! # Zero-Allocation Decoding
+//!
+//! [`decode_frame_iter`] yields the same values as [`decode_frame`] without
+//! allocating a `Vec`, which suits embedded and hot-path use:
+//!
+//! ```rust
+//! use voltage_j1939::decode_frame_iter;
+//!
+//! let data = [0xFF, 0xFF, 0xFF, 0x20, 0x4E, 0xFF, 0xFF, 0xFF];
+//! for spn in decode_frame_iter(0x0CF00400, &data) {
+//!     println!("{}: {} {}", spn.name, spn.value, spn.unit);
+//! }
+//! ```
+//!
 //! # Decoding Individual SPNs
 //!
 //! ```rust
@@ -54,7 +73,7 @@
 //! |-----|------|-------------|
 //! | 61444 | EEC1 | Electronic Engine Controller 1 |
 //! | 61443 | EEC2 | Electronic Engine Controller 2 |
-//! | 65270 | EEC3 | Electronic Engine Controller 3 |
+//! | 65247 | EEC3 | Electronic Engine Controller 3 |
 //! | 65262 | ET1 | Engine Temperature 1 |
 //! | 65263 | EFL/P1 | Engine Fluid Level/Pressure 1 |
 //! | 65270 | IC1 | Inlet/Exhaust Conditions 1 |
@@ -63,9 +82,11 @@
 //! | 65266 | LFE | Fuel Economy |
 //! | 65253 | HOURS | Engine Hours/Revolutions |
 //! | 65257 | FC | Fuel Consumption |
-//! | 65259 | VH | Vehicle Hours |
+//! | 65255 | VH | Vehicle Hours |
 //! | 65276 | DD | Dash Display |
 //! | 65265 | CCVS | Cruise Control/Vehicle Speed |
+//! | 65110 | AT1T1I | Aftertreatment 1 DEF Tank 1 Information |
+//! | 65031 | ET | Exhaust Temperature (per bank) |
 //!
 //! # J1939 CAN ID Format
 //!
@@ -84,19 +105,57 @@
 //! - **SA**: Source Address
 
 #![deny(missing_docs)]
+#![cfg_attr(docsrs, feature(doc_cfg))]
 // Note: We allow unsafe in decoder.rs for performance-critical hot paths
 // after bounds checking. All unsafe is minimal and well-documented.
 
 pub mod database;
+pub mod dbc;
 pub mod decoder;
+pub mod derived;
+pub mod diagnostics;
+pub mod encoder;
 pub mod frame;
+pub mod identification;
+pub mod monitor;
+pub mod prelude;
+pub mod transport;
 pub mod types;
 
 // Re-export commonly used functions (optimized O(log n) lookups)
-pub use database::{database_stats, get_spn_def, get_spns_for_pgn, list_supported_pgns};
-pub use decoder::{decode_frame, decode_frame_iter, decode_spn, decode_spn_by_number, decode_spn_full};
+pub use database::{
+    database_stats, database_version, default_can_id_for_pgn, default_priority, expected_dlc,
+    expected_interval_ms, get_pgn_info, get_spn_def, get_spn_def_in_pgn, get_spn_def_instance,
+    get_spns_for_pgn, list_supported_pgns, pgn_byte_coverage, pgns_for_spn, sanity_check_ranges,
+    search_spns, spn_state_name, spns_for_pgn_const, unit_id, unit_str, Database,
+};
+pub use dbc::export_dbc;
+#[cfg(feature = "embedded-can")]
+pub use decoder::decode_can_frame;
+pub use decoder::{
+    decode_addressed_frame, decode_frame, decode_frame_all, decode_frame_array,
+    decode_frame_array_into, decode_frame_at, decode_frame_buffer, decode_frame_c,
+    decode_frame_collect, decode_frame_iter, decode_frame_map, decode_frame_partial,
+    decode_frame_ref, decode_frame_result, decode_frame_strict, decode_frame_verbose,
+    decode_frame_with, decode_frame_with_id, decode_frame_with_source, decode_frames, decode_hex,
+    decode_pgn, decode_spn, decode_spn_by_number, decode_spn_f32, decode_spn_full,
+    decode_spn_milli, decode_spn_ref, decode_spn_status, decode_spn_temp, decode_spn_with_order,
+    decode_switch, extract_bits, extract_bits_with, format_frame, read_le_int, read_le_uint,
+};
+pub use encoder::{encode_spn, value_to_raw, FrameBuilder};
 pub use frame::{
-    build_can_id, build_request_pgn, extract_pgn, extract_source_address, is_valid_j1939_id,
-    parse_can_id,
+    build_can_id, build_request2, build_request_pgn, can_id_for_pgn, extract_pgn,
+    extract_source_address, is_acknowledgement, is_address_claim, is_proprietary_pgn,
+    is_request_pgn, is_response_to, is_transport_pgn, is_valid_j1939_id, parse_can_id,
+    parse_request2, parse_request_pgn, pgn_from_components, pgn_to_components,
+    proprietary_pgn_kind, request_and_match, GLOBAL_ADDRESS, NULL_ADDRESS,
+};
+pub use identification::{parse_component_id, parse_software_id, parse_vin};
+pub use monitor::{BusStats, DecodeSink, FreshnessMonitor};
+pub use transport::{ReassembledMessage, TpReassembler};
+pub use types::{
+    BitNumbering, ByteOrder, DecodeError, DecodedSpn, DecodedSpnC, DecodedSpnF32, DecodedSpnRef,
+    DecodedSpnStatus, FrameDecode, J1939Id, ParseError, PartialDecodedSpn, Pgn, PgnInfo,
+    ProprietaryPgn, Request2, SourcedDecodedSpn, SpnDataType, SpnDef, SpnStatus, SwitchState,
+    TempUnit, TimedDecodedSpn,
 };
-pub use types::{DecodedSpn, J1939Id, SpnDataType, SpnDef};
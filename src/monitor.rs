@@ -0,0 +1,376 @@
+//! Bus monitoring utilities.
+//!
+//! Stateful helpers that watch a stream of received frames, built on top of
+//! the PGN metadata in the database.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::database::{expected_interval_ms, get_spns_for_pgn};
+use crate::decoder::decode_frame_iter;
+use crate::frame::{extract_pgn, extract_source_address};
+use crate::types::TimedDecodedSpn;
+
+/// Detects PGNs that stopped arriving at their nominal broadcast rate.
+///
+/// Feed every received PGN with its timestamp via [`observe`](Self::observe),
+/// then periodically call [`overdue`](Self::overdue). A PGN is overdue once
+/// more than `max_missed` of its nominal intervals have elapsed since it was
+/// last seen. Only PGNs with a known broadcast rate that were seen at least
+/// once are tracked.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::monitor::FreshnessMonitor;
+///
+/// let mut monitor = FreshnessMonitor::new(3);
+/// monitor.observe(61444, 0); // EEC1, nominal 100ms
+///
+/// assert!(monitor.overdue(250).is_empty());
+/// assert_eq!(monitor.overdue(500), vec![(61444, 500)]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FreshnessMonitor {
+    /// PGN -> (last seen timestamp in ms, nominal interval in ms)
+    last_seen: HashMap<u32, (u64, u32)>,
+    /// Number of missed intervals tolerated before a PGN is reported
+    max_missed: u32,
+}
+
+impl FreshnessMonitor {
+    /// Create a monitor that reports PGNs overdue by more than `max_missed` intervals.
+    pub fn new(max_missed: u32) -> Self {
+        Self {
+            last_seen: HashMap::new(),
+            max_missed,
+        }
+    }
+
+    /// Record that `pgn` was received at `timestamp_ms`.
+    ///
+    /// PGNs without a known broadcast rate are ignored.
+    pub fn observe(&mut self, pgn: u32, timestamp_ms: u64) {
+        if let Some(interval) = expected_interval_ms(pgn) {
+            self.last_seen.insert(pgn, (timestamp_ms, interval));
+        }
+    }
+
+    /// Forget a PGN (e.g. after its ECU was intentionally shut down).
+    pub fn forget(&mut self, pgn: u32) {
+        self.last_seen.remove(&pgn);
+    }
+
+    /// List PGNs overdue at `now_ms`, as `(pgn, ms since last seen)` sorted by PGN.
+    pub fn overdue(&self, now_ms: u64) -> Vec<(u32, u64)> {
+        let mut overdue: Vec<(u32, u64)> = self
+            .last_seen
+            .iter()
+            .filter_map(|(&pgn, &(last, interval))| {
+                let elapsed = now_ms.saturating_sub(last);
+                let limit = interval as u64 * self.max_missed as u64;
+                (elapsed > limit).then_some((pgn, elapsed))
+            })
+            .collect();
+        overdue.sort_unstable_by_key(|(pgn, _)| *pgn);
+        overdue
+    }
+}
+
+/// Per-PGN and per-source-address frame counts for a capture.
+///
+/// Feed every frame via [`observe`](Self::observe), or pass a whole capture
+/// through [`Extend`]. PGNs not in the database are collected separately so a
+/// recording's coverage is visible at a glance.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::monitor::BusStats;
+///
+/// let mut stats = BusStats::new();
+/// stats.extend([
+///     (0x0CF00400, [0xFF; 8]), // EEC1 from 0x00
+///     (0x0CF00400, [0xFF; 8]),
+///     (0x18FF1017, [0x00; 8]), // Proprietary B from 0x17
+/// ]);
+///
+/// assert_eq!(stats.frame_count(), 3);
+/// assert_eq!(stats.pgn_histogram()[&61444], 2);
+/// assert_eq!(stats.sa_histogram()[&0x17], 1);
+/// assert!(stats.unknown_pgns().contains(&0xFF10));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BusStats {
+    /// PGN -> frame count
+    pgns: HashMap<u32, u64>,
+    /// Source address -> frame count
+    source_addresses: HashMap<u8, u64>,
+    /// PGNs seen that are not in the database
+    unknown: HashSet<u32>,
+    /// Total frames observed
+    frames: u64,
+    /// Total data bytes observed
+    bytes: u64,
+}
+
+impl BusStats {
+    /// Create an empty collector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one received frame.
+    pub fn observe(&mut self, can_id: u32, data: &[u8]) {
+        let pgn = extract_pgn(can_id);
+        *self.pgns.entry(pgn).or_insert(0) += 1;
+        *self
+            .source_addresses
+            .entry(extract_source_address(can_id))
+            .or_insert(0) += 1;
+        if get_spns_for_pgn(pgn).is_none() {
+            self.unknown.insert(pgn);
+        }
+        self.frames += 1;
+        self.bytes += data.len() as u64;
+    }
+
+    /// Frame count per PGN.
+    pub fn pgn_histogram(&self) -> &HashMap<u32, u64> {
+        &self.pgns
+    }
+
+    /// Frame count per source address.
+    pub fn sa_histogram(&self) -> &HashMap<u8, u64> {
+        &self.source_addresses
+    }
+
+    /// PGNs seen that have no SPN definitions in the database.
+    pub fn unknown_pgns(&self) -> &HashSet<u32> {
+        &self.unknown
+    }
+
+    /// Total number of frames observed.
+    pub fn frame_count(&self) -> u64 {
+        self.frames
+    }
+
+    /// Total number of data bytes observed.
+    pub fn byte_count(&self) -> u64 {
+        self.bytes
+    }
+}
+
+impl<D: AsRef<[u8]>> Extend<(u32, D)> for BusStats {
+    fn extend<I: IntoIterator<Item = (u32, D)>>(&mut self, frames: I) {
+        for (can_id, data) in frames {
+            self.observe(can_id, data.as_ref());
+        }
+    }
+}
+
+/// Bounded buffer of decoded values between a frame reader and a consumer.
+///
+/// [`push`](Self::push) decodes a frame and queues its values with the frame
+/// timestamp; [`drain`](Self::drain) hands them out oldest first. The buffer
+/// never grows past its capacity: a frame whose values don't fit is rejected
+/// as a whole and counted in [`rejected`](Self::rejected), so the producer
+/// can apply backpressure (e.g. wait on its channel) while
+/// [`remaining`](Self::remaining) is low. No async runtime is required; wrap
+/// it in whatever task or lock the application already uses.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::monitor::DecodeSink;
+///
+/// let mut sink = DecodeSink::with_capacity(16);
+/// let data = [0xFF, 0xFF, 0xFF, 0x20, 0x4E, 0xFF, 0xFF, 0xFF];
+/// assert!(sink.push(0x0CF00400, &data, 0.1));
+/// assert!(sink.push(0x0CF00400, &data, 0.2));
+///
+/// let values: Vec<_> = sink.drain().collect();
+/// assert_eq!(values.len(), 2);
+/// assert_eq!(values[1].timestamp, 0.2);
+/// assert!(sink.is_empty());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DecodeSink {
+    /// Queued values, oldest first
+    queue: VecDeque<TimedDecodedSpn>,
+    /// Maximum number of queued values
+    capacity: usize,
+    /// Frames rejected because their values did not fit
+    rejected: u64,
+}
+
+impl DecodeSink {
+    /// Create a sink holding at most `capacity` decoded values.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            queue: VecDeque::with_capacity(capacity),
+            capacity,
+            rejected: 0,
+        }
+    }
+
+    /// Decode a frame and queue its values tagged with `timestamp`.
+    ///
+    /// Returns `false` and leaves the sink unchanged if the values don't fit
+    /// in the remaining capacity. Frames without any decodable SPN are
+    /// accepted and queue nothing.
+    pub fn push(&mut self, can_id: u32, data: &[u8], timestamp: f64) -> bool {
+        let len = self.queue.len();
+        self.queue.extend(
+            decode_frame_iter(can_id, data)
+                .take(self.capacity - len + 1)
+                .map(|spn| TimedDecodedSpn { timestamp, spn }),
+        );
+        if self.queue.len() > self.capacity {
+            self.queue.truncate(len);
+            self.rejected += 1;
+            return false;
+        }
+        true
+    }
+
+    /// Remove and return all queued values, oldest first.
+    pub fn drain(&mut self) -> impl Iterator<Item = TimedDecodedSpn> + '_ {
+        self.queue.drain(..)
+    }
+
+    /// Number of queued values.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Whether no values are queued.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Maximum number of queued values.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Free space, in values.
+    pub fn remaining(&self) -> usize {
+        self.capacity - self.queue.len()
+    }
+
+    /// Number of frames rejected because the sink was full.
+    pub fn rejected(&self) -> u64 {
+        self.rejected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_freshness_monitor_overdue() {
+        let mut monitor = FreshnessMonitor::new(2);
+        monitor.observe(61444, 1000); // EEC1, 100ms
+        monitor.observe(65262, 1000); // ET1, 1000ms
+
+        // Exactly at the limit is still fresh
+        assert!(monitor.overdue(1200).is_empty());
+
+        // EEC1 is overdue after 2 missed intervals, ET1 is not
+        assert_eq!(monitor.overdue(1201), vec![(61444, 201)]);
+
+        // Both overdue later on
+        assert_eq!(monitor.overdue(3500), vec![(61444, 2500), (65262, 2500)]);
+    }
+
+    #[test]
+    fn test_freshness_monitor_observe_refreshes() {
+        let mut monitor = FreshnessMonitor::new(1);
+        monitor.observe(61444, 0);
+        assert_eq!(monitor.overdue(150).len(), 1);
+
+        monitor.observe(61444, 150);
+        assert!(monitor.overdue(200).is_empty());
+
+        monitor.forget(61444);
+        assert!(monitor.overdue(10_000).is_empty());
+    }
+
+    #[test]
+    fn test_freshness_monitor_ignores_unknown_pgns() {
+        let mut monitor = FreshnessMonitor::new(1);
+        monitor.observe(0xFF10, 0); // Proprietary B - no known rate
+        assert!(monitor.overdue(u64::MAX).is_empty());
+    }
+
+    #[test]
+    fn test_bus_stats_counts() {
+        let mut stats = BusStats::new();
+        stats.observe(0x0CF00400, &[0xFF; 8]); // EEC1 from 0x00
+        stats.observe(0x18FEEE00, &[0xFF; 8]); // ET1 from 0x00
+        stats.observe(0x0CF00403, &[0xFF; 8]); // EEC1 from 0x03
+        stats.observe(0x18EA0021, &[0xEE, 0xFE, 0x00]); // Request from 0x21
+
+        assert_eq!(stats.frame_count(), 4);
+        assert_eq!(stats.byte_count(), 27);
+        assert_eq!(stats.pgn_histogram()[&61444], 2);
+        assert_eq!(stats.pgn_histogram()[&65262], 1);
+        assert_eq!(stats.sa_histogram()[&0x00], 2);
+        assert_eq!(stats.sa_histogram()[&0x03], 1);
+        assert_eq!(stats.sa_histogram()[&0x21], 1);
+
+        // Request PGN has no SPNs; destination is not part of the PGN
+        assert_eq!(stats.pgn_histogram()[&59904], 1);
+        assert_eq!(stats.unknown_pgns().len(), 1);
+        assert!(stats.unknown_pgns().contains(&59904));
+    }
+
+    #[test]
+    fn test_bus_stats_extend() {
+        let capture = vec![
+            (0x18FF1017, vec![0x00; 8]),
+            (0x18FF1017, vec![0x00; 4]),
+            (0x18FF2017, vec![0x00; 8]),
+        ];
+        let mut stats = BusStats::default();
+        stats.extend(capture);
+
+        assert_eq!(stats.frame_count(), 3);
+        assert_eq!(stats.byte_count(), 20);
+        assert_eq!(stats.sa_histogram().len(), 1);
+        let mut unknown: Vec<_> = stats.unknown_pgns().iter().copied().collect();
+        unknown.sort_unstable();
+        assert_eq!(unknown, vec![0xFF10, 0xFF20]);
+    }
+
+    #[test]
+    fn test_decode_sink_rejects_frames_that_do_not_fit() {
+        // ET1 frame with coolant and oil temperature: 2 values
+        let et1 = [130, 0xFF, 0x00, 0x2B, 0xFF, 0xFF, 0xFF, 0xFF];
+        let mut sink = DecodeSink::with_capacity(3);
+
+        assert!(sink.push(0x18FEEE00, &et1, 1.0));
+        assert_eq!(sink.remaining(), 1);
+        assert!(!sink.push(0x18FEEE00, &et1, 2.0));
+        assert_eq!(sink.len(), 2);
+        assert_eq!(sink.rejected(), 1);
+
+        // Unknown PGN: nothing to queue, always accepted
+        assert!(sink.push(0x18FF0000, &et1, 3.0));
+
+        let drained: Vec<_> = sink.drain().collect();
+        assert!(drained.iter().all(|v| v.timestamp == 1.0));
+        assert_eq!(drained[0].spn.spn, 110);
+        assert_eq!(sink.remaining(), sink.capacity());
+        assert!(sink.push(0x18FEEE00, &et1, 4.0));
+    }
+
+    #[test]
+    fn test_decode_sink_zero_capacity() {
+        let mut sink = DecodeSink::default();
+        assert!(!sink.push(0x0CF00400, &[0xFF, 0xFF, 0xFF, 0x20, 0x4E], 0.0));
+        assert!(sink.is_empty());
+        assert_eq!(sink.rejected(), 1);
+    }
+}
@@ -0,0 +1,14 @@
+//! Commonly used functions and types.
+//!
+//! ```
+//! use voltage_j1939::prelude::*;
+//!
+//! let id: J1939Id = parse_can_id(0x0CF00400);
+//! let decoded: Vec<DecodedSpn> = decode_frame(build_can_id(&id), &[0, 0, 0, 0x20, 0x4E, 0, 0, 0]);
+//! assert!(decoded.iter().any(|spn| spn.name == "engine_speed"));
+//! ```
+
+pub use crate::database::get_spn_def;
+pub use crate::decoder::{decode_frame, decode_frame_iter, decode_spn};
+pub use crate::frame::{build_can_id, parse_can_id};
+pub use crate::types::{DecodedSpn, J1939Id, SpnDataType, SpnDef};
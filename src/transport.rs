@@ -0,0 +1,617 @@
+//! J1939-21 transport protocol reassembly.
+//!
+//! Messages longer than 8 bytes (DM1 with several DTCs, VI, SOFT, ...) are
+//! split into 7-byte TP.DT packets announced by a TP.CM frame, either as a
+//! broadcast (BAM) or over a peer-to-peer connection (RTS/CTS). Messages over
+//! 1785 bytes use the Extended Transport Protocol (ETP), which is
+//! peer-to-peer only and addresses packets through a data packet offset
+//! (DPO) window. [`TpReassembler`] listens to both and hands back the
+//! complete payload.
+
+use crate::frame::{parse_can_id, ETP_CM_PGN, ETP_DT_PGN, TP_CM_PGN, TP_DT_PGN};
+
+/// Largest message the transport protocol can carry (255 packets of 7 bytes).
+pub const TP_MAX_MESSAGE_SIZE: usize = 1785;
+
+/// Largest message the extended transport protocol can carry
+/// (2^24 - 1 packets of 7 bytes).
+pub const ETP_MAX_MESSAGE_SIZE: usize = 117_440_505;
+
+/// Default limit on concurrent sessions in a [`TpReassembler`].
+pub const DEFAULT_MAX_SESSIONS: usize = 32;
+
+/// Default limit on ETP message size in a [`TpReassembler`] (1 MiB).
+pub const DEFAULT_MAX_ETP_MESSAGE_SIZE: usize = 1 << 20;
+
+/// Payload bytes per TP.DT / ETP.DT packet.
+const TP_PACKET_PAYLOAD: usize = 7;
+
+/// TP.CM control byte: Request To Send (connection mode).
+const CM_RTS: u8 = 16;
+
+/// TP.CM control byte: Clear To Send (connection mode).
+const CM_CTS: u8 = 17;
+
+/// TP.CM control byte: Broadcast Announce Message.
+const CM_BAM: u8 = 32;
+
+/// ETP.CM control byte: Request To Send.
+const ETP_CM_RTS: u8 = 20;
+
+/// ETP.CM control byte: Data Packet Offset.
+const ETP_CM_DPO: u8 = 22;
+
+/// TP.CM / ETP.CM control byte: Connection Abort.
+const CM_ABORT: u8 = 255;
+
+/// A message reassembled from transport protocol packets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReassembledMessage {
+    /// PGN of the transported message (from the TP.CM announcement).
+    pub pgn: u32,
+    /// Source address of the sender.
+    pub source_address: u8,
+    /// Destination address (global address for BAM).
+    pub destination_address: u8,
+    /// Payload, exactly the announced size.
+    pub data: Vec<u8>,
+}
+
+/// One message being received.
+#[derive(Debug, Clone)]
+struct Session {
+    source_address: u8,
+    destination_address: u8,
+    pgn: u32,
+    /// Started by ETP.CM, fed by ETP.DT
+    extended: bool,
+    /// Announced message size in bytes
+    size: usize,
+    /// Packets announced for the current ETP window (DPO count, unused for TP)
+    window: u8,
+    /// Sequence number expected in the next data packet, within the window
+    next_sequence: u8,
+    data: Vec<u8>,
+}
+
+impl Session {
+    fn is_between(&self, source_address: u8, destination_address: u8) -> bool {
+        self.source_address == source_address && self.destination_address == destination_address
+    }
+}
+
+/// Reassembles transport protocol messages from received frames.
+///
+/// Feed every received frame to [`push`](Self::push); frames other than
+/// TP.CM / TP.DT / ETP.CM / ETP.DT are ignored. One session is kept per
+/// (source, destination) pair, and a new announcement replaces the session
+/// in progress.
+///
+/// Memory is bounded for untrusted input: announcements larger than
+/// `max_message_size` (TP) or `max_etp_message_size` (ETP), or with an
+/// inconsistent packet count, are rejected before anything is allocated,
+/// and at most `max_sessions` sessions are kept, evicting the oldest.
+/// Out-of-sequence packets drop the session. Timeouts are left to the
+/// caller; stale sessions go away through eviction or [`clear`](Self::clear).
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::diagnostics::parse_dm1;
+/// use voltage_j1939::transport::TpReassembler;
+///
+/// let mut tp = TpReassembler::new();
+/// // BAM: 10 bytes of DM1 (0xFECA) in 2 packets from 0x00
+/// assert!(tp.push(0x1CECFF00, &[0x20, 0x0A, 0x00, 0x02, 0xFF, 0xCA, 0xFE, 0x00]).is_none());
+/// assert!(tp.push(0x1CEBFF00, &[0x01, 0x04, 0xFF, 0x64, 0x00, 0x01, 0x03, 0x6E]).is_none());
+/// let msg = tp.push(0x1CEBFF00, &[0x02, 0x00, 0x00, 0x01, 0xFF, 0xFF, 0xFF, 0xFF]).unwrap();
+///
+/// assert_eq!(msg.pgn, 0xFECA);
+/// assert_eq!(msg.data.len(), 10);
+/// assert_eq!(parse_dm1(&msg.data).unwrap().dtcs.len(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TpReassembler {
+    /// Largest announced TP message size accepted (capped at [`TP_MAX_MESSAGE_SIZE`]).
+    pub max_message_size: usize,
+    /// Largest announced ETP message size accepted (capped at [`ETP_MAX_MESSAGE_SIZE`]).
+    pub max_etp_message_size: usize,
+    /// Maximum number of concurrent sessions; the oldest is evicted beyond it.
+    pub max_sessions: usize,
+    /// Sessions in progress, oldest first
+    sessions: Vec<Session>,
+}
+
+impl Default for TpReassembler {
+    fn default() -> Self {
+        Self {
+            max_message_size: TP_MAX_MESSAGE_SIZE,
+            max_etp_message_size: DEFAULT_MAX_ETP_MESSAGE_SIZE,
+            max_sessions: DEFAULT_MAX_SESSIONS,
+            sessions: Vec::new(),
+        }
+    }
+}
+
+impl TpReassembler {
+    /// Create a reassembler with the default limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Process one received frame.
+    ///
+    /// Returns the complete message when `can_id`/`data` is the last data
+    /// packet of a session, `None` otherwise.
+    pub fn push(&mut self, can_id: u32, data: &[u8]) -> Option<ReassembledMessage> {
+        let id = parse_can_id(can_id);
+        let (sa, da) = (id.source_address, id.destination_address);
+        match id.pgn {
+            TP_CM_PGN => {
+                self.connection_management(sa, da, data);
+                None
+            }
+            ETP_CM_PGN => {
+                self.extended_connection_management(sa, da, data);
+                None
+            }
+            TP_DT_PGN => self.data_transfer(sa, da, data, false),
+            ETP_DT_PGN => self.data_transfer(sa, da, data, true),
+            _ => None,
+        }
+    }
+
+    /// Number of sessions in progress.
+    pub fn active_sessions(&self) -> usize {
+        self.sessions.len()
+    }
+
+    /// Drop all sessions in progress.
+    pub fn clear(&mut self) {
+        self.sessions.clear();
+    }
+
+    fn connection_management(&mut self, sa: u8, da: u8, data: &[u8]) {
+        match data.first() {
+            Some(&CM_BAM | &CM_RTS) => {
+                let &[_, size_lo, size_hi, packets, _, ..] = data else {
+                    return self.remove(sa, da);
+                };
+                let size = u16::from_le_bytes([size_lo, size_hi]) as usize;
+                let limit = self.max_message_size.min(TP_MAX_MESSAGE_SIZE);
+                let valid = size <= limit && packets as usize == size.div_ceil(TP_PACKET_PAYLOAD);
+                self.open(sa, da, data, size, valid, false);
+            }
+            // Sent by the receiver: rewind the sender's session if packets
+            // are requested again
+            Some(&CM_CTS) => {
+                if let (Some(&count), Some(&next)) = (data.get(1), data.get(2)) {
+                    self.rewind(da, sa, count, next);
+                }
+            }
+            Some(&CM_ABORT) => self.abort(sa, da),
+            _ => {}
+        }
+    }
+
+    fn extended_connection_management(&mut self, sa: u8, da: u8, data: &[u8]) {
+        match data.first() {
+            Some(&ETP_CM_RTS) => {
+                let &[_, s0, s1, s2, s3, ..] = data else {
+                    return self.remove(sa, da);
+                };
+                let size = u32::from_le_bytes([s0, s1, s2, s3]) as usize;
+                let limit = self.max_etp_message_size.min(ETP_MAX_MESSAGE_SIZE);
+                self.open(sa, da, data, size, size <= limit, true);
+            }
+            Some(&ETP_CM_DPO) => {
+                if let &[_, count, o0, o1, o2, ..] = data {
+                    let offset = u32::from_le_bytes([o0, o1, o2, 0]) as usize;
+                    self.set_offset(sa, da, count, offset);
+                }
+            }
+            Some(&CM_ABORT) => self.abort(sa, da),
+            // CTS / EOMA: the DPO that follows a CTS carries the window
+            _ => {}
+        }
+    }
+
+    /// Start a session announced by `data`, if `valid`.
+    fn open(&mut self, sa: u8, da: u8, data: &[u8], size: usize, valid: bool, extended: bool) {
+        self.remove(sa, da);
+        let Some(&[pgn_lo, pgn_mid, pgn_hi]) = data.get(5..8) else {
+            return;
+        };
+        if size == 0 || !valid || self.max_sessions == 0 {
+            return;
+        }
+
+        if self.sessions.len() >= self.max_sessions {
+            let excess = self.sessions.len() + 1 - self.max_sessions;
+            self.sessions.drain(..excess);
+        }
+        self.sessions.push(Session {
+            source_address: sa,
+            destination_address: da,
+            pgn: u32::from_le_bytes([pgn_lo, pgn_mid, pgn_hi, 0]),
+            extended,
+            size,
+            window: 0,
+            next_sequence: 1,
+            data: Vec::with_capacity(size),
+        });
+    }
+
+    fn remove(&mut self, sa: u8, da: u8) {
+        self.sessions.retain(|s| !s.is_between(sa, da));
+    }
+
+    /// Either side may abort.
+    fn abort(&mut self, sa: u8, da: u8) {
+        self.remove(sa, da);
+        self.remove(da, sa);
+    }
+
+    fn rewind(&mut self, sa: u8, da: u8, count: u8, next: u8) {
+        let Some(session) = self
+            .sessions
+            .iter_mut()
+            .find(|s| !s.extended && s.is_between(sa, da))
+        else {
+            return;
+        };
+        // A CTS for zero packets is a hold, not a rewind
+        if count > 0 && next >= 1 && next < session.next_sequence {
+            session
+                .data
+                .truncate((next as usize - 1) * TP_PACKET_PAYLOAD);
+            session.next_sequence = next;
+        }
+    }
+
+    /// Start a new ETP window at packet `offset` (sent by the sender).
+    fn set_offset(&mut self, sa: u8, da: u8, count: u8, offset: usize) {
+        let Some(index) = self
+            .sessions
+            .iter()
+            .position(|s| s.extended && s.is_between(sa, da))
+        else {
+            return;
+        };
+        let session = &mut self.sessions[index];
+        let received = session.data.len().div_ceil(TP_PACKET_PAYLOAD);
+        // Windows may repeat packets (retransmission) but never skip any
+        if count == 0 || offset > received {
+            self.sessions.remove(index);
+            return;
+        }
+        session.data.truncate(offset * TP_PACKET_PAYLOAD);
+        session.window = count;
+        session.next_sequence = 1;
+    }
+
+    fn data_transfer(
+        &mut self,
+        sa: u8,
+        da: u8,
+        data: &[u8],
+        extended: bool,
+    ) -> Option<ReassembledMessage> {
+        let index = self
+            .sessions
+            .iter()
+            .position(|s| s.extended == extended && s.is_between(sa, da))?;
+        let (&sequence, payload) = data.split_first()?;
+        let session = &mut self.sessions[index];
+
+        // ETP packets must fall inside the window announced by the last DPO
+        let in_window = !extended || (1..=session.window).contains(&sequence);
+        let take = TP_PACKET_PAYLOAD.min(session.size - session.data.len());
+        if !in_window || sequence != session.next_sequence || payload.len() < take {
+            self.sessions.remove(index);
+            return None;
+        }
+        session.data.extend_from_slice(&payload[..take]);
+        session.next_sequence = session.next_sequence.wrapping_add(1);
+        if session.data.len() < session.size {
+            return None;
+        }
+
+        let session = self.sessions.remove(index);
+        Some(ReassembledMessage {
+            pgn: session.pgn,
+            source_address: session.source_address,
+            destination_address: session.destination_address,
+            data: session.data,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// TP.CM BAM announcing `size` bytes of `pgn`.
+    fn bam(size: u16, packets: u8, pgn: u32) -> [u8; 8] {
+        let [s0, s1] = size.to_le_bytes();
+        let [p0, p1, p2, _] = pgn.to_le_bytes();
+        [CM_BAM, s0, s1, packets, 0xFF, p0, p1, p2]
+    }
+
+    /// Feed a whole message as BAM from `sa`, returning the last push result.
+    fn send_bam(tp: &mut TpReassembler, sa: u8, payload: &[u8]) -> Option<ReassembledMessage> {
+        let packets = payload.len().div_ceil(TP_PACKET_PAYLOAD) as u8;
+        tp.push(
+            0x1CECFF00 | sa as u32,
+            &bam(payload.len() as u16, packets, 0xFECA),
+        );
+        let mut result = None;
+        for (i, chunk) in payload.chunks(TP_PACKET_PAYLOAD).enumerate() {
+            let mut frame = [0xFF; 8];
+            frame[0] = i as u8 + 1;
+            frame[1..1 + chunk.len()].copy_from_slice(chunk);
+            result = tp.push(0x1CEBFF00 | sa as u32, &frame);
+        }
+        result
+    }
+
+    // ========================================================================
+    // Reassembly tests
+    // ========================================================================
+
+    #[test]
+    fn test_bam_reassembly() {
+        let payload: Vec<u8> = (0..20).collect();
+        let mut tp = TpReassembler::new();
+        let msg = send_bam(&mut tp, 0x3D, &payload).unwrap();
+
+        assert_eq!(msg.pgn, 0xFECA);
+        assert_eq!(msg.source_address, 0x3D);
+        assert_eq!(msg.destination_address, 0xFF);
+        assert_eq!(msg.data, payload);
+        assert_eq!(tp.active_sessions(), 0);
+    }
+
+    #[test]
+    fn test_max_size_message() {
+        let payload = vec![0xA5; TP_MAX_MESSAGE_SIZE];
+        let mut tp = TpReassembler::new();
+        assert_eq!(send_bam(&mut tp, 0x00, &payload).unwrap().data, payload);
+    }
+
+    #[test]
+    fn test_interleaved_senders() {
+        let mut tp = TpReassembler::new();
+        tp.push(0x1CECFF00, &bam(9, 2, 0xFECA));
+        tp.push(0x1CECFF17, &bam(9, 2, 0xFEEB));
+        assert_eq!(tp.active_sessions(), 2);
+
+        tp.push(0x1CEBFF00, &[1, 0, 0, 0, 0, 0, 0, 0]);
+        tp.push(0x1CEBFF17, &[1, 1, 1, 1, 1, 1, 1, 1]);
+        let a = tp.push(0x1CEBFF17, &[2, 1, 1, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+        let b = tp.push(0x1CEBFF00, &[2, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+        assert_eq!(a.unwrap().data, vec![1; 9]);
+        assert_eq!(b.unwrap().data, vec![0; 9]);
+    }
+
+    #[test]
+    fn test_connection_mode_with_retransmission() {
+        let mut tp = TpReassembler::new();
+        // RTS from 0x00 to 0x21: 14 bytes of SOFT (0xFEDA) in 2 packets
+        tp.push(0x1CEC2100, &[CM_RTS, 14, 0, 2, 0xFF, 0xDA, 0xFE, 0x00]);
+        tp.push(0x1CEB2100, &[1, 1, 1, 1, 1, 1, 1, 1]);
+        // Receiver asks for packet 1 again
+        tp.push(0x1CEC0021, &[CM_CTS, 2, 1, 0xFF, 0xFF, 0xDA, 0xFE, 0x00]);
+        tp.push(0x1CEB2100, &[1, 1, 1, 1, 1, 1, 1, 1]);
+        let msg = tp.push(0x1CEB2100, &[2, 2, 2, 2, 2, 2, 2, 2]).unwrap();
+
+        assert_eq!(msg.destination_address, 0x21);
+        assert_eq!(msg.data, [[1u8; 7], [2u8; 7]].concat());
+    }
+
+    // ========================================================================
+    // Extended transport protocol tests
+    // ========================================================================
+
+    /// ETP.CM RTS from 0x00 to 0x21 announcing `size` bytes of `pgn`.
+    fn etp_rts(tp: &mut TpReassembler, size: u32, pgn: u32) {
+        let [s0, s1, s2, s3] = size.to_le_bytes();
+        let [p0, p1, p2, _] = pgn.to_le_bytes();
+        tp.push(0x1CC82100, &[ETP_CM_RTS, s0, s1, s2, s3, p0, p1, p2]);
+    }
+
+    /// ETP.CM DPO from 0x00 to 0x21 opening a window of `count` packets at `offset`.
+    fn etp_dpo(tp: &mut TpReassembler, count: u8, offset: u32) {
+        let [o0, o1, o2, _] = offset.to_le_bytes();
+        tp.push(
+            0x1CC82100,
+            &[ETP_CM_DPO, count, o0, o1, o2, 0x00, 0xDA, 0xFE],
+        );
+    }
+
+    /// Send packets `first..first + count` of `payload` as one ETP window.
+    fn etp_window(
+        tp: &mut TpReassembler,
+        payload: &[u8],
+        first: usize,
+        count: usize,
+    ) -> Option<ReassembledMessage> {
+        etp_dpo(tp, count as u8, first as u32);
+        let mut result = None;
+        let packets = payload.chunks(TP_PACKET_PAYLOAD).skip(first).take(count);
+        for (i, chunk) in packets.enumerate() {
+            let mut frame = [0xFF; 8];
+            frame[0] = i as u8 + 1;
+            frame[1..1 + chunk.len()].copy_from_slice(chunk);
+            result = tp.push(0x1CC72100, &frame);
+        }
+        result
+    }
+
+    #[test]
+    fn test_etp_reassembly_across_windows() {
+        let payload: Vec<u8> = (0..4000u32).map(|i| i as u8).collect();
+        let packets = payload.len().div_ceil(TP_PACKET_PAYLOAD);
+        let mut tp = TpReassembler::new();
+        etp_rts(&mut tp, payload.len() as u32, 0xFEDA);
+
+        let mut msg = None;
+        for first in (0..packets).step_by(255) {
+            assert!(msg.is_none());
+            msg = etp_window(&mut tp, &payload, first, 255);
+        }
+        let msg = msg.unwrap();
+        assert_eq!(msg.pgn, 0xFEDA);
+        assert_eq!(msg.source_address, 0x00);
+        assert_eq!(msg.destination_address, 0x21);
+        assert_eq!(msg.data, payload);
+        assert_eq!(tp.active_sessions(), 0);
+    }
+
+    #[test]
+    fn test_etp_window_retransmission() {
+        let payload: Vec<u8> = (0..2000u32).map(|i| (i % 251) as u8).collect();
+        let mut tp = TpReassembler::new();
+        etp_rts(&mut tp, payload.len() as u32, 0xFEDA);
+
+        assert!(etp_window(&mut tp, &payload, 0, 200).is_none());
+        // Receiver lost packets 150..200 and the sender repeats them
+        assert!(etp_window(&mut tp, &payload, 150, 100).is_none());
+        let msg = etp_window(&mut tp, &payload, 250, 255).unwrap();
+        assert_eq!(msg.data, payload);
+
+        // A sender overrunning its window drops the session
+        etp_rts(&mut tp, payload.len() as u32, 0xFEDA);
+        assert!(etp_window(&mut tp, &payload, 0, 2).is_none());
+        assert_eq!(tp.active_sessions(), 1);
+        tp.push(0x1CC72100, &[3, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(tp.active_sessions(), 0);
+
+        // So does data before any DPO
+        etp_rts(&mut tp, payload.len() as u32, 0xFEDA);
+        tp.push(0x1CC72100, &[1, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(tp.active_sessions(), 0);
+
+        // A window that skips packets drops the session
+        etp_rts(&mut tp, payload.len() as u32, 0xFEDA);
+        etp_window(&mut tp, &payload, 0, 10);
+        etp_dpo(&mut tp, 10, 20);
+        assert_eq!(tp.active_sessions(), 0);
+    }
+
+    #[test]
+    fn test_etp_rejects_oversized_announcements() {
+        let mut tp = TpReassembler {
+            max_etp_message_size: 10_000,
+            ..TpReassembler::default()
+        };
+        etp_rts(&mut tp, 10_001, 0xFEDA);
+        assert_eq!(tp.active_sessions(), 0);
+        etp_rts(&mut tp, 0, 0xFEDA);
+        assert_eq!(tp.active_sessions(), 0);
+        etp_rts(&mut tp, 10_000, 0xFEDA);
+        assert_eq!(tp.active_sessions(), 1);
+
+        // The protocol limit applies even when the configured limit is higher
+        tp.max_etp_message_size = usize::MAX;
+        etp_rts(&mut tp, u32::MAX, 0xFEDA);
+        assert_eq!(tp.active_sessions(), 0);
+    }
+
+    #[test]
+    fn test_etp_and_tp_packets_do_not_mix() {
+        let mut tp = TpReassembler::new();
+        etp_rts(&mut tp, 2000, 0xFEDA);
+        etp_dpo(&mut tp, 255, 0);
+        // TP.DT between the same pair does not feed the ETP session
+        assert!(tp.push(0x1CEB2100, &[1; 8]).is_none());
+        assert_eq!(tp.active_sessions(), 1);
+        tp.push(0x1CC72100, &[1; 8]);
+
+        // Abort from the receiver ends the session
+        tp.push(
+            0x1CC80021,
+            &[CM_ABORT, 0xFF, 0xFF, 0xFF, 0xFF, 0xDA, 0xFE, 0x00],
+        );
+        assert_eq!(tp.active_sessions(), 0);
+
+        // ETP.DT never feeds a TP session
+        tp.push(0x1CEC2100, &[CM_RTS, 9, 0, 2, 0xFF, 0xDA, 0xFE, 0x00]);
+        tp.push(0x1CC72100, &[1; 8]);
+        tp.push(0x1CEB2100, &[1; 8]);
+        assert!(tp.push(0x1CEB2100, &[2; 8]).is_some());
+    }
+
+    // ========================================================================
+    // Robustness tests
+    // ========================================================================
+
+    #[test]
+    fn test_rejects_bad_announcements() {
+        let mut tp = TpReassembler::new();
+        tp.push(0x1CECFF00, &bam(1786, 255, 0xFECA)); // Beyond protocol limit
+        tp.push(0x1CECFF01, &bam(20, 2, 0xFECA)); // Packet count mismatch
+        tp.push(0x1CECFF02, &bam(0, 0, 0xFECA));
+        tp.push(0x1CECFF03, &[CM_BAM, 20, 0]); // Truncated
+        assert_eq!(tp.active_sessions(), 0);
+
+        tp.max_message_size = 100;
+        tp.push(0x1CECFF00, &bam(101, 15, 0xFECA));
+        assert_eq!(tp.active_sessions(), 0);
+        tp.push(0x1CECFF00, &bam(100, 15, 0xFECA));
+        assert_eq!(tp.active_sessions(), 1);
+    }
+
+    #[test]
+    fn test_evicts_oldest_session() {
+        let mut tp = TpReassembler {
+            max_sessions: 2,
+            ..TpReassembler::default()
+        };
+        for sa in 0..3u32 {
+            tp.push(0x1CECFF00 | sa, &bam(9, 2, 0xFECA));
+        }
+        assert_eq!(tp.active_sessions(), 2);
+
+        // Sender 0x00 was evicted, 0x02 is still alive
+        assert!(tp.push(0x1CEBFF00, &[1; 8]).is_none());
+        tp.push(0x1CEBFF02, &[1; 8]);
+        assert!(tp.push(0x1CEBFF02, &[2; 8]).is_some());
+
+        tp.max_sessions = 0;
+        tp.push(0x1CECFF05, &bam(9, 2, 0xFECA));
+        assert_eq!(tp.active_sessions(), 1);
+    }
+
+    #[test]
+    fn test_out_of_sequence_and_abort_drop_session() {
+        let mut tp = TpReassembler::new();
+        tp.push(0x1CECFF00, &bam(20, 3, 0xFECA));
+        tp.push(0x1CEBFF00, &[1; 8]);
+        assert!(tp.push(0x1CEBFF00, &[3; 8]).is_none()); // Skipped packet 2
+        assert_eq!(tp.active_sessions(), 0);
+        assert!(tp.push(0x1CEBFF00, &[2; 8]).is_none());
+
+        tp.push(0x1CEC2100, &[CM_RTS, 9, 0, 2, 0xFF, 0xDA, 0xFE, 0x00]);
+        tp.push(
+            0x1CEC0021,
+            &[CM_ABORT, 0xFF, 0xFF, 0xFF, 0xFF, 0xDA, 0xFE, 0x00],
+        );
+        assert_eq!(tp.active_sessions(), 0);
+
+        // Short packet in the middle of a message
+        tp.push(0x1CECFF00, &bam(20, 3, 0xFECA));
+        tp.push(0x1CEBFF00, &[1, 0, 0]);
+        assert_eq!(tp.active_sessions(), 0);
+    }
+
+    #[test]
+    fn test_ignores_other_frames() {
+        let mut tp = TpReassembler::new();
+        assert!(tp.push(0x0CF00400, &[0xFF; 8]).is_none());
+        assert!(tp.push(0x1CEBFF00, &[1; 8]).is_none()); // No announcement
+        tp.push(0x1CECFF00, &bam(9, 2, 0xFECA));
+        tp.clear();
+        assert_eq!(tp.active_sessions(), 0);
+    }
+}
@@ -12,6 +12,8 @@
 //! - `J1939Id` fits in 8 bytes
 //! - `DecodedSpn` fits in a cache line (64 bytes)
 
+use crate::frame::{extract_pgn, GLOBAL_ADDRESS};
+
 /// Data type for SPN values.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
@@ -33,6 +35,16 @@ pub enum SpnDataType {
 impl SpnDataType {
     /// Returns the number of bytes required to store this data type.
     /// Hot path: always inlined, computed at compile time when possible.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use voltage_j1939::SpnDataType;
+    ///
+    /// assert_eq!(SpnDataType::Uint8.byte_size(), 1);
+    /// assert_eq!(SpnDataType::Int16.byte_size(), 2);
+    /// assert_eq!(SpnDataType::Uint32.byte_size(), 4);
+    /// ```
     #[inline(always)]
     pub const fn byte_size(self) -> usize {
         // Use a match table that the compiler can optimize to a simple lookup
@@ -45,6 +57,15 @@ impl SpnDataType {
 
     /// Returns true if this is a signed type.
     /// Const fn for compile-time evaluation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use voltage_j1939::SpnDataType;
+    ///
+    /// assert!(SpnDataType::Int8.is_signed());
+    /// assert!(!SpnDataType::Uint16.is_signed());
+    /// ```
     #[inline(always)]
     pub const fn is_signed(self) -> bool {
         // Compiler optimizes this to a simple bit check on repr(u8) value
@@ -58,6 +79,76 @@ impl SpnDataType {
     }
 }
 
+/// Byte order of a multi-byte SPN value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum ByteOrder {
+    /// Least significant byte first, as J1939 specifies.
+    #[default]
+    LittleEndian = 0,
+    /// Most significant byte first (nonconforming devices).
+    BigEndian = 1,
+}
+
+/// Bit numbering convention for a signal's start bit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum BitNumbering {
+    /// J1939 / DBC Intel (`@1`): the start bit is the least significant bit
+    /// of the signal, numbered LSB first from byte 0 (bit 8 is the LSB of
+    /// byte 1), and bytes are little-endian.
+    #[default]
+    J1939Lsb = 0,
+    /// DBC Motorola (`@0`), as written by CANdb++: the start bit is the most
+    /// significant bit of the signal in sawtooth numbering (bit 7 is the MSB
+    /// of byte 0, bit 15 the MSB of byte 1), and bytes are big-endian.
+    DbcSawtooth = 1,
+}
+
+/// Output unit for temperature SPNs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum TempUnit {
+    /// Degrees Celsius, as the database stores temperatures.
+    #[default]
+    Celsius = 0,
+    /// Kelvin.
+    Kelvin = 1,
+    /// Degrees Fahrenheit.
+    Fahrenheit = 2,
+}
+
+impl TempUnit {
+    /// Convert a temperature in degrees Celsius to this unit.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use voltage_j1939::types::TempUnit;
+    ///
+    /// assert_eq!(TempUnit::Kelvin.convert_celsius(90.0), 363.15);
+    /// assert_eq!(TempUnit::Fahrenheit.convert_celsius(100.0), 212.0);
+    /// ```
+    #[inline]
+    pub fn convert_celsius(self, celsius: f64) -> f64 {
+        match self {
+            TempUnit::Celsius => celsius,
+            TempUnit::Kelvin => celsius + 273.15,
+            TempUnit::Fahrenheit => celsius.mul_add(1.8, 32.0),
+        }
+    }
+
+    /// Unit symbol, in the style of the database units ("C", "K", "F").
+    #[inline]
+    pub const fn symbol(self) -> &'static str {
+        match self {
+            TempUnit::Celsius => "C",
+            TempUnit::Kelvin => "K",
+            TempUnit::Fahrenheit => "F",
+        }
+    }
+}
+
 /// SPN (Suspect Parameter Number) definition.
 ///
 /// Contains all metadata needed to decode a specific parameter from a J1939 PGN.
@@ -84,6 +175,220 @@ pub struct SpnDef {
     pub bit_length: u8,
     /// Data type of the raw value.
     pub data_type: SpnDataType,
+    /// Byte holding the multiplexer selector, for SPNs that share bytes
+    /// with other SPNs depending on a mode/index byte (`None` if not muxed).
+    pub mux_byte: Option<u8>,
+    /// Selector value for which this SPN is present (`None` if not muxed).
+    /// A byte, like the `mux_byte` it is compared with.
+    pub mux_value: Option<u8>,
+    /// Instance (bank, side, cylinder, ...) for parameters repeated within
+    /// a PGN, counted from 1 (`None` if the PGN carries a single instance).
+    pub instance: Option<u8>,
+    /// Report every raw value, including the top two codes normally reserved
+    /// for error and "not available". Set on discrete SPNs whose four 2-bit
+    /// states are all meaningful to the caller.
+    pub reports_all_states: bool,
+}
+
+impl SpnDef {
+    /// Base for `SpnDef` literals: an unnamed, unscaled 8-bit field that is
+    /// neither multiplexed nor instanced.
+    ///
+    /// Fill in the fields that matter and take the rest from here:
+    ///
+    /// ```
+    /// use voltage_j1939::types::{SpnDataType, SpnDef};
+    ///
+    /// const BOOST: SpnDef = SpnDef {
+    ///     spn: 102,
+    ///     name: "boost_pressure",
+    ///     pgn: 65270,
+    ///     start_byte: 1,
+    ///     scale: 2.0,
+    ///     unit: "kPa",
+    ///     ..SpnDef::DEFAULT
+    /// };
+    /// assert_eq!(BOOST.data_type, SpnDataType::Uint8);
+    /// assert!(BOOST.mux_byte.is_none() && BOOST.instance.is_none());
+    /// ```
+    pub const DEFAULT: SpnDef = SpnDef {
+        scale: 1.0,
+        offset: 0.0,
+        spn: 0,
+        pgn: 0,
+        name: "",
+        unit: "",
+        start_byte: 0,
+        start_bit: 0,
+        bit_length: 8,
+        data_type: SpnDataType::Uint8,
+        mux_byte: None,
+        mux_value: None,
+        instance: None,
+        reports_all_states: false,
+    };
+
+    /// Number of significant bits, clamped to the width of the data type.
+    #[inline(always)]
+    pub const fn effective_bits(&self) -> u8 {
+        let max = self.data_type.bit_size();
+        if self.bit_length < max {
+            self.bit_length
+        } else {
+            max
+        }
+    }
+
+    /// Number of bytes the field spans.
+    ///
+    /// At least the size of the data type; larger when a bit field's
+    /// `start_bit + bit_length` crosses a byte boundary. `bit_length` is
+    /// clamped to the data type width, like [`effective_bits`](Self::effective_bits).
+    #[inline(always)]
+    pub const fn byte_width(&self) -> usize {
+        let type_bytes = self.data_type.byte_size();
+        let field_bytes = (self.start_bit as usize + self.effective_bits() as usize).div_ceil(8);
+        if field_bytes > type_bytes {
+            field_bytes
+        } else {
+            type_bytes
+        }
+    }
+
+    /// Minimum data length needed to decode this SPN (`start_byte + byte_width`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use voltage_j1939::database::get_spn_def;
+    ///
+    /// // Engine speed: 2 bytes starting at byte 3
+    /// assert_eq!(get_spn_def(190).unwrap().required_len(), 5);
+    /// ```
+    #[inline(always)]
+    pub const fn required_len(&self) -> usize {
+        self.start_byte as usize + self.byte_width()
+    }
+
+    /// Check if this SPN is a discrete state (1 or 2 bits, e.g. a switch).
+    #[inline(always)]
+    pub const fn is_discrete(&self) -> bool {
+        self.effective_bits() <= 2
+    }
+
+    /// Check if this SPN is a temperature (unit "C").
+    #[inline(always)]
+    pub const fn is_temperature(&self) -> bool {
+        matches!(self.unit.as_bytes(), b"C")
+    }
+
+    /// Number of decimals needed to show a value at this SPN's resolution.
+    ///
+    /// The smallest `d` for which `scale * 10^d` is an integer, capped at
+    /// 9 for scales that are not exact decimals (e.g. 1/3). Scale 0.125
+    /// gives 3, scale 1.0 gives 0.
+    #[inline]
+    pub fn decimals(&self) -> usize {
+        scale_decimals(self.scale)
+    }
+
+    /// Largest raw value that is not reserved as an error / "not available" code.
+    ///
+    /// J1939 reserves the top two raw codes: (2^N - 2) = error, (2^N - 1) = not available.
+    /// With [`reports_all_states`](Self::reports_all_states) every code is valid.
+    #[inline]
+    pub const fn max_valid_raw(&self) -> u64 {
+        if self.reports_all_states {
+            return crate::decoder::bit_mask(self.effective_bits());
+        }
+        match self.effective_bits() {
+            0 | 1 => 0,
+            bits => (1u64 << bits) - 3,
+        }
+    }
+
+    /// Check if this SPN is present in `data`.
+    ///
+    /// Non-multiplexed SPNs are always present. A multiplexed SPN is present
+    /// only when its selector byte equals `mux_value`.
+    #[inline]
+    pub fn is_present(&self, data: &[u8]) -> bool {
+        match (self.mux_byte, self.mux_value) {
+            (Some(byte), Some(value)) => data.get(byte as usize) == Some(&value),
+            _ => true,
+        }
+    }
+
+    /// Range of valid raw values as `(min, max)`.
+    ///
+    /// Unsigned types span `0..=max_valid_raw()`. Signed types span the two's
+    /// complement range of their bit width, except for -1 and -2: those are the
+    /// all-ones "not available" and error bit patterns and are never valid (see
+    /// [`is_reserved_raw`](Self::is_reserved_raw)).
+    #[inline]
+    pub const fn raw_range(&self) -> (i64, i64) {
+        if self.data_type.is_signed() {
+            let half = 1i64 << (self.effective_bits().saturating_sub(1));
+            (-half, half - 1)
+        } else {
+            (0, self.max_valid_raw() as i64)
+        }
+    }
+
+    /// Check if a raw value is one of the special codes inside [`raw_range`](Self::raw_range).
+    ///
+    /// Only signed types have such values: -1 and -2 share their bit patterns
+    /// with "not available" and error, unless the SPN
+    /// [`reports_all_states`](Self::reports_all_states).
+    #[inline]
+    pub const fn is_reserved_raw(&self, raw: i64) -> bool {
+        !self.reports_all_states && self.data_type.is_signed() && (raw == -1 || raw == -2)
+    }
+
+    /// Range of valid values in engineering units as `(min, max)`.
+    ///
+    /// Endpoints are ordered so `min <= max` even for a negative `scale`
+    /// (reversed polarity sensors).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use voltage_j1939::database::get_spn_def;
+    ///
+    /// // Engine coolant temperature: raw 0..=253, scale 1, offset -40
+    /// let (min, max) = get_spn_def(110).unwrap().value_range();
+    /// assert_eq!((min, max), (-40.0, 213.0));
+    /// ```
+    #[inline]
+    pub fn value_range(&self) -> (f64, f64) {
+        let (raw_min, raw_max) = self.raw_range();
+        let a = (raw_min as f64).mul_add(self.scale, self.offset);
+        let b = (raw_max as f64).mul_add(self.scale, self.offset);
+        if a <= b {
+            (a, b)
+        } else {
+            (b, a)
+        }
+    }
+}
+
+/// PGN (Parameter Group Number) metadata.
+///
+/// Describes a parameter group as a whole, independent of the SPNs it carries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PgnInfo {
+    /// Parameter Group Number.
+    pub pgn: u32,
+    /// Standard acronym (e.g. "EEC1").
+    pub acronym: &'static str,
+    /// Human-readable name.
+    pub name: &'static str,
+    /// Nominal broadcast interval in milliseconds (`None` if only sent on request).
+    pub rate_ms: Option<u32>,
+    /// Expected data length in bytes (`None` for variable-length PGNs).
+    pub dlc: Option<u8>,
+    /// Default transmit priority (0-7, lower is higher).
+    pub priority: u8,
 }
 
 /// Decoded SPN value with metadata.
@@ -97,10 +402,585 @@ pub struct DecodedSpn {
     pub raw_value: u64,
     /// SPN number.
     pub spn: u32,
+    /// Instance of the parameter (see [`SpnDef::instance`]).
+    pub instance: Option<u8>,
     /// Parameter name.
     pub name: &'static str,
     /// Engineering unit.
     pub unit: &'static str,
+    /// Scale factor the value was decoded with (resolution of `value`).
+    pub scale: f64,
+}
+
+impl DecodedSpn {
+    /// Value formatted with the number of decimals implied by the SPN's scale.
+    ///
+    /// Removes floating point noise (e.g. `2500.0000000001`) for display.
+    /// See [`SpnDef::decimals`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use voltage_j1939::decoder::decode_spn_full;
+    /// use voltage_j1939::database::get_spn_def;
+    ///
+    /// // Engine speed, scale 0.125
+    /// let spn = decode_spn_full(&[0, 0, 0, 0x21, 0x4E, 0, 0, 0], get_spn_def(190).unwrap()).unwrap();
+    /// assert_eq!(spn.display_value(), "2500.125");
+    /// ```
+    pub fn display_value(&self) -> String {
+        format_decimals(self.value, scale_decimals(self.scale))
+    }
+
+    /// SAE name of the value for enumerated SPNs (e.g. PTO state), `None` otherwise.
+    ///
+    /// See [`spn_state_name`](crate::database::spn_state_name).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use voltage_j1939::decoder::decode_frame;
+    ///
+    /// // CCVS with PTO state 7 in byte 6
+    /// let decoded = decode_frame(0x18FEF100, &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xE7, 0xFF]);
+    /// let pto = decoded.iter().find(|d| d.spn == 976).unwrap();
+    /// assert_eq!(pto.state_name(), Some("Resume"));
+    /// ```
+    pub fn state_name(&self) -> Option<&'static str> {
+        crate::database::spn_state_name(self.spn, self.raw_value)
+    }
+
+    /// Check if the value is pegged at the top of the SPN's valid range.
+    ///
+    /// True when `raw_value` is the largest valid raw code (e.g. 0xFD for a
+    /// 1-byte SPN), which usually means the sensor is at its range limit.
+    /// Always false for discrete (switch / state) SPNs. `spn_def` must be
+    /// the definition this value was decoded with.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use voltage_j1939::decoder::decode_spn_full;
+    /// use voltage_j1939::database::get_spn_def;
+    ///
+    /// let coolant = get_spn_def(110).unwrap();
+    /// assert!(decode_spn_full(&[0xFD], coolant).unwrap().is_saturated(coolant));
+    /// assert!(!decode_spn_full(&[0xFC], coolant).unwrap().is_saturated(coolant));
+    /// ```
+    #[inline]
+    pub fn is_saturated(&self, spn_def: &SpnDef) -> bool {
+        !spn_def.is_discrete() && self.raw_value as i64 == spn_def.raw_range().1
+    }
+
+    /// Compare with another decoded SPN, allowing `value` to differ by up to `epsilon`.
+    ///
+    /// `spn`, `name`, `unit` and `raw_value` must match exactly.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use voltage_j1939::decode_frame;
+    ///
+    /// let a = decode_frame(0x0CF00400, &[0, 0, 0, 0x20, 0x4E, 0, 0, 0]);
+    /// let b = decode_frame(0x0CF00400, &[0, 0, 0, 0x20, 0x4E, 0, 0, 0]);
+    /// assert!(a.iter().zip(&b).all(|(x, y)| x.approx_eq(y, 1e-9)));
+    /// ```
+    #[inline]
+    pub fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.spn == other.spn
+            && self.raw_value == other.raw_value
+            && self.name == other.name
+            && self.unit == other.unit
+            && (self.value - other.value).abs() <= epsilon
+    }
+}
+
+/// Formats as `name = value unit`, e.g. `engine_speed = 2500 RPM`.
+///
+/// The unit is omitted when empty. Use [`DecodedSpn::display_value`] for a
+/// value rounded to the SPN's resolution.
+impl std::fmt::Display for DecodedSpn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} = {}", self.name, self.value)?;
+        if !self.unit.is_empty() {
+            write!(f, " {}", self.unit)?;
+        }
+        Ok(())
+    }
+}
+
+/// Decoded SPN tagged with the timestamp of the frame it came from.
+#[derive(Debug, Clone, Copy)]
+pub struct TimedDecodedSpn {
+    /// Frame timestamp, in the caller's time base (e.g. seconds from a capture).
+    pub timestamp: f64,
+    /// Decoded SPN.
+    pub spn: DecodedSpn,
+}
+
+/// Decoded SPN that keeps a reference to its definition.
+///
+/// 24 bytes, and the full [`SpnDef`] (scale, offset, position, ...) stays
+/// reachable, e.g. for re-encoding, without a second lookup. Convert to a
+/// self-contained [`DecodedSpn`] with `From`.
+#[derive(Debug, Clone, Copy)]
+pub struct DecodedSpnRef {
+    /// Decoded value in engineering units.
+    pub value: f64,
+    /// Raw value before scaling.
+    pub raw_value: u64,
+    /// Definition the value was decoded with.
+    pub spn_def: &'static SpnDef,
+}
+
+impl DecodedSpnRef {
+    /// SPN number.
+    #[inline]
+    pub const fn spn(&self) -> u32 {
+        self.spn_def.spn
+    }
+
+    /// Parameter name.
+    #[inline]
+    pub const fn name(&self) -> &'static str {
+        self.spn_def.name
+    }
+
+    /// Engineering unit.
+    #[inline]
+    pub const fn unit(&self) -> &'static str {
+        self.spn_def.unit
+    }
+}
+
+impl From<DecodedSpnRef> for DecodedSpn {
+    fn from(decoded: DecodedSpnRef) -> Self {
+        let spn_def = decoded.spn_def;
+        DecodedSpn {
+            value: decoded.value,
+            raw_value: decoded.raw_value,
+            spn: spn_def.spn,
+            instance: spn_def.instance,
+            name: spn_def.name,
+            unit: spn_def.unit,
+            scale: spn_def.scale,
+        }
+    }
+}
+
+/// Compact single-precision decoded SPN for storing large numbers of samples.
+///
+/// 8 bytes instead of the 64 of [`DecodedSpn`]; name, unit and scale can be
+/// looked up again from the SPN number. `f32` keeps about 7 significant
+/// digits, plenty for physical quantities such as engine speed or
+/// temperatures, but not for large counters (total hours, distance, fuel)
+/// at full resolution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecodedSpnF32 {
+    /// Decoded value in engineering units, rounded to single precision.
+    pub value: f32,
+    /// SPN number.
+    pub spn: u32,
+}
+
+impl From<DecodedSpn> for DecodedSpnF32 {
+    fn from(decoded: DecodedSpn) -> Self {
+        Self {
+            value: decoded.value as f32,
+            spn: decoded.spn,
+        }
+    }
+}
+
+/// Decoded SPN from [`decode_frame_partial`](crate::decoder::decode_frame_partial),
+/// flagged when the frame was too short to hold all of its bytes.
+#[derive(Debug, Clone, Copy)]
+pub struct PartialDecodedSpn {
+    /// Decoded SPN.
+    pub spn: DecodedSpn,
+    /// `true` if missing high bytes were read as zero.
+    pub partial: bool,
+}
+
+/// Plain-data decoded SPN for passing across a C FFI boundary.
+///
+/// No references: the unit is an index into
+/// [`UNITS`](crate::database::UNITS), resolved with
+/// [`unit_str`](crate::database::unit_str). The trailing `_reserved`
+/// field makes the 8-byte alignment padding explicit, so the layout is 24
+/// bytes with no implicit padding.
+///
+/// ```c
+/// typedef struct {
+///     double value;
+///     uint64_t raw_value;
+///     uint32_t spn;
+///     uint16_t unit_id;
+///     uint16_t reserved;
+/// } DecodedSpnC;
+/// ```
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DecodedSpnC {
+    /// Decoded value in engineering units.
+    pub value: f64,
+    /// Raw value before scaling.
+    pub raw_value: u64,
+    /// SPN number.
+    pub spn: u32,
+    /// Index of the unit in [`UNITS`](crate::database::UNITS).
+    pub unit_id: u16,
+    /// Always zero.
+    pub _reserved: u16,
+}
+
+/// Decoded SPN tagged with the PGN and source address it was received from.
+///
+/// The same quantity (e.g. vehicle speed) may be broadcast by several ECUs
+/// or in several PGNs; the tag lets a consumer prefer one source.
+#[derive(Debug, Clone, Copy)]
+pub struct SourcedDecodedSpn {
+    /// PGN of the frame the SPN was decoded from.
+    pub pgn: u32,
+    /// Source address of the transmitting ECU.
+    pub source_address: u8,
+    /// Decoded SPN.
+    pub spn: DecodedSpn,
+}
+
+/// Contents of a Request2 (PGN 0xC900) message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Request2 {
+    /// The PGN being requested.
+    pub requested_pgn: u32,
+    /// PGN the responder should use to transfer the data, if transfer mode is requested.
+    ///
+    /// `None` means the responder answers with the requested PGN itself.
+    pub transfer_pgn: Option<u32>,
+}
+
+/// Proprietary PGN ranges defined by SAE J1939-21.
+///
+/// Manufacturers use these PGNs for their own messages, so their contents
+/// are not described by the standard SPN database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum ProprietaryPgn {
+    /// Proprietary A (PGN 0xEF00, PDU1 / peer-to-peer, data page 0).
+    ProprietaryA = 0,
+    /// Proprietary A2 (PGN 0x1EF00, PDU1 / peer-to-peer, data page 1).
+    ProprietaryA2 = 1,
+    /// Proprietary B (PGN 0xFF00-0xFFFF, PDU2 / broadcast, data page 0).
+    ProprietaryB = 2,
+}
+
+/// Validity status of a single SPN in a received frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum SpnStatus {
+    /// Value is within the valid range.
+    Valid = 0,
+    /// Transmitter reported an error (raw code 2^N - 2).
+    Error = 1,
+    /// Parameter is not available (raw code 2^N - 1).
+    NotAvailable = 2,
+    /// Frame is too short to contain the SPN.
+    Truncated = 3,
+}
+
+/// State of a J1939 2-bit discrete parameter (switch, enable, status).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum SwitchState {
+    /// Disabled / off / not active (`00`).
+    Off = 0,
+    /// Enabled / on / active (`01`).
+    On = 1,
+    /// Error indicator (`10`).
+    Error = 2,
+    /// Not available or not installed (`11`).
+    NotAvailable = 3,
+}
+
+impl SwitchState {
+    /// State encoded by the two lowest bits of `bits`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use voltage_j1939::types::SwitchState;
+    ///
+    /// assert_eq!(SwitchState::from_bits(0b01), SwitchState::On);
+    /// assert_eq!(SwitchState::from_bits(0b11), SwitchState::NotAvailable);
+    /// ```
+    #[inline]
+    pub const fn from_bits(bits: u8) -> Self {
+        match bits & 0b11 {
+            0 => SwitchState::Off,
+            1 => SwitchState::On,
+            2 => SwitchState::Error,
+            _ => SwitchState::NotAvailable,
+        }
+    }
+}
+
+/// SPN decode result that is reported even when the value is not valid.
+///
+/// Unlike [`DecodedSpn`], this is produced for every SPN of a known PGN, so
+/// "not available" and truncated fields stay visible.
+#[derive(Debug, Clone, Copy)]
+pub struct DecodedSpnStatus {
+    /// Decoded value in engineering units (`Some` only when `status` is `Valid`).
+    pub value: Option<f64>,
+    /// Raw value before scaling (`None` when `status` is `Truncated`).
+    pub raw_value: Option<u64>,
+    /// Definition of the SPN.
+    pub spn_def: &'static SpnDef,
+    /// Validity status.
+    pub status: SpnStatus,
+}
+
+/// Maximum decimals inferred from a scale factor.
+const MAX_SCALE_DECIMALS: usize = 9;
+
+/// Decimals needed to represent multiples of `scale` exactly.
+fn scale_decimals(scale: f64) -> usize {
+    let scale = scale.abs();
+    if !scale.is_finite() || scale == 0.0 {
+        return 0;
+    }
+    let mut scaled = scale;
+    for decimals in 0..MAX_SCALE_DECIMALS {
+        if (scaled - scaled.round()).abs() < 1e-9 * scaled.max(1.0) {
+            return decimals;
+        }
+        scaled *= 10.0;
+    }
+    MAX_SCALE_DECIMALS
+}
+
+/// Format `value` with `decimals` digits, avoiding a "-0" result.
+fn format_decimals(value: f64, decimals: usize) -> String {
+    let factor = 10f64.powi(decimals as i32);
+    let rounded = (value * factor).round() / factor;
+    // Adding 0.0 turns -0.0 into 0.0
+    format!("{:.*}", decimals, rounded + 0.0)
+}
+
+/// Error returned by the strict decoding functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// Frame length does not match the PGN's expected DLC.
+    LengthMismatch {
+        /// Parameter Group Number.
+        pgn: u32,
+        /// Expected data length in bytes.
+        expected: u8,
+        /// Actual data length in bytes.
+        actual: usize,
+    },
+    /// Frame is too short to contain an SPN.
+    SpnTruncated {
+        /// SPN number.
+        spn: u32,
+        /// Minimum data length the SPN needs.
+        required: usize,
+        /// Actual data length in bytes.
+        actual: usize,
+    },
+    /// SPN definition can't be decoded (e.g. zero bit length).
+    InvalidDefinition {
+        /// SPN number.
+        spn: u32,
+        /// What is wrong with the definition.
+        reason: &'static str,
+    },
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LengthMismatch {
+                pgn,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "PGN {} expects {} data bytes, got {}",
+                pgn, expected, actual
+            ),
+            Self::SpnTruncated {
+                spn,
+                required,
+                actual,
+            } => write!(
+                f,
+                "SPN {} needs {} data bytes, got {}",
+                spn, required, actual
+            ),
+            Self::InvalidDefinition { spn, reason } => write!(f, "SPN {}: {}", spn, reason),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Error returned when parsing a frame or J1939 ID from text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseError {
+    /// CAN ID is empty or not a hexadecimal number.
+    InvalidId,
+    /// CAN ID does not fit in 29 bits.
+    IdOutOfRange(u32),
+    /// Data contains a character that is not a hex digit.
+    InvalidHexDigit(char),
+    /// Data has an odd number of hex digits.
+    OddLength(usize),
+    /// A required field of a textual J1939 ID is missing.
+    MissingField(&'static str),
+    /// A field of a textual J1939 ID is unknown, repeated or not a number.
+    InvalidField(&'static str),
+    /// A field of a textual J1939 ID is outside its valid range.
+    FieldOutOfRange(&'static str, u32),
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidId => write!(f, "CAN ID is not a hexadecimal number"),
+            Self::IdOutOfRange(id) => write!(f, "CAN ID 0x{:X} exceeds 29 bits", id),
+            Self::InvalidHexDigit(c) => write!(f, "invalid hex digit {:?} in data", c),
+            Self::OddLength(len) => write!(f, "data has an odd number of hex digits ({})", len),
+            Self::MissingField(field) => write!(f, "missing {} in J1939 ID", field),
+            Self::InvalidField(field) => write!(f, "invalid {} in J1939 ID", field),
+            Self::FieldOutOfRange(field, value) => {
+                write!(f, "{} {} out of range in J1939 ID", field, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Outcome of decoding a whole frame.
+///
+/// Separates the reasons a frame can produce no values, which a plain
+/// `Vec<DecodedSpn>` cannot.
+#[derive(Debug, Clone)]
+pub enum FrameDecode {
+    /// PGN is not in the database.
+    UnknownPgn(u32),
+    /// Transport protocol frame (TP.CM, TP.DT, ETP.CM, ETP.DT).
+    ///
+    /// Carries a segment of a larger message; route it to a reassembler
+    /// such as [`TpReassembler`](crate::transport::TpReassembler).
+    Transport(u32),
+    /// PGN is known but the frame is shorter than its SPN layout requires.
+    Truncated {
+        /// Parameter Group Number.
+        pgn: u32,
+        /// Minimum data length covering every SPN of the PGN.
+        expected_len: usize,
+    },
+    /// PGN is known and the frame is long enough.
+    ///
+    /// May still be empty if every SPN is "not available" or in error.
+    Decoded(Vec<DecodedSpn>),
+}
+
+/// Parameter Group Number.
+///
+/// Keeps PGNs apart from full CAN IDs at the type level: [`Pgn::new`] and
+/// `TryFrom<u32>` reject values wider than 18 bits. [`Pgn::spns`],
+/// [`Pgn::info`] and [`Pgn::expected_dlc`] look a `Pgn` up in the database,
+/// so a CAN ID can't be passed where a PGN is expected.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::Pgn;
+///
+/// let pgn = Pgn::from_can_id(0x0CF00400);
+/// assert_eq!(pgn, Pgn::new(61444).unwrap());
+/// assert_eq!(pgn.to_string(), "61444 (0xF004)");
+/// assert_eq!(pgn.info().unwrap().acronym, "EEC1");
+///
+/// // A full CAN ID is not a PGN
+/// assert_eq!(Pgn::new(0x0CF00400), None);
+/// assert!(Pgn::try_from(0x0CF00400).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Pgn(u32);
+
+impl Pgn {
+    /// Largest PGN (18 bits: EDP, DP, PF, PS).
+    pub const MAX: u32 = 0x3FFFF;
+
+    /// Create a PGN, or `None` if `value` does not fit in 18 bits.
+    #[inline]
+    pub const fn new(value: u32) -> Option<Self> {
+        if value <= Self::MAX {
+            Some(Self(value))
+        } else {
+            None
+        }
+    }
+
+    /// Extract the PGN from a 29-bit CAN ID (see [`extract_pgn`]).
+    #[inline]
+    pub const fn from_can_id(can_id: u32) -> Self {
+        Self(extract_pgn(can_id))
+    }
+
+    /// PGN as a plain number.
+    #[inline]
+    pub const fn value(self) -> u32 {
+        self.0
+    }
+
+    /// SPNs carried by this PGN (see [`get_spns_for_pgn`](crate::database::get_spns_for_pgn)).
+    #[inline]
+    pub fn spns(self) -> Option<&'static [&'static SpnDef]> {
+        crate::database::get_spns_for_pgn(self.0)
+    }
+
+    /// Metadata for this PGN (see [`get_pgn_info`](crate::database::get_pgn_info)).
+    #[inline]
+    pub fn info(self) -> Option<&'static PgnInfo> {
+        crate::database::get_pgn_info(self.0)
+    }
+
+    /// Expected data length of this PGN (see [`expected_dlc`](crate::database::expected_dlc)).
+    #[inline]
+    pub fn expected_dlc(self) -> Option<u8> {
+        crate::database::expected_dlc(self.0)
+    }
+}
+
+/// Fails for values wider than 18 bits, such as a full CAN ID.
+impl TryFrom<u32> for Pgn {
+    type Error = ParseError;
+
+    #[inline]
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        Self::new(value).ok_or(ParseError::FieldOutOfRange("PGN", value))
+    }
+}
+
+impl From<Pgn> for u32 {
+    #[inline]
+    fn from(pgn: Pgn) -> Self {
+        pgn.0
+    }
+}
+
+/// Formats as decimal followed by hex, e.g. `61444 (0xF004)`.
+impl std::fmt::Display for Pgn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (0x{:04X})", self.0, self.0)
+    }
 }
 
 /// PDU2 format threshold (PF >= 240 means broadcast)
@@ -110,7 +990,7 @@ const PDU2_THRESHOLD: u32 = 240;
 ///
 /// A 29-bit extended CAN ID broken down into J1939 fields.
 /// Fields ordered to minimize padding (8 bytes total).
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(C)]
 pub struct J1939Id {
     /// Parameter Group Number.
@@ -137,26 +1017,179 @@ impl J1939Id {
         !self.is_broadcast()
     }
 
-    /// Build a 29-bit CAN ID from J1939 components.
+    /// Check if this message is for every node: a broadcast (PDU2) message,
+    /// or a PDU1 message sent to [`GLOBAL_ADDRESS`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use voltage_j1939::parse_can_id;
+    ///
+    /// assert!(parse_can_id(0x18EAFF21).is_broadcast_destination()); // Request to global
+    /// assert!(!parse_can_id(0x18EA0021).is_broadcast_destination()); // Request to 0x00
+    /// ```
     #[inline]
-    pub const fn to_can_id(&self) -> u32 {
-        let dp = (self.pgn >> 16) & 0x01;
-        let pf = (self.pgn >> 8) & 0xFF;
-        let ps = if pf >= PDU2_THRESHOLD {
-            self.pgn & 0xFF
+    pub const fn is_broadcast_destination(&self) -> bool {
+        self.is_broadcast() || self.destination_address == GLOBAL_ADDRESS
+    }
+
+    /// Check if a node with source address `my_sa` should process this message.
+    ///
+    /// True for broadcast (PDU2) messages, and for PDU1 messages sent to
+    /// `my_sa` or to [`GLOBAL_ADDRESS`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use voltage_j1939::parse_can_id;
+    ///
+    /// // Request PGN from 0x21 to 0x00
+    /// let id = parse_can_id(0x18EA0021);
+    /// assert!(id.is_addressed_to(0x00));
+    /// assert!(!id.is_addressed_to(0x17));
+    /// ```
+    #[inline]
+    pub const fn is_addressed_to(&self, my_sa: u8) -> bool {
+        self.is_broadcast_destination() || self.destination_address == my_sa
+    }
+
+    /// Data Page (DP) bit of the PGN (0 or 1).
+    #[inline]
+    pub const fn data_page(&self) -> u8 {
+        ((self.pgn >> 16) & 0x01) as u8
+    }
+
+    /// PDU Format (PF) byte of the PGN.
+    #[inline]
+    pub const fn pdu_format(&self) -> u8 {
+        (self.pgn >> 8) as u8
+    }
+
+    /// PDU Specific (PS) byte as transmitted in the CAN ID.
+    ///
+    /// For PDU2 (broadcast) this is the group extension from the PGN, for
+    /// PDU1 (peer-to-peer) it is the destination address.
+    #[inline]
+    pub const fn pdu_specific(&self) -> u8 {
+        if self.is_broadcast() {
+            self.pgn as u8
         } else {
-            self.destination_address as u32
-        };
+            self.destination_address
+        }
+    }
 
+    /// Return a copy with the given priority, clamped to the valid 0-7 range.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use voltage_j1939::frame::parse_can_id;
+    ///
+    /// let id = parse_can_id(0x18FEEE00).with_priority(3);
+    /// assert_eq!(id.to_can_id(), 0x0CFEEE00);
+    /// assert_eq!(id.with_priority(9).priority, 7);
+    /// ```
+    #[inline]
+    pub const fn with_priority(self, priority: u8) -> Self {
+        Self {
+            priority: if priority > 7 { 7 } else { priority },
+            ..self
+        }
+    }
+
+    /// Build a 29-bit CAN ID from J1939 components.
+    #[inline]
+    pub const fn to_can_id(&self) -> u32 {
         ((self.priority as u32) << 26)
-            | (dp << 24)
-            | (pf << 16)
-            | (ps << 8)
+            | ((self.data_page() as u32) << 24)
+            | ((self.pdu_format() as u32) << 16)
+            | ((self.pdu_specific() as u32) << 8)
             | (self.source_address as u32)
     }
 }
 
+/// Formats as `PGN 61444 pri 3 SA 0x00`.
+///
+/// Peer-to-peer (PDU1) IDs also show the destination, e.g.
+/// `PGN 59904 pri 6 DA 0x00 SA 0x21`.
+impl std::fmt::Display for J1939Id {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PGN {} pri {}", self.pgn, self.priority)?;
+        if self.is_peer_to_peer() {
+            write!(f, " DA 0x{:02X}", self.destination_address)?;
+        }
+        write!(f, " SA 0x{:02X}", self.source_address)
+    }
+}
+
+/// Parses the [`Display`](std::fmt::Display) format back, e.g.
+/// `"PGN 59904 pri 6 DA 0x00 SA 0x21"`.
+///
+/// Fields are `PGN`, `pri`, `SA` and the optional `DA` (global address if
+/// omitted), in any order, with case-insensitive names. Values are decimal
+/// or `0x`-prefixed hex. Priority must be 0-7 and the PGN fit in 18 bits.
+///
+/// # Example
+///
+/// ```
+/// use voltage_j1939::types::J1939Id;
+///
+/// let id: J1939Id = "PGN 65262 pri 6 SA 0x00".parse().unwrap();
+/// assert_eq!(id.to_can_id(), 0x18FEEE00);
+/// assert_eq!(id.to_string().parse::<J1939Id>(), Ok(id));
+///
+/// assert!("PGN 65262 pri 9 SA 0x00".parse::<J1939Id>().is_err());
+/// ```
+impl std::str::FromStr for J1939Id {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut pgn = None;
+        let mut priority = None;
+        let mut source_address = None;
+        let mut destination_address = None;
+
+        let mut tokens = s.split_whitespace();
+        while let Some(key) = tokens.next() {
+            let (name, slot, max) = match key.to_ascii_lowercase().as_str() {
+                "pgn" => ("PGN", &mut pgn, Pgn::MAX),
+                "pri" => ("priority", &mut priority, 7),
+                "sa" => ("source address", &mut source_address, 0xFF),
+                "da" => ("destination address", &mut destination_address, 0xFF),
+                _ => return Err(ParseError::InvalidField("field name")),
+            };
+            let value = tokens
+                .next()
+                .and_then(parse_id_number)
+                .ok_or(ParseError::InvalidField(name))?;
+            if value > max {
+                return Err(ParseError::FieldOutOfRange(name, value));
+            }
+            if slot.replace(value).is_some() {
+                return Err(ParseError::InvalidField(name));
+            }
+        }
+
+        Ok(Self {
+            pgn: pgn.ok_or(ParseError::MissingField("PGN"))?,
+            priority: priority.ok_or(ParseError::MissingField("priority"))? as u8,
+            source_address: source_address.ok_or(ParseError::MissingField("source address"))? as u8,
+            destination_address: destination_address.map_or(GLOBAL_ADDRESS, |da| da as u8),
+        })
+    }
+}
+
+/// Parse a decimal or `0x`-prefixed hex number.
+fn parse_id_number(s: &str) -> Option<u32> {
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+    .filter(|_| !s.contains('+'))
+}
+
 #[cfg(test)]
+#[allow(clippy::items_after_test_module)]
 mod tests {
     use super::*;
 
@@ -190,6 +1223,40 @@ mod tests {
         assert_eq!(std::mem::size_of::<SpnDataType>(), 1);
     }
 
+    // ========================================================================
+    // Pgn tests
+    // ========================================================================
+
+    #[test]
+    fn test_pgn_new_validates_range() {
+        assert_eq!(Pgn::new(0).map(Pgn::value), Some(0));
+        assert_eq!(Pgn::new(Pgn::MAX).map(Pgn::value), Some(0x3FFFF));
+        assert_eq!(Pgn::new(Pgn::MAX + 1), None);
+        assert_eq!(Pgn::new(0x0CF00400), None);
+
+        assert_eq!(Pgn::try_from(61444).map(Pgn::value), Ok(61444));
+        assert_eq!(
+            Pgn::try_from(0x0CF00400),
+            Err(ParseError::FieldOutOfRange("PGN", 0x0CF00400))
+        );
+    }
+
+    #[test]
+    fn test_pgn_from_can_id() {
+        assert_eq!(Pgn::from_can_id(0x0CF00400).value(), 61444);
+        // PDU1: destination address is not part of the PGN
+        assert_eq!(Pgn::from_can_id(0x18EA0021).value(), 59904);
+        assert_eq!(u32::from(Pgn::from_can_id(0x18FEEE00)), 65262);
+    }
+
+    #[test]
+    fn test_pgn_display() {
+        let display = |value| Pgn::new(value).unwrap().to_string();
+        assert_eq!(display(61444), "61444 (0xF004)");
+        assert_eq!(display(0x1EF00), "126720 (0x1EF00)");
+        assert_eq!(display(0), "0 (0x0000)");
+    }
+
     // ========================================================================
     // J1939Id tests
     // ========================================================================
@@ -279,6 +1346,84 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_j1939_id_accessors_pdu2() {
+        let id = J1939Id {
+            priority: 6,
+            pgn: 65262, // ET1: DP=0, PF=0xFE, PS=0xEE
+            source_address: 0x00,
+            destination_address: 0xFF,
+        };
+        assert_eq!(id.data_page(), 0);
+        assert_eq!(id.pdu_format(), 0xFE);
+        assert_eq!(id.pdu_specific(), 0xEE);
+    }
+
+    #[test]
+    fn test_j1939_id_accessors_pdu1() {
+        let id = J1939Id {
+            priority: 6,
+            pgn: 0x1EA00, // DP=1, PF=0xEA
+            source_address: 0xFE,
+            destination_address: 0x21,
+        };
+        assert_eq!(id.data_page(), 1);
+        assert_eq!(id.pdu_format(), 0xEA);
+        assert_eq!(id.pdu_specific(), 0x21); // Destination address
+    }
+
+    #[test]
+    fn test_j1939_id_with_priority() {
+        let id = J1939Id {
+            priority: 6,
+            pgn: 61444,
+            source_address: 0x00,
+            destination_address: 0xFF,
+        };
+        assert_eq!(id.with_priority(3).priority, 3);
+        assert_eq!(id.with_priority(0).priority, 0);
+        assert_eq!(id.with_priority(8).priority, 7);
+        assert_eq!(id.with_priority(u8::MAX).priority, 7);
+        // Other fields untouched
+        assert_eq!(id.with_priority(3).pgn, 61444);
+        assert_eq!(id.with_priority(3).to_can_id(), 0x0CF00400);
+    }
+
+    #[test]
+    fn test_j1939_id_is_addressed_to() {
+        let mut id = J1939Id {
+            priority: 6,
+            pgn: 0xEA00,
+            source_address: 0x21,
+            destination_address: 0x00,
+        };
+        assert!(id.is_addressed_to(0x00));
+        assert!(!id.is_addressed_to(0x17));
+
+        // Global destination
+        id.destination_address = 0xFF;
+        assert!(id.is_addressed_to(0x17));
+
+        // PDU2 is always for everyone, whatever the PS field holds
+        let id = J1939Id {
+            priority: 3,
+            pgn: 61444,
+            source_address: 0x00,
+            destination_address: 0xFF,
+        };
+        assert!(id.is_addressed_to(0x17));
+        assert!(crate::frame::parse_can_id(0x18FEF117).is_addressed_to(0x42));
+    }
+
+    #[test]
+    fn test_j1939_id_is_broadcast_destination() {
+        let mut id = crate::frame::parse_can_id(0x18EA0021);
+        assert!(!id.is_broadcast_destination());
+        id.destination_address = GLOBAL_ADDRESS;
+        assert!(id.is_broadcast_destination());
+        assert!(crate::frame::parse_can_id(0x0CF00400).is_broadcast_destination());
+    }
+
     #[test]
     fn test_j1939_id_copy() {
         let id1 = J1939Id {
@@ -292,32 +1437,267 @@ mod tests {
         assert_eq!(id1.priority, id2.priority);
     }
 
+    #[test]
+    fn test_j1939_id_display() {
+        let eec1 = J1939Id {
+            pgn: 61444,
+            priority: 3,
+            source_address: 0x00,
+            destination_address: 0xFF,
+        };
+        assert_eq!(eec1.to_string(), "PGN 61444 pri 3 SA 0x00");
+
+        let request = J1939Id {
+            pgn: 59904,
+            priority: 6,
+            source_address: 0x21,
+            destination_address: 0x00,
+        };
+        assert_eq!(request.to_string(), "PGN 59904 pri 6 DA 0x00 SA 0x21");
+    }
+
+    #[test]
+    fn test_j1939_id_from_str_roundtrip() {
+        for can_id in [0x0CF00400, 0x18FEEE00, 0x18EA0021, 0x1CECFF3D, 0x00C90000] {
+            let id = crate::frame::parse_can_id(can_id);
+            assert_eq!(
+                id.to_string().parse::<J1939Id>(),
+                Ok(id),
+                "0x{:08X}",
+                can_id
+            );
+        }
+
+        // Any order, case-insensitive names, hex or decimal values
+        let id: J1939Id = "sa 33 da 0 PGN 0xEA00 PRI 0x6".parse().unwrap();
+        assert_eq!(id.to_can_id(), 0x18EA0021);
+    }
+
+    #[test]
+    fn test_j1939_id_from_str_errors() {
+        let parse = |s: &str| s.parse::<J1939Id>();
+        assert_eq!(parse(""), Err(ParseError::MissingField("PGN")));
+        assert_eq!(
+            parse("PGN 1 SA 0"),
+            Err(ParseError::MissingField("priority"))
+        );
+        assert_eq!(
+            parse("PGN 1 pri 3"),
+            Err(ParseError::MissingField("source address"))
+        );
+        assert_eq!(
+            parse("PGN 0x40000 pri 3 SA 0"),
+            Err(ParseError::FieldOutOfRange("PGN", 0x40000))
+        );
+        assert_eq!(
+            parse("PGN 1 pri 8 SA 0"),
+            Err(ParseError::FieldOutOfRange("priority", 8))
+        );
+        assert_eq!(
+            parse("PGN 1 pri 3 SA 256"),
+            Err(ParseError::FieldOutOfRange("source address", 256))
+        );
+        assert_eq!(
+            parse("PGN 1 pri 3 SA"),
+            Err(ParseError::InvalidField("source address"))
+        );
+        assert_eq!(
+            parse("PGN x pri 3 SA 0"),
+            Err(ParseError::InvalidField("PGN"))
+        );
+        assert_eq!(
+            parse("PGN +1 pri 3 SA 0"),
+            Err(ParseError::InvalidField("PGN"))
+        );
+        assert_eq!(
+            parse("PGN 1 PGN 2 pri 3 SA 0"),
+            Err(ParseError::InvalidField("PGN"))
+        );
+        assert_eq!(
+            parse("PGN 1 pri 3 SA 0 foo 1"),
+            Err(ParseError::InvalidField("field name"))
+        );
+        assert_eq!(
+            ParseError::FieldOutOfRange("priority", 8).to_string(),
+            "priority 8 out of range in J1939 ID"
+        );
+    }
+
     // ========================================================================
     // DecodedSpn tests
     // ========================================================================
 
+    #[test]
+    fn test_decoded_spn_is_saturated() {
+        let spn_def = test_spn_def(SpnDataType::Uint16, 16, 0.125, 0.0);
+        let decoded = |raw_value: u64| DecodedSpn {
+            value: raw_value as f64 * 0.125,
+            raw_value,
+            spn: spn_def.spn,
+            instance: None,
+            name: spn_def.name,
+            unit: spn_def.unit,
+            scale: spn_def.scale,
+        };
+        assert!(!decoded(0xFAFF).is_saturated(&spn_def));
+        assert!(decoded(0xFFFD).is_saturated(&spn_def));
+
+        // Signed: top of the two's complement range
+        let signed = test_spn_def(SpnDataType::Int8, 8, 1.0, 0.0);
+        let value = DecodedSpn {
+            raw_value: 127,
+            ..decoded(0)
+        };
+        assert!(value.is_saturated(&signed));
+        let negative = DecodedSpn {
+            raw_value: -128i64 as u64,
+            ..decoded(0)
+        };
+        assert!(!negative.is_saturated(&signed));
+
+        // Discrete states are never saturated
+        let switch = test_spn_def(SpnDataType::Uint8, 2, 1.0, 0.0);
+        assert!(!DecodedSpn {
+            raw_value: 1,
+            ..decoded(0)
+        }
+        .is_saturated(&switch));
+    }
+
+    #[test]
+    fn test_decoded_spn_display() {
+        let mut spn = DecodedSpn {
+            value: 2500.0,
+            raw_value: 20000,
+            spn: 190,
+            instance: None,
+            name: "engine_speed",
+            unit: "RPM",
+            scale: 0.125,
+        };
+        assert_eq!(spn.to_string(), "engine_speed = 2500 RPM");
+
+        spn.unit = "";
+        assert_eq!(spn.to_string(), "engine_speed = 2500");
+    }
+
     #[test]
     fn test_decoded_spn_copy() {
         let spn1 = DecodedSpn {
             value: 100.0,
             raw_value: 100,
             spn: 190,
+            instance: None,
             name: "test",
             unit: "RPM",
+            scale: 0.125,
         };
         let spn2 = spn1; // Copy
         assert_eq!(spn1.value, spn2.value);
         assert_eq!(spn1.spn, spn2.spn);
     }
 
+    #[test]
+    fn test_decoded_spn_display_value() {
+        let mut spn = DecodedSpn {
+            value: 2500.0000000001,
+            raw_value: 20000,
+            spn: 190,
+            instance: None,
+            name: "engine_speed",
+            unit: "RPM",
+            scale: 0.125,
+        };
+        assert_eq!(spn.display_value(), "2500.000");
+
+        spn.scale = 1.0;
+        spn.value = 89.99999999;
+        assert_eq!(spn.display_value(), "90");
+
+        spn.scale = 0.03125;
+        spn.value = -273.0;
+        assert_eq!(spn.display_value(), "-273.00000");
+
+        spn.scale = 0.001;
+        spn.value = -0.0000001;
+        assert_eq!(spn.display_value(), "0.000");
+    }
+
+    #[test]
+    fn test_decoded_spn_approx_eq() {
+        let a = DecodedSpn {
+            value: 2500.0,
+            raw_value: 20000,
+            spn: 190,
+            instance: None,
+            name: "engine_speed",
+            unit: "RPM",
+            scale: 0.125,
+        };
+        let b = DecodedSpn {
+            value: 2500.0000001,
+            ..a
+        };
+        assert!(a.approx_eq(&b, 1e-6));
+        assert!(!a.approx_eq(&b, 1e-9));
+        assert!(!a.approx_eq(
+            &DecodedSpn {
+                raw_value: 20001,
+                ..a
+            },
+            1.0
+        ));
+        assert!(!a.approx_eq(&DecodedSpn { spn: 191, ..a }, 1.0));
+        assert!(!a.approx_eq(&DecodedSpn { unit: "rpm", ..a }, 1.0));
+        assert!(!a.approx_eq(
+            &DecodedSpn {
+                value: f64::NAN,
+                ..a
+            },
+            1.0
+        ));
+    }
+
+    #[test]
+    fn test_scale_decimals() {
+        assert_eq!(scale_decimals(1.0), 0);
+        assert_eq!(scale_decimals(10.0), 0);
+        assert_eq!(scale_decimals(0.5), 1);
+        assert_eq!(scale_decimals(0.4), 1);
+        assert_eq!(scale_decimals(0.05), 2);
+        assert_eq!(scale_decimals(0.125), 3);
+        assert_eq!(scale_decimals(0.0025), 4);
+        assert_eq!(scale_decimals(0.03125), 5);
+        assert_eq!(scale_decimals(-0.125), 3);
+        assert_eq!(scale_decimals(1.0 / 3.0), MAX_SCALE_DECIMALS);
+        assert_eq!(scale_decimals(0.0), 0);
+    }
+
     #[test]
     fn test_decoded_spn_size() {
         // DecodedSpn should be reasonably sized
-        // f64(8) + u64(8) + u32(4) + 2*&str(16 each on 64-bit) = 52 bytes
-        // With padding it should be around 56 bytes
+        // 2*f64(8) + u64(8) + u32(4) + 2*&str(16 each on 64-bit) = 60 bytes
+        // With padding it should be 64 bytes
         assert!(std::mem::size_of::<DecodedSpn>() <= 64);
     }
 
+    #[test]
+    fn test_decoded_spn_f32_from() {
+        let decoded = DecodedSpn {
+            value: 2500.125,
+            raw_value: 20001,
+            spn: 190,
+            instance: None,
+            name: "engine_speed",
+            unit: "RPM",
+            scale: 0.125,
+        };
+        let compact = DecodedSpnF32::from(decoded);
+        assert_eq!(compact.value, 2500.125);
+        assert_eq!(compact.spn, 190);
+        assert_eq!(std::mem::size_of::<DecodedSpnF32>(), 8);
+    }
+
     // ========================================================================
     // SpnDef tests
     // ========================================================================
@@ -335,11 +1715,169 @@ mod tests {
             start_bit: 0,
             bit_length: 8,
             data_type: SpnDataType::Uint8,
+            ..SpnDef::DEFAULT
         };
         let def2 = def1; // Copy
         assert_eq!(def1.spn, def2.spn);
         assert_eq!(def1.scale, def2.scale);
     }
+
+    #[test]
+    fn test_spn_def_byte_width() {
+        // Sub-byte fields within one byte
+        let def = test_spn_def(SpnDataType::Uint8, 2, 1.0, 0.0);
+        assert_eq!(def.byte_width(), 1);
+        let def = SpnDef {
+            start_bit: 6,
+            ..def
+        };
+        assert_eq!(def.byte_width(), 1);
+
+        // Bit field crossing into the next byte
+        let def = SpnDef {
+            start_bit: 6,
+            bit_length: 4,
+            ..def
+        };
+        assert_eq!(def.byte_width(), 2);
+
+        // Multi-byte types
+        assert_eq!(
+            test_spn_def(SpnDataType::Uint16, 16, 1.0, 0.0).byte_width(),
+            2
+        );
+        assert_eq!(
+            test_spn_def(SpnDataType::Uint32, 32, 1.0, 0.0).byte_width(),
+            4
+        );
+        assert_eq!(
+            test_spn_def(SpnDataType::Int32, 24, 1.0, 0.0).byte_width(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_spn_def_required_len() {
+        let def = SpnDef {
+            start_byte: 3,
+            ..test_spn_def(SpnDataType::Uint16, 16, 1.0, 0.0)
+        };
+        assert_eq!(def.required_len(), 5);
+
+        let def = SpnDef {
+            start_byte: 7,
+            start_bit: 4,
+            ..test_spn_def(SpnDataType::Uint8, 4, 1.0, 0.0)
+        };
+        assert_eq!(def.required_len(), 8);
+    }
+
+    #[test]
+    fn test_spn_def_is_present() {
+        let plain = test_spn_def(SpnDataType::Uint8, 8, 1.0, 0.0);
+        assert!(plain.is_present(&[]));
+        assert!(plain.is_present(&[0x03]));
+
+        let muxed = SpnDef {
+            mux_byte: Some(1),
+            mux_value: Some(3),
+            ..plain
+        };
+        assert!(muxed.is_present(&[0x00, 0x03]));
+        assert!(!muxed.is_present(&[0x00, 0x04]));
+        // Selector byte missing from the frame
+        assert!(!muxed.is_present(&[0x03]));
+    }
+
+    fn test_spn_def(data_type: SpnDataType, bit_length: u8, scale: f64, offset: f64) -> SpnDef {
+        SpnDef {
+            scale,
+            offset,
+            spn: 9000,
+            pgn: 0xFF00,
+            name: "test",
+            unit: "",
+            start_byte: 0,
+            start_bit: 0,
+            bit_length,
+            data_type,
+            ..SpnDef::DEFAULT
+        }
+    }
+
+    #[test]
+    fn test_spn_def_max_valid_raw() {
+        assert_eq!(
+            test_spn_def(SpnDataType::Uint8, 8, 1.0, 0.0).max_valid_raw(),
+            253
+        );
+        assert_eq!(
+            test_spn_def(SpnDataType::Uint8, 2, 1.0, 0.0).max_valid_raw(),
+            1
+        );
+        assert_eq!(
+            test_spn_def(SpnDataType::Uint16, 16, 1.0, 0.0).max_valid_raw(),
+            65533
+        );
+        assert_eq!(
+            test_spn_def(SpnDataType::Uint32, 32, 1.0, 0.0).max_valid_raw(),
+            0xFFFF_FFFD
+        );
+        // bit_length wider than the type is clamped
+        assert_eq!(
+            test_spn_def(SpnDataType::Uint8, 200, 1.0, 0.0).max_valid_raw(),
+            253
+        );
+
+        // Every code is valid for SPNs that report all states
+        let switch = SpnDef {
+            reports_all_states: true,
+            ..test_spn_def(SpnDataType::Uint8, 2, 1.0, 0.0)
+        };
+        assert_eq!(switch.max_valid_raw(), 3);
+        assert_eq!(switch.raw_range(), (0, 3));
+        let signed = SpnDef {
+            reports_all_states: true,
+            ..test_spn_def(SpnDataType::Int8, 8, 1.0, 0.0)
+        };
+        assert!(!signed.is_reserved_raw(-1));
+    }
+
+    #[test]
+    fn test_spn_def_raw_range_signed() {
+        let def = test_spn_def(SpnDataType::Int8, 8, 1.0, 0.0);
+        assert_eq!(def.raw_range(), (-128, 127));
+        let def = test_spn_def(SpnDataType::Int16, 16, 1.0, 0.0);
+        assert_eq!(def.raw_range(), (-32768, 32767));
+
+        // -1 / -2 are the "not available" / error bit patterns
+        assert!(def.is_reserved_raw(-1));
+        assert!(def.is_reserved_raw(-2));
+        assert!(!def.is_reserved_raw(-3));
+        assert!(!def.is_reserved_raw(0));
+        let def = test_spn_def(SpnDataType::Uint16, 16, 1.0, 0.0);
+        assert!(!def.is_reserved_raw(-1));
+    }
+
+    #[test]
+    fn test_spn_def_value_range() {
+        // Engine speed: 0..=65533 * 0.125
+        let def = test_spn_def(SpnDataType::Uint16, 16, 0.125, 0.0);
+        assert_eq!(def.value_range(), (0.0, 8191.625));
+
+        // Percent torque: offset -125
+        let def = test_spn_def(SpnDataType::Uint8, 8, 1.0, -125.0);
+        assert_eq!(def.value_range(), (-125.0, 128.0));
+    }
+
+    #[test]
+    fn test_spn_def_value_range_negative_scale() {
+        // Reversed polarity: higher raw means lower value
+        let def = test_spn_def(SpnDataType::Uint8, 8, -0.5, 100.0);
+        let (min, max) = def.value_range();
+        assert!(min < max);
+        assert_eq!((min, max), (-26.5, 100.0));
+    }
 }
 
 // ============================================================================
@@ -356,7 +1894,17 @@ const _: () = {
     // DecodedSpn should fit in a cache line (64 bytes)
     assert!(std::mem::size_of::<DecodedSpn>() <= 64);
 
+    // DecodedSpnRef replaces name/unit/scale with one reference
+    assert!(std::mem::size_of::<DecodedSpnRef>() <= 24);
+
+    // DecodedSpnC mirrors a C struct of 8 + 8 + 4 + 2 + 2 bytes
+    assert!(std::mem::size_of::<DecodedSpnC>() == 24);
+
+    // DecodedSpnF32 is meant for bulk storage
+    assert!(std::mem::size_of::<DecodedSpnF32>() == 8);
+
     // SpnDef should be reasonably sized (contains 2 static refs + primitives)
-    // On 64-bit: 2*f64(16) + 2*u32(8) + 2*&str(32) + 4*u8(4) = 60 bytes + padding
+    // On 64-bit: 2*f64(16) + 2*u32(8) + 2*&str(32) + 4*u8(4)
+    //   + 3*Option<u8>(6) + bool(1) = 67 bytes + padding = 72
     assert!(std::mem::size_of::<SpnDef>() <= 72);
 };